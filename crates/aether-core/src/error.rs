@@ -93,6 +93,9 @@ pub enum AetherError {
     #[error("already exists: {resource} with id {id}")]
     AlreadyExists { resource: &'static str, id: String },
 
+    #[error("conflict: {0}")]
+    Conflict(String),
+
     // Validation
     #[error("validation failed: {field} — {reason}")]
     ValidationFailed { field: String, reason: String },
@@ -160,6 +163,7 @@ impl AetherError {
             Self::TenantQuotaExceeded { .. } => ErrorCode::TenantQuotaExceeded,
             Self::NotFound { .. } => ErrorCode::NotFound,
             Self::AlreadyExists { .. } => ErrorCode::AlreadyExists,
+            Self::Conflict(_) => ErrorCode::Conflict,
             Self::ValidationFailed { .. } => ErrorCode::ValidationFailed,
             Self::InvalidSchema(_) => ErrorCode::InvalidSchema,
             Self::ToolExecutionFailed { .. } => ErrorCode::ToolExecutionFailed,
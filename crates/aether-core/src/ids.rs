@@ -64,6 +64,18 @@ define_id!(LedgerBlockId, "Unique identifier for a ledger block.");
 define_id!(SessionId, "Unique identifier for a conversation session.");
 define_id!(WorkerId, "Unique identifier for a VM/worker.");
 define_id!(RequestId, "Unique identifier for an API request (tracing).");
+define_id!(
+    SimulationId,
+    "Unique identifier for a simulated ledger branch."
+);
+define_id!(
+    ApprovalId,
+    "Unique identifier for a human approval request."
+);
+define_id!(
+    UserId,
+    "Unique identifier for a human user within a tenant."
+);
 
 #[cfg(test)]
 mod tests {
@@ -3,11 +3,20 @@
 //! Every tool execution, memory write, agent spawn, and deploy is recorded
 //! as an immutable ledger block. Blocks form a hash chain for tamper detection.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::ids::{AgentId, LedgerBlockId, TaskId, TenantId, ToolId};
 
+/// Original hash encoding: `canonical_string` (id, sequence and hashes only).
+pub const HASH_VERSION_LEGACY: u8 = 1;
+
+/// Length-prefixed encoding of every block field except `refs`.
+pub const HASH_VERSION_FIELDS: u8 = 2;
+
+/// `HASH_VERSION_FIELDS` plus the block's typed `refs` (`canonical_bytes`).
+pub const HASH_VERSION_CURRENT: u8 = 3;
+
 /// SHA-256 hash represented as a hex string.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -52,6 +61,56 @@ pub enum LedgerAction {
     Compensation,
 }
 
+impl LedgerAction {
+    /// Stable wire name (matches the serde representation).
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ToolCall => "TOOL_CALL",
+            Self::CodeChange => "CODE_CHANGE",
+            Self::MemoryWrite => "MEMORY_WRITE",
+            Self::MemoryDelete => "MEMORY_DELETE",
+            Self::AgentSpawn => "AGENT_SPAWN",
+            Self::AgentComplete => "AGENT_COMPLETE",
+            Self::Deploy => "DEPLOY",
+            Self::HumanReview => "HUMAN_REVIEW",
+            Self::ToolCreated => "TOOL_CREATED",
+            Self::Compensation => "COMPENSATION",
+        }
+    }
+}
+
+/// How a block relates to the block it references.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockRefKind {
+    /// This `Compensation` block undoes the referenced block.
+    Compensates,
+    /// This block was triggered by the referenced block.
+    CausedBy,
+    /// The referenced `HumanReview` block approved this action.
+    ApprovedBy,
+}
+
+impl BlockRefKind {
+    /// Stable wire name (matches the serde representation).
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Compensates => "compensates",
+            Self::CausedBy => "caused_by",
+            Self::ApprovedBy => "approved_by",
+        }
+    }
+}
+
+/// A typed reference from one block to an earlier block of the same tenant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BlockRef {
+    pub kind: BlockRefKind,
+    pub block_id: LedgerBlockId,
+}
+
 /// An immutable ledger block.
 ///
 /// Each block records one action and chains to its predecessor via `parent_hash`.
@@ -74,14 +133,32 @@ pub struct LedgerBlock {
     pub output_hash: BlockHash,
     /// Hash of the previous block in this tenant's chain.
     pub parent_hash: BlockHash,
-    /// Ed25519 signature of (id + sequence + input_hash + output_hash + parent_hash).
+    /// Hex-encoded Ed25519 signature over the block hash.
     pub signature: Option<String>,
+    /// Identifier of the key that produced `signature`.
+    #[serde(default)]
+    pub signer_key_id: Option<String>,
+    /// Canonical encoding used for hashing. Blocks serialized before
+    /// versioning existed deserialize as `HASH_VERSION_LEGACY`.
+    #[serde(default = "legacy_hash_version")]
+    pub hash_version: u8,
+    /// Typed links to earlier blocks (compensation, causality, approval).
+    /// Only hashed from `HASH_VERSION_CURRENT`; older blocks must leave it empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub refs: Vec<BlockRef>,
+}
+
+fn legacy_hash_version() -> u8 {
+    HASH_VERSION_LEGACY
 }
 
 impl LedgerBlock {
-    /// Build the string that should be signed/hashed for chain verification.
+    /// Legacy (`HASH_VERSION_LEGACY`) string hashed for chain verification.
     ///
     /// Format: `{block_id}|{seq}|{input_hash}|{output_hash}|{parent_hash}`
+    ///
+    /// Does not cover tenant, agent, task, action, tool or timestamp — kept
+    /// only so chains written before `HASH_VERSION_CURRENT` still verify.
     #[must_use]
     pub fn canonical_string(&self) -> String {
         format!(
@@ -93,6 +170,58 @@ impl LedgerBlock {
             self.parent_hash.0,
         )
     }
+
+    /// Length-prefixed canonical encoding (`HASH_VERSION_FIELDS` and later).
+    ///
+    /// Every hashed field is written as a big-endian `u64` length followed by
+    /// its bytes, so no field boundary can be shifted by crafted content.
+    /// `signature` and `signer_key_id` are excluded: they sign this hash.
+    /// From `HASH_VERSION_CURRENT` the ref count and each ref's kind and
+    /// target id follow the block fields.
+    #[must_use]
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let timestamp = self
+            .timestamp_utc
+            .to_rfc3339_opts(SecondsFormat::Nanos, true);
+        let tool = self.tool_id.map(|t| t.to_string()).unwrap_or_default();
+        let mut buf = Vec::with_capacity(512);
+        push_field(&mut buf, &[self.hash_version]);
+        push_field(&mut buf, self.id.to_string().as_bytes());
+        push_field(&mut buf, &self.sequence_number.to_be_bytes());
+        push_field(&mut buf, timestamp.as_bytes());
+        push_field(&mut buf, self.tenant_id.to_string().as_bytes());
+        push_field(&mut buf, self.agent_id.to_string().as_bytes());
+        push_field(&mut buf, self.task_id.to_string().as_bytes());
+        push_field(&mut buf, self.action.as_str().as_bytes());
+        push_field(&mut buf, &[u8::from(self.tool_id.is_some())]);
+        push_field(&mut buf, tool.as_bytes());
+        push_field(&mut buf, self.input_hash.0.as_bytes());
+        push_field(&mut buf, self.output_hash.0.as_bytes());
+        push_field(&mut buf, self.parent_hash.0.as_bytes());
+        if self.hash_version >= HASH_VERSION_CURRENT {
+            push_field(&mut buf, &(self.refs.len() as u64).to_be_bytes());
+            for r in &self.refs {
+                push_field(&mut buf, r.kind.as_str().as_bytes());
+                push_field(&mut buf, r.block_id.to_string().as_bytes());
+            }
+        }
+        buf
+    }
+
+    /// Target of the first reference of `kind`, if any.
+    #[must_use]
+    pub fn ref_of(&self, kind: BlockRefKind) -> Option<LedgerBlockId> {
+        self.refs
+            .iter()
+            .find(|r| r.kind == kind)
+            .map(|r| r.block_id)
+    }
+}
+
+/// Append one length-prefixed field to a canonical encoding buffer.
+fn push_field(buf: &mut Vec<u8>, bytes: &[u8]) {
+    buf.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+    buf.extend_from_slice(bytes);
 }
 
 /// Compact reference to a ledger block used in tool results.
@@ -136,10 +265,95 @@ mod tests {
             output_hash: BlockHash("b".repeat(64)),
             parent_hash: BlockHash::genesis(),
             signature: None,
+            signer_key_id: None,
+            hash_version: HASH_VERSION_CURRENT,
+            refs: Vec::new(),
         };
         let s1 = block.canonical_string();
         let s2 = block.canonical_string();
         assert_eq!(s1, s2);
         assert!(s1.contains("42"));
     }
+
+    #[test]
+    fn test_canonical_bytes_prefix_lengths() {
+        let mut a = LedgerBlock {
+            id: LedgerBlockId::new(),
+            sequence_number: 1,
+            timestamp_utc: Utc::now(),
+            tenant_id: TenantId::new(),
+            agent_id: AgentId::new(),
+            task_id: TaskId::new(),
+            action: LedgerAction::ToolCall,
+            tool_id: None,
+            input_hash: BlockHash("ab".into()),
+            output_hash: BlockHash("c".into()),
+            parent_hash: BlockHash::genesis(),
+            signature: None,
+            signer_key_id: None,
+            hash_version: HASH_VERSION_CURRENT,
+            refs: Vec::new(),
+        };
+        let before = a.canonical_bytes();
+        // Shifting a character across the field boundary must change the encoding.
+        a.input_hash = BlockHash("a".into());
+        a.output_hash = BlockHash("bc".into());
+        assert_ne!(before, a.canonical_bytes());
+    }
+
+    #[test]
+    fn test_missing_hash_version_deserializes_as_legacy() {
+        let block = LedgerBlock {
+            id: LedgerBlockId::new(),
+            sequence_number: 1,
+            timestamp_utc: Utc::now(),
+            tenant_id: TenantId::new(),
+            agent_id: AgentId::new(),
+            task_id: TaskId::new(),
+            action: LedgerAction::Deploy,
+            tool_id: None,
+            input_hash: BlockHash("a".repeat(64)),
+            output_hash: BlockHash("b".repeat(64)),
+            parent_hash: BlockHash::genesis(),
+            signature: None,
+            signer_key_id: None,
+            hash_version: HASH_VERSION_CURRENT,
+            refs: Vec::new(),
+        };
+        let mut json = serde_json::to_value(&block).unwrap();
+        json.as_object_mut().unwrap().remove("hash_version");
+        let back: LedgerBlock = serde_json::from_value(json).unwrap();
+        assert_eq!(back.hash_version, HASH_VERSION_LEGACY);
+    }
+
+    #[test]
+    fn test_refs_are_hashed_only_from_current_version() {
+        let mut block: LedgerBlock = serde_json::from_value(serde_json::json!({
+            "id": LedgerBlockId::new(), "sequence_number": 1, "timestamp_utc": Utc::now(),
+            "tenant_id": TenantId::new(), "agent_id": AgentId::new(), "task_id": TaskId::new(),
+            "action": "COMPENSATION", "tool_id": null, "input_hash": "a", "output_hash": "b",
+            "parent_hash": BlockHash::genesis(), "signature": null, "hash_version": 3,
+        }))
+        .unwrap();
+        assert!(block.refs.is_empty());
+        let unlinked = block.canonical_bytes();
+        let target = LedgerBlockId::new();
+        block.refs.push(BlockRef {
+            kind: BlockRefKind::Compensates,
+            block_id: target,
+        });
+        assert_ne!(unlinked, block.canonical_bytes());
+        assert_eq!(block.ref_of(BlockRefKind::Compensates), Some(target));
+
+        block.hash_version = HASH_VERSION_FIELDS;
+        let with_refs = block.canonical_bytes();
+        block.refs.clear();
+        assert_eq!(with_refs, block.canonical_bytes());
+    }
+
+    #[test]
+    fn test_action_as_str_matches_serde() {
+        let json = serde_json::to_string(&LedgerAction::HumanReview).unwrap();
+        assert_eq!(json, format!("\"{}\"", LedgerAction::HumanReview.as_str()));
+    }
 }
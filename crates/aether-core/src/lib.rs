@@ -20,7 +20,10 @@ pub mod tool;
 
 // Re-export most commonly used items at crate root.
 pub use error::{AetherError, ErrorCode, ErrorEnvelope, Result};
-pub use ids::{AgentId, LedgerBlockId, RequestId, SessionId, TaskId, TenantId, ToolId, WorkflowId, WorkerId};
+pub use ids::{
+    AgentId, ApprovalId, LedgerBlockId, RequestId, SessionId, SimulationId, TaskId, TenantId,
+    ToolId, UserId, WorkerId, WorkflowId,
+};
 pub use tenant::{ResourceQuota, Tenant, TenantTier, UserRole};
 pub use tool::{
     ExecutionScope, RetryPolicy, ToolAccessLevel, ToolCall, ToolDefinition, ToolExecutionContext,
@@ -112,8 +112,12 @@ pub struct ToolResult {
 
 impl ToolResult {
     #[must_use]
-    pub fn success(call_id: impl Into<String>, tool_name: impl Into<String>,
-                   content: serde_json::Value, duration_ms: u64) -> Self {
+    pub fn success(
+        call_id: impl Into<String>,
+        tool_name: impl Into<String>,
+        content: serde_json::Value,
+        duration_ms: u64,
+    ) -> Self {
         Self {
             call_id: call_id.into(),
             tool_name: tool_name.into(),
@@ -126,8 +130,12 @@ impl ToolResult {
     }
 
     #[must_use]
-    pub fn failure(call_id: impl Into<String>, tool_name: impl Into<String>,
-                   error: impl Into<String>, duration_ms: u64) -> Self {
+    pub fn failure(
+        call_id: impl Into<String>,
+        tool_name: impl Into<String>,
+        error: impl Into<String>,
+        duration_ms: u64,
+    ) -> Self {
         Self {
             call_id: call_id.into(),
             tool_name: tool_name.into(),
@@ -0,0 +1,191 @@
+//! Chain integrity anomalies (PRD §14).
+//!
+//! A `ChainAnomaly` is one integrity problem in one block, as reported by a
+//! full-chain scan (see `scan`).
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use aether_core::ids::{LedgerBlockId, TenantId};
+use aether_core::ledger::BlockHash;
+
+/// One integrity problem found while scanning a chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChainAnomaly {
+    /// `parent_hash` does not equal the hash of the preceding block.
+    ParentMismatch {
+        block_id: LedgerBlockId,
+        sequence_number: u64,
+        expected: BlockHash,
+        actual: BlockHash,
+    },
+    /// Sequence numbers skip ahead.
+    SequenceGap {
+        block_id: LedgerBlockId,
+        sequence_number: u64,
+        expected: u64,
+    },
+    /// Same sequence number as the preceding block.
+    SequenceDuplicate {
+        block_id: LedgerBlockId,
+        sequence_number: u64,
+    },
+    /// Sequence number lower than the preceding block's.
+    SequenceOutOfOrder {
+        block_id: LedgerBlockId,
+        sequence_number: u64,
+        previous: u64,
+    },
+    /// Timestamp earlier than the preceding block's.
+    NonMonotonicTimestamp {
+        block_id: LedgerBlockId,
+        sequence_number: u64,
+        previous: DateTime<Utc>,
+        actual: DateTime<Utc>,
+    },
+    /// A hash field is not 64 hex characters.
+    InvalidHashFormat {
+        block_id: LedgerBlockId,
+        sequence_number: u64,
+        field: String,
+    },
+    /// Block belongs to a different tenant than the chain being scanned.
+    CrossTenant {
+        block_id: LedgerBlockId,
+        sequence_number: u64,
+        tenant_id: TenantId,
+    },
+    /// `hash_version` unknown to this build.
+    UnsupportedHashVersion {
+        block_id: LedgerBlockId,
+        sequence_number: u64,
+        version: u8,
+    },
+    /// Block carries `refs` but its `hash_version` predates hashing them.
+    UnhashedReferences {
+        block_id: LedgerBlockId,
+        sequence_number: u64,
+        version: u8,
+    },
+}
+
+impl ChainAnomaly {
+    /// The offending block.
+    pub fn block_id(&self) -> LedgerBlockId {
+        match self {
+            Self::ParentMismatch { block_id, .. }
+            | Self::SequenceGap { block_id, .. }
+            | Self::SequenceDuplicate { block_id, .. }
+            | Self::SequenceOutOfOrder { block_id, .. }
+            | Self::NonMonotonicTimestamp { block_id, .. }
+            | Self::InvalidHashFormat { block_id, .. }
+            | Self::CrossTenant { block_id, .. }
+            | Self::UnsupportedHashVersion { block_id, .. }
+            | Self::UnhashedReferences { block_id, .. } => *block_id,
+        }
+    }
+
+    /// Whether the anomaly breaks the hash chain itself.
+    ///
+    /// Only these fail `verify_chain`; sequence, timestamp, tenant and
+    /// hash-format findings are reported by scans alone.
+    pub fn breaks_chain(&self) -> bool {
+        matches!(
+            self,
+            Self::ParentMismatch { .. }
+                | Self::UnsupportedHashVersion { .. }
+                | Self::UnhashedReferences { .. }
+        )
+    }
+}
+
+impl fmt::Display for ChainAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ParentMismatch {
+                sequence_number,
+                expected,
+                actual,
+                ..
+            } => write!(
+                f,
+                "parent_hash mismatch at seq {sequence_number}: expected {}, got {}",
+                expected.0, actual.0
+            ),
+            Self::SequenceGap {
+                sequence_number,
+                expected,
+                ..
+            } => {
+                write!(
+                    f,
+                    "sequence gap: expected seq {expected}, got {sequence_number}"
+                )
+            }
+            Self::SequenceDuplicate {
+                sequence_number, ..
+            } => {
+                write!(f, "duplicate sequence number {sequence_number}")
+            }
+            Self::SequenceOutOfOrder {
+                sequence_number,
+                previous,
+                ..
+            } => {
+                write!(f, "seq {sequence_number} follows higher seq {previous}")
+            }
+            Self::NonMonotonicTimestamp {
+                sequence_number,
+                previous,
+                actual,
+                ..
+            } => write!(
+                f,
+                "timestamp at seq {sequence_number} ({actual}) precedes previous block ({previous})"
+            ),
+            Self::InvalidHashFormat {
+                sequence_number,
+                field,
+                ..
+            } => {
+                write!(
+                    f,
+                    "{field} at seq {sequence_number} is not a SHA-256 hex digest"
+                )
+            }
+            Self::CrossTenant {
+                sequence_number,
+                tenant_id,
+                ..
+            } => {
+                write!(
+                    f,
+                    "block at seq {sequence_number} belongs to tenant {tenant_id}"
+                )
+            }
+            Self::UnsupportedHashVersion {
+                sequence_number,
+                version,
+                ..
+            } => {
+                write!(
+                    f,
+                    "unsupported hash_version {version} at seq {sequence_number}"
+                )
+            }
+            Self::UnhashedReferences {
+                sequence_number,
+                version,
+                ..
+            } => {
+                write!(
+                    f,
+                    "refs at seq {sequence_number} are not covered by hash_version {version}"
+                )
+            }
+        }
+    }
+}
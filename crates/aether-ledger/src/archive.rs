@@ -0,0 +1,130 @@
+//! Durable storage for pruned ledger blocks (PRD §14).
+//!
+//! `LedgerPruner` hands each pruned range to a `PruneArchive` and reads it
+//! back against the checkpoint's Merkle root before the backend drops the
+//! blocks, so a failed or lossy archive aborts the prune instead of losing
+//! evidence. Archives are keyed by tenant and checkpoint head.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::TenantId;
+use aether_core::ledger::LedgerBlock;
+
+use crate::prune::PruneCheckpoint;
+
+/// Stores the blocks removed by a prune.
+pub trait PruneArchive: Send + Sync {
+    /// Persist `blocks`, the range covered by `checkpoint`. Must be durable
+    /// once this returns.
+    fn store(&self, checkpoint: &PruneCheckpoint, blocks: &[LedgerBlock]) -> Result<()>;
+
+    /// Blocks stored for `checkpoint`; `None` if it was never archived.
+    fn load(&self, checkpoint: &PruneCheckpoint) -> Result<Option<Vec<LedgerBlock>>>;
+}
+
+impl<A: PruneArchive + ?Sized> PruneArchive for Arc<A> {
+    fn store(&self, checkpoint: &PruneCheckpoint, blocks: &[LedgerBlock]) -> Result<()> {
+        (**self).store(checkpoint, blocks)
+    }
+
+    fn load(&self, checkpoint: &PruneCheckpoint) -> Result<Option<Vec<LedgerBlock>>> {
+        (**self).load(checkpoint)
+    }
+}
+
+/// In-memory prune archive — suitable for testing.
+#[derive(Default)]
+pub struct InMemoryPruneArchive {
+    /// (tenant_id, last pruned sequence) → blocks
+    ranges: RwLock<HashMap<(TenantId, u64), Vec<LedgerBlock>>>,
+}
+
+impl InMemoryPruneArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PruneArchive for InMemoryPruneArchive {
+    fn store(&self, checkpoint: &PruneCheckpoint, blocks: &[LedgerBlock]) -> Result<()> {
+        let mut map = self
+            .ranges
+            .write()
+            .map_err(|e| AetherError::internal(format!("archive lock poisoned: {e}")))?;
+        map.insert(key(checkpoint), blocks.to_vec());
+        Ok(())
+    }
+
+    fn load(&self, checkpoint: &PruneCheckpoint) -> Result<Option<Vec<LedgerBlock>>> {
+        let map = self
+            .ranges
+            .read()
+            .map_err(|e| AetherError::internal(format!("archive lock poisoned: {e}")))?;
+        Ok(map.get(&key(checkpoint)).cloned())
+    }
+}
+
+fn key(checkpoint: &PruneCheckpoint) -> (TenantId, u64) {
+    (checkpoint.tenant_id, checkpoint.head.sequence_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::LedgerBlockBuilder;
+    use crate::prune::LedgerPruner;
+    use crate::signing::{Ed25519Signer, LedgerKeyring};
+    use crate::storage::{InMemoryLedgerStorage, LedgerStorage};
+    use crate::writer::LedgerWriter;
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::LedgerAction;
+
+    /// Accepts every range but reads back only its first block.
+    struct LossyArchive(InMemoryPruneArchive);
+
+    impl PruneArchive for LossyArchive {
+        fn store(&self, checkpoint: &PruneCheckpoint, blocks: &[LedgerBlock]) -> Result<()> {
+            self.0.store(checkpoint, &blocks[..1])
+        }
+
+        fn load(&self, checkpoint: &PruneCheckpoint) -> Result<Option<Vec<LedgerBlock>>> {
+            self.0.load(checkpoint)
+        }
+    }
+
+    fn pruner(archive: Arc<dyn PruneArchive>) -> (TenantId, LedgerPruner<InMemoryLedgerStorage>) {
+        let signer = Arc::new(Ed25519Signer::from_seed("node", &[5; 32]));
+        let keyring = Arc::new(LedgerKeyring::new().with_node_signer(signer));
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let t = TenantId::new();
+        for _ in 0..4 {
+            let (agent, task) = (AgentId::new(), TaskId::new());
+            let entry = LedgerBlockBuilder::unlinked(t, agent, task, LedgerAction::ToolCall);
+            writer.append(entry).unwrap();
+        }
+        (
+            t,
+            LedgerPruner::new(writer.into_storage(), keyring, archive),
+        )
+    }
+
+    #[test]
+    fn test_pruned_range_is_archived_before_deletion() {
+        let archive = Arc::new(InMemoryPruneArchive::new());
+        let (t, pruner) = pruner(archive.clone());
+        let outcome = pruner.prune_through(&t, 2).unwrap().unwrap();
+        let stored = archive.load(&outcome.checkpoint).unwrap().unwrap();
+        assert!(outcome.checkpoint.verify_archive(&stored));
+        assert_eq!(pruner.storage().count(&t).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_lossy_archive_aborts_prune() {
+        let (t, pruner) = pruner(Arc::new(LossyArchive(InMemoryPruneArchive::new())));
+        assert!(pruner.prune_through(&t, 2).is_err());
+        assert_eq!(pruner.storage().count(&t).unwrap(), 4);
+        assert!(pruner.storage().prune_checkpoint(&t).unwrap().is_none());
+    }
+}
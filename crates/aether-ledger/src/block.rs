@@ -1,14 +1,15 @@
 //! Ledger block construction and hashing (PRD §14).
 //!
 //! Builds LedgerBlocks from execution events and computes SHA-256 hashes
-//! for chain integrity. Ed25519 signing is optional (can be added later).
+//! for chain integrity. Signing happens at append time (see `signing`).
 
 use chrono::Utc;
 use sha2::{Digest, Sha256};
 
 use aether_core::ids::{AgentId, LedgerBlockId, TaskId, TenantId, ToolId};
-use aether_core::ledger::{BlockHash, LedgerAction, LedgerBlock, LedgerRef};
-use aether_core::error::Result;
+use aether_core::ledger::{
+    BlockHash, BlockRef, BlockRefKind, LedgerAction, LedgerBlock, LedgerRef, HASH_VERSION_CURRENT,
+};
 
 /// Builder for `LedgerBlock`.
 ///
@@ -23,6 +24,8 @@ pub struct LedgerBlockBuilder {
     output: serde_json::Value,
     parent_hash: BlockHash,
     sequence_number: u64,
+    refs: Vec<BlockRef>,
+    id: Option<LedgerBlockId>,
 }
 
 impl LedgerBlockBuilder {
@@ -44,14 +47,89 @@ impl LedgerBlockBuilder {
             output: serde_json::Value::Null,
             parent_hash,
             sequence_number,
+            refs: Vec::new(),
+            id: None,
         }
     }
 
+    /// Start a block whose chain position will be filled in by `LedgerWriter`.
+    pub fn unlinked(
+        tenant_id: TenantId,
+        agent_id: AgentId,
+        task_id: TaskId,
+        action: LedgerAction,
+    ) -> Self {
+        Self::new(
+            tenant_id,
+            agent_id,
+            task_id,
+            action,
+            BlockHash::genesis(),
+            0,
+        )
+    }
+
+    /// Set the chain position (parent hash and sequence number).
+    pub(crate) fn link(mut self, parent_hash: BlockHash, sequence_number: u64) -> Self {
+        self.parent_hash = parent_hash;
+        self.sequence_number = sequence_number;
+        self
+    }
+
+    /// Give the block a pre-allocated id, so a caller can record it before
+    /// the append. `LedgerWriter` rejects an id that is already stored.
+    pub fn id(mut self, id: LedgerBlockId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub(crate) fn preset_id(&self) -> Option<LedgerBlockId> {
+        self.id
+    }
+
+    pub(crate) fn tenant_id(&self) -> TenantId {
+        self.tenant_id
+    }
+
+    pub(crate) fn action(&self) -> &LedgerAction {
+        &self.action
+    }
+
+    pub(crate) fn refs(&self) -> &[BlockRef] {
+        &self.refs
+    }
+
+    /// Input and output payloads, for storing alongside the block.
+    pub(crate) fn payloads(&self) -> [&serde_json::Value; 2] {
+        [&self.input, &self.output]
+    }
+
     pub fn tool_id(mut self, id: ToolId) -> Self {
         self.tool_id = Some(id);
         self
     }
 
+    /// Add a typed reference to an earlier block of the same tenant.
+    pub fn reference(mut self, kind: BlockRefKind, block_id: LedgerBlockId) -> Self {
+        self.refs.push(BlockRef { kind, block_id });
+        self
+    }
+
+    /// Mark this `Compensation` block as undoing `block_id`.
+    pub fn compensates(self, block_id: LedgerBlockId) -> Self {
+        self.reference(BlockRefKind::Compensates, block_id)
+    }
+
+    /// Record the block that triggered this one.
+    pub fn caused_by(self, block_id: LedgerBlockId) -> Self {
+        self.reference(BlockRefKind::CausedBy, block_id)
+    }
+
+    /// Record the `HumanReview` block that approved this action.
+    pub fn approved_by(self, block_id: LedgerBlockId) -> Self {
+        self.reference(BlockRefKind::ApprovedBy, block_id)
+    }
+
     pub fn input(mut self, v: serde_json::Value) -> Self {
         self.input = v;
         self
@@ -65,7 +143,7 @@ impl LedgerBlockBuilder {
     /// Build the block, computing SHA-256 hashes for input and output.
     pub fn build(self) -> LedgerBlock {
         LedgerBlock {
-            id: LedgerBlockId::new(),
+            id: self.id.unwrap_or_default(),
             sequence_number: self.sequence_number,
             timestamp_utc: Utc::now(),
             tenant_id: self.tenant_id,
@@ -77,12 +155,15 @@ impl LedgerBlockBuilder {
             output_hash: hash_value(&self.output),
             parent_hash: self.parent_hash,
             signature: None,
+            signer_key_id: None,
+            hash_version: HASH_VERSION_CURRENT,
+            refs: self.refs,
         }
     }
 }
 
 /// Compute SHA-256 hash of a JSON value, returned as lowercase hex string.
-fn hash_value(value: &serde_json::Value) -> BlockHash {
+pub(crate) fn hash_value(value: &serde_json::Value) -> BlockHash {
     let bytes = serde_json::to_vec(value).unwrap_or_default();
     let mut hasher = Sha256::new();
     hasher.update(&bytes);
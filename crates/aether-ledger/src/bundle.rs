@@ -0,0 +1,397 @@
+//! Signed export bundles for external auditors (PRD §14).
+//!
+//! A `LedgerBundle` is one self-contained JSON document holding a tenant's
+//! blocks, optionally their payloads and Merkle checkpoints, the public keys
+//! that signed them, and an Ed25519 signature over the whole bundle.
+//! `verify_bundle` re-checks all of it offline: it needs the bundle and a
+//! keyring, nothing else. Auditors either pin keys they obtained out of band
+//! or use `LedgerBundle::embedded_keyring` and compare the reported key ids.
+//! Bundles of a pruned chain carry the signed `PruneCheckpoint` the blocks
+//! continue from.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Verifier};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::TenantId;
+use aether_core::ledger::{BlockHash, LedgerBlock};
+
+use crate::block::hash_value;
+use crate::chain::{verify_chain_signed_from, ChainHead};
+use crate::merkle::{MerkleCheckpoint, MerkleCheckpointer};
+use crate::payload::PayloadStore;
+use crate::prune::PruneCheckpoint;
+use crate::segment::io_error;
+use crate::signing::{decode_hex, encode_hex, LedgerKeyring, PublicKeyRecord, SignerRecord};
+use crate::storage::LedgerStorage;
+
+/// Bundle layout version written by this build.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// A stored payload and the hash blocks refer to it by.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundlePayload {
+    pub hash: BlockHash,
+    pub payload: serde_json::Value,
+}
+
+/// Exporter's signature over the bundle digest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSignature {
+    pub key_id: String,
+    /// Hex-encoded Ed25519 signature over `LedgerBundle::digest`.
+    pub signature: String,
+}
+
+/// A tenant's ledger packaged for offline verification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerBundle {
+    pub format_version: u32,
+    pub tenant_id: TenantId,
+    pub exported_at: DateTime<Utc>,
+    pub blocks: Vec<LedgerBlock>,
+    pub payloads: Vec<BundlePayload>,
+    pub checkpoints: Vec<MerkleCheckpoint>,
+    /// Where `blocks` start if the chain was pruned (`None` = genesis).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prune_checkpoint: Option<PruneCheckpoint>,
+    /// Public keys for every signer in the bundle.
+    pub public_keys: Vec<PublicKeyRecord>,
+    pub signature: Option<BundleSignature>,
+}
+
+impl LedgerBundle {
+    /// SHA-256 over the bundle's JSON encoding with `signature` cleared.
+    ///
+    /// # Errors
+    /// Returns `SerializationError` if the bundle cannot be encoded.
+    pub fn digest(&self) -> Result<BlockHash> {
+        let unsigned = Self {
+            signature: None,
+            ..self.clone()
+        };
+        let bytes = serde_json::to_vec(&unsigned)
+            .map_err(|e| AetherError::SerializationError(e.to_string()))?;
+        Ok(BlockHash(format!("{:x}", Sha256::digest(&bytes))))
+    }
+
+    /// Keyring built from the bundle's own public keys.
+    ///
+    /// # Errors
+    /// Returns `ValidationFailed` for a malformed key.
+    pub fn embedded_keyring(&self) -> Result<LedgerKeyring> {
+        LedgerKeyring::from_public_keys(&self.public_keys)
+    }
+
+    /// Write the bundle as a single JSON file.
+    ///
+    /// # Errors
+    /// Returns storage or serialization errors.
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(self)
+            .map_err(|e| AetherError::SerializationError(e.to_string()))?;
+        fs::write(path, bytes).map_err(io_error)
+    }
+
+    /// Read a bundle written by `write_to`.
+    ///
+    /// # Errors
+    /// Returns storage or serialization errors.
+    pub fn read_from(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).map_err(io_error)?;
+        serde_json::from_slice(&bytes).map_err(|e| AetherError::SerializationError(e.to_string()))
+    }
+}
+
+/// Builds signed bundles from any `LedgerStorage`.
+pub struct BundleExporter<'a> {
+    keyring: &'a LedgerKeyring,
+    payloads: Option<&'a dyn PayloadStore>,
+    checkpointer: Option<MerkleCheckpointer>,
+}
+
+impl<'a> BundleExporter<'a> {
+    /// `keyring` supplies the bundle signer and the public keys to embed.
+    pub fn new(keyring: &'a LedgerKeyring) -> Self {
+        Self {
+            keyring,
+            payloads: None,
+            checkpointer: None,
+        }
+    }
+
+    /// Include every stored input/output payload the blocks reference.
+    pub fn with_payloads(mut self, payloads: &'a dyn PayloadStore) -> Self {
+        self.payloads = Some(payloads);
+        self
+    }
+
+    /// Include Merkle checkpoints for every complete range.
+    pub fn with_checkpoints(mut self, checkpointer: MerkleCheckpointer) -> Self {
+        self.checkpointer = Some(checkpointer);
+        self
+    }
+
+    /// Export and sign one tenant's ledger.
+    ///
+    /// # Errors
+    /// Returns storage errors, or `Internal` if the keyring has no signer
+    /// for the tenant.
+    pub fn export<S: LedgerStorage + ?Sized>(
+        &self,
+        storage: &S,
+        tenant_id: &TenantId,
+    ) -> Result<LedgerBundle> {
+        let signer = self.keyring.signer_for(tenant_id).ok_or_else(|| {
+            AetherError::internal(format!("no ledger signing key for tenant {tenant_id}"))
+        })?;
+        let blocks = storage.get_blocks(tenant_id)?;
+        let mut bundle = LedgerBundle {
+            format_version: BUNDLE_FORMAT_VERSION,
+            tenant_id: *tenant_id,
+            exported_at: Utc::now(),
+            payloads: self.collect_payloads(tenant_id, &blocks)?,
+            checkpoints: self
+                .checkpointer
+                .map(|c| c.checkpoints(&blocks))
+                .unwrap_or_default(),
+            prune_checkpoint: storage.prune_checkpoint(tenant_id)?,
+            blocks,
+            public_keys: self.keyring.public_keys_for(tenant_id),
+            signature: None,
+        };
+        let digest = bundle.digest()?;
+        bundle.signature = Some(BundleSignature {
+            key_id: signer.key_id().to_string(),
+            signature: encode_hex(&signer.sign(digest.0.as_bytes()).to_bytes()),
+        });
+        Ok(bundle)
+    }
+
+    fn collect_payloads(
+        &self,
+        tenant_id: &TenantId,
+        blocks: &[LedgerBlock],
+    ) -> Result<Vec<BundlePayload>> {
+        let Some(store) = self.payloads else {
+            return Ok(Vec::new());
+        };
+        let mut found = BTreeMap::new();
+        for hash in blocks.iter().flat_map(|b| [&b.input_hash, &b.output_hash]) {
+            if found.contains_key(&hash.0) {
+                continue;
+            }
+            if let Some(payload) = store.get(tenant_id, hash)? {
+                found.insert(hash.0.clone(), payload);
+            }
+        }
+        Ok(found
+            .into_iter()
+            .map(|(hash, payload)| BundlePayload {
+                hash: BlockHash(hash),
+                payload,
+            })
+            .collect())
+    }
+}
+
+/// Outcome of a successful bundle verification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleVerification {
+    pub tenant_id: TenantId,
+    pub blocks_verified: u64,
+    pub payloads_verified: u64,
+    pub checkpoints_verified: u64,
+    /// Key that signed the bundle itself.
+    pub bundle_key_id: String,
+    /// Which key signed each block.
+    pub signers: Vec<SignerRecord>,
+}
+
+/// Verify a bundle offline: chain, block signatures, payload hashes,
+/// checkpoint roots, the prune checkpoint, and the bundle signature.
+///
+/// # Errors
+/// Returns `LedgerIntegrityViolation` describing the first failed check.
+pub fn verify_bundle(bundle: &LedgerBundle, keyring: &LedgerKeyring) -> Result<BundleVerification> {
+    let violation = |reason: String| AetherError::LedgerIntegrityViolation {
+        block_id: format!("bundle:{}", bundle.tenant_id),
+        reason,
+    };
+    if bundle.format_version != BUNDLE_FORMAT_VERSION {
+        let version = bundle.format_version;
+        return Err(violation(format!("unsupported bundle format {version}")));
+    }
+    let bundle_key_id = verify_bundle_signature(bundle, keyring).map_err(violation)?;
+    let start = match &bundle.prune_checkpoint {
+        Some(c) if c.tenant_id != bundle.tenant_id => {
+            let tenant = c.tenant_id;
+            return Err(violation(format!(
+                "prune checkpoint belongs to tenant {tenant}"
+            )));
+        }
+        Some(c) => {
+            c.verify_signature(keyring)?;
+            c.head.clone()
+        }
+        None => ChainHead::genesis(),
+    };
+    check_contents(bundle).map_err(violation)?;
+    let signers = verify_chain_signed_from(&bundle.blocks, &start, keyring)?;
+    Ok(BundleVerification {
+        tenant_id: bundle.tenant_id,
+        blocks_verified: bundle.blocks.len() as u64,
+        payloads_verified: bundle.payloads.len() as u64,
+        checkpoints_verified: bundle.checkpoints.len() as u64,
+        bundle_key_id,
+        signers,
+    })
+}
+
+/// Every block belongs to the bundle's tenant, every payload matches its
+/// hash and every checkpoint matches the bundled blocks.
+fn check_contents(bundle: &LedgerBundle) -> std::result::Result<(), String> {
+    let (blocks, payloads) = (&bundle.blocks, &bundle.payloads);
+    if let Some(b) = blocks.iter().find(|b| b.tenant_id != bundle.tenant_id) {
+        return Err(format!("block {} belongs to tenant {}", b.id, b.tenant_id));
+    }
+    if let Some(p) = payloads.iter().find(|p| hash_value(&p.payload) != p.hash) {
+        return Err(format!("payload {} does not match its hash", p.hash.0));
+    }
+    if let Some(c) = bundle.checkpoints.iter().find(|c| !c.verify_blocks(blocks)) {
+        let (first, last) = (c.first_sequence, c.last_sequence);
+        return Err(format!(
+            "checkpoint {first}..={last} does not match the bundled blocks"
+        ));
+    }
+    Ok(())
+}
+
+fn verify_bundle_signature(
+    bundle: &LedgerBundle,
+    keyring: &LedgerKeyring,
+) -> std::result::Result<String, String> {
+    let sig = bundle.signature.as_ref().ok_or("bundle is unsigned")?;
+    let key = keyring
+        .verifying_key(&sig.key_id, &bundle.tenant_id)
+        .ok_or_else(|| format!("bundle key '{}' is not trusted", sig.key_id))?;
+    let bytes: [u8; 64] = decode_hex(&sig.signature)
+        .and_then(|b| b.try_into().ok())
+        .ok_or("malformed bundle signature")?;
+    let digest = bundle.digest().map_err(|e| e.to_string())?;
+    key.verify(digest.0.as_bytes(), &Signature::from_bytes(&bytes))
+        .map_err(|_| format!("invalid bundle signature by key '{}'", sig.key_id))?;
+    Ok(sig.key_id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::block::LedgerBlockBuilder;
+    use crate::payload::InMemoryPayloadStore;
+    use crate::signing::Ed25519Signer;
+    use crate::storage::InMemoryLedgerStorage;
+    use crate::writer::LedgerWriter;
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::LedgerAction;
+
+    struct Fixture {
+        tenant: TenantId,
+        keyring: Arc<LedgerKeyring>,
+        payloads: Arc<InMemoryPayloadStore>,
+        writer: LedgerWriter<InMemoryLedgerStorage>,
+    }
+
+    fn fixture(blocks: u32) -> Fixture {
+        let signer = Arc::new(Ed25519Signer::from_seed("node-1", &[9; 32]));
+        let keyring = Arc::new(LedgerKeyring::new().with_node_signer(signer));
+        let payloads = Arc::new(InMemoryPayloadStore::new());
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new())
+            .with_keyring(keyring.clone())
+            .with_payload_store(payloads.clone());
+        let tenant = TenantId::new();
+        for n in 0..blocks {
+            let (agent, task) = (AgentId::new(), TaskId::new());
+            let entry = LedgerBlockBuilder::unlinked(tenant, agent, task, LedgerAction::ToolCall)
+                .input(serde_json::json!({"n": n}));
+            writer.append(entry).unwrap();
+        }
+        Fixture {
+            tenant,
+            keyring,
+            payloads,
+            writer,
+        }
+    }
+
+    fn export(f: &Fixture) -> LedgerBundle {
+        BundleExporter::new(&f.keyring)
+            .with_payloads(f.payloads.as_ref())
+            .with_checkpoints(MerkleCheckpointer::new(2))
+            .export(f.writer.storage(), &f.tenant)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bundle_roundtrips_and_verifies_offline() {
+        let f = fixture(5);
+        let bundle = export(&f);
+        assert_eq!(bundle.checkpoints.len(), 2);
+        let name = format!("ledger-bundle-{}.json", uuid::Uuid::new_v4());
+        let path = std::env::temp_dir().join(name);
+        bundle.write_to(&path).unwrap();
+        let read = LedgerBundle::read_from(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let report = verify_bundle(&read, &read.embedded_keyring().unwrap()).unwrap();
+        assert_eq!(report.blocks_verified, 5);
+        assert_eq!(report.payloads_verified, 6); // 5 inputs + one shared null output
+        assert_eq!(report.checkpoints_verified, 2);
+        assert_eq!(report.bundle_key_id, "node-1");
+    }
+
+    #[test]
+    fn test_tampered_block_fails() {
+        let f = fixture(3);
+        let mut bundle = export(&f);
+        bundle.blocks[1].sequence_number = 7;
+        assert!(verify_bundle(&bundle, &f.keyring).is_err());
+    }
+
+    #[test]
+    fn test_tampered_payload_fails() {
+        let f = fixture(2);
+        let mut bundle = export(&f);
+        bundle.payloads[0].payload = serde_json::json!({"forged": true});
+        assert!(verify_bundle(&bundle, &f.keyring).is_err());
+    }
+
+    #[test]
+    fn test_untrusted_exporter_key_fails() {
+        let f = fixture(2);
+        let bundle = export(&f);
+        let other = Arc::new(Ed25519Signer::from_seed("node-1", &[1; 32]));
+        let pinned = LedgerKeyring::new().with_node_signer(other);
+        assert!(verify_bundle(&bundle, &pinned).is_err());
+    }
+
+    #[test]
+    fn test_bundle_excludes_other_tenants() {
+        let f = fixture(2);
+        let other = TenantId::new();
+        let (agent, task) = (AgentId::new(), TaskId::new());
+        let entry = LedgerBlockBuilder::unlinked(other, agent, task, LedgerAction::Deploy);
+        f.writer.append(entry).unwrap();
+        let bundle = export(&f);
+        assert!(bundle.blocks.iter().all(|b| b.tenant_id == f.tenant));
+        assert!(verify_bundle(&bundle, &f.keyring).is_ok());
+    }
+}
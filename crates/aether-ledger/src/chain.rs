@@ -2,19 +2,68 @@
 //!
 //! Verifies that a sequence of `LedgerBlock`s forms a valid hash chain:
 //! each block's `parent_hash` must equal the hash of its predecessor.
+//! Fail-fast; see `scan` for the full-scan audit, which also reports
+//! sequence, timestamp and tenant findings.
 
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 use aether_core::error::{AetherError, Result};
-use aether_core::ledger::{BlockHash, LedgerBlock};
+use aether_core::ids::TenantId;
+use aether_core::ledger::{BlockHash, LedgerBlock, HASH_VERSION_LEGACY};
+
+use crate::anomaly::ChainAnomaly;
+use crate::scan::scan_chain;
+use crate::signing::{verify_block_signature, LedgerKeyring, SignerRecord};
+use crate::storage::LedgerStorage;
+
+/// The tip of a tenant's chain: what the next block must link to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainHead {
+    /// Sequence number of the last block (0 for an empty chain).
+    pub sequence_number: u64,
+    /// Hash of the last block (genesis hash for an empty chain).
+    pub hash: BlockHash,
+}
+
+impl ChainHead {
+    /// Head of an empty chain.
+    #[must_use]
+    pub fn genesis() -> Self {
+        Self {
+            sequence_number: 0,
+            hash: BlockHash::genesis(),
+        }
+    }
+
+    /// Head after appending `block`.
+    #[must_use]
+    pub fn of(block: &LedgerBlock) -> Self {
+        Self {
+            sequence_number: block.sequence_number,
+            hash: compute_block_hash(block),
+        }
+    }
+
+    /// Sequence number the next block must carry.
+    #[must_use]
+    pub fn next_sequence(&self) -> u64 {
+        self.sequence_number + 1
+    }
+}
 
 /// Compute the block's own hash (used as the `parent_hash` by the next block).
 ///
-/// Hashes the canonical string: `{id}|{seq}|{input_hash}|{output_hash}|{parent_hash}`
+/// The encoding is selected by `block.hash_version`: legacy blocks hash
+/// `canonical_string`, later versions hash the length-prefixed
+/// `canonical_bytes` (which covers `refs` from `HASH_VERSION_CURRENT`).
 pub fn compute_block_hash(block: &LedgerBlock) -> BlockHash {
-    let canonical = block.canonical_string();
     let mut hasher = Sha256::new();
-    hasher.update(canonical.as_bytes());
+    if block.hash_version == HASH_VERSION_LEGACY {
+        hasher.update(block.canonical_string().as_bytes());
+    } else {
+        hasher.update(block.canonical_bytes());
+    }
     BlockHash(format!("{:x}", hasher.finalize()))
 }
 
@@ -24,44 +73,94 @@ pub fn compute_block_hash(block: &LedgerBlock) -> BlockHash {
 ///
 /// # Errors
 /// Returns `LedgerIntegrityViolation` if any block's parent hash does not
-/// match the computed hash of the preceding block.
+/// match the computed hash of the preceding block (the genesis hash for the
+/// first), a block uses an unknown `hash_version`, or a block's `refs` are
+/// not covered by its `hash_version`.
 pub fn verify_chain(blocks: &[LedgerBlock]) -> Result<()> {
-    if blocks.is_empty() {
+    verify_chain_from(blocks, &ChainHead::genesis())
+}
+
+/// Verify `blocks` as a continuation of a trusted `start` head.
+///
+/// Used to audit a sub-range whose predecessor is vouched for by a
+/// checkpoint rather than by re-walking the chain from genesis.
+///
+/// # Errors
+/// As `verify_chain`.
+pub fn verify_chain_from(blocks: &[LedgerBlock], start: &ChainHead) -> Result<()> {
+    let Some(first) = blocks.first() else {
         return Ok(());
+    };
+    let findings = scan_chain(&first.tenant_id, blocks, start);
+    let broken = findings
+        .into_iter()
+        .flat_map(|f| f.anomalies)
+        .find(ChainAnomaly::breaks_chain);
+    match broken {
+        Some(anomaly) => Err(AetherError::LedgerIntegrityViolation {
+            block_id: anomaly.block_id().to_string(),
+            reason: anomaly.to_string(),
+        }),
+        None => Ok(()),
     }
+}
 
-    // First block's parent must be the genesis hash
-    let genesis = BlockHash::genesis();
-    if blocks[0].parent_hash != genesis {
-        return Err(AetherError::LedgerIntegrityViolation {
-            block_id: blocks[0].id.to_string(),
-            reason: "first block parent_hash is not genesis".into(),
-        });
-    }
+/// Verify the hash chain and require a valid Ed25519 signature on every block.
+///
+/// Returns which key signed each block, in chain order.
+///
+/// # Errors
+/// Returns `LedgerIntegrityViolation` if the chain is broken, or any block is
+/// unsigned, signed by an untrusted key, or carries an invalid signature.
+pub fn verify_chain_signed(
+    blocks: &[LedgerBlock],
+    keyring: &LedgerKeyring,
+) -> Result<Vec<SignerRecord>> {
+    verify_chain_signed_from(blocks, &ChainHead::genesis(), keyring)
+}
 
-    // Each subsequent block's parent_hash = hash of previous block
-    for window in blocks.windows(2) {
-        let prev = &window[0];
-        let curr = &window[1];
-        let expected_parent = compute_block_hash(prev);
-        if curr.parent_hash != expected_parent {
-            return Err(AetherError::LedgerIntegrityViolation {
-                block_id: curr.id.to_string(),
-                reason: format!(
-                    "parent_hash mismatch at seq {}: expected {}, got {}",
-                    curr.sequence_number, expected_parent.0, curr.parent_hash.0
-                ),
-            });
-        }
-    }
-    Ok(())
+/// `verify_chain_signed` for a continuation of a trusted `start` head.
+///
+/// # Errors
+/// As `verify_chain_signed`.
+pub fn verify_chain_signed_from(
+    blocks: &[LedgerBlock],
+    start: &ChainHead,
+    keyring: &LedgerKeyring,
+) -> Result<Vec<SignerRecord>> {
+    verify_chain_from(blocks, start)?;
+    blocks
+        .iter()
+        .map(|b| verify_block_signature(b, keyring))
+        .collect()
+}
+
+/// Where verification of `tenant_id`'s stored chain starts: the latest
+/// prune checkpoint, or genesis if the chain was never pruned.
+///
+/// The checkpoint is taken as stored; use
+/// `PruneCheckpoint::verify_signature` to authenticate it.
+///
+/// # Errors
+/// Propagates storage errors.
+pub fn chain_start<S: LedgerStorage + ?Sized>(
+    storage: &S,
+    tenant_id: &TenantId,
+) -> Result<ChainHead> {
+    Ok(storage
+        .prune_checkpoint(tenant_id)?
+        .map(|c| c.head)
+        .unwrap_or_else(ChainHead::genesis))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use aether_core::ids::{AgentId, LedgerBlockId, TaskId, TenantId};
-    use aether_core::ledger::{BlockHash, LedgerAction, LedgerBlock};
+    use aether_core::ids::{AgentId, LedgerBlockId, TaskId, TenantId, ToolId};
+    use aether_core::ledger::{
+        BlockHash, BlockRef, BlockRefKind, LedgerAction, LedgerBlock, HASH_VERSION_CURRENT,
+        HASH_VERSION_FIELDS,
+    };
     use chrono::Utc;
 
     fn make_genesis_block() -> LedgerBlock {
@@ -78,6 +177,9 @@ mod tests {
             output_hash: BlockHash("b".repeat(64)),
             parent_hash: BlockHash::genesis(),
             signature: None,
+            signer_key_id: None,
+            hash_version: HASH_VERSION_CURRENT,
+            refs: Vec::new(),
         }
     }
 
@@ -110,6 +212,9 @@ mod tests {
             output_hash: BlockHash("d".repeat(64)),
             parent_hash: b1_hash,
             signature: None,
+            signer_key_id: None,
+            hash_version: HASH_VERSION_CURRENT,
+            refs: Vec::new(),
         };
         assert!(verify_chain(&[b1, b2]).is_ok());
     }
@@ -130,7 +235,122 @@ mod tests {
             output_hash: BlockHash("d".repeat(64)),
             parent_hash: BlockHash("0".repeat(64)), // WRONG — tampered
             signature: None,
+            signer_key_id: None,
+            hash_version: HASH_VERSION_CURRENT,
+            refs: Vec::new(),
         };
         assert!(verify_chain(&[b1, b2]).is_err());
     }
+
+    fn make_successor(prev: &LedgerBlock) -> LedgerBlock {
+        LedgerBlock {
+            id: LedgerBlockId::new(),
+            sequence_number: prev.sequence_number + 1,
+            parent_hash: compute_block_hash(prev),
+            ..prev.clone()
+        }
+    }
+
+    type Mutation = (&'static str, fn(&mut LedgerBlock));
+
+    #[test]
+    fn test_mutating_any_field_is_detected() {
+        let b1 = make_genesis_block();
+        let b2 = make_successor(&b1);
+        let mutations: Vec<Mutation> = vec![
+            ("id", |b| b.id = LedgerBlockId::new()),
+            ("sequence_number", |b| b.sequence_number += 1),
+            ("timestamp_utc", |b| {
+                b.timestamp_utc += chrono::Duration::nanoseconds(1)
+            }),
+            ("tenant_id", |b| b.tenant_id = TenantId::new()),
+            ("agent_id", |b| b.agent_id = AgentId::new()),
+            ("task_id", |b| b.task_id = TaskId::new()),
+            ("action", |b| b.action = LedgerAction::Deploy),
+            ("tool_id", |b| b.tool_id = Some(ToolId::new())),
+            ("input_hash", |b| b.input_hash = BlockHash("e".repeat(64))),
+            ("output_hash", |b| b.output_hash = BlockHash("f".repeat(64))),
+            ("hash_version", |b| b.hash_version = HASH_VERSION_LEGACY),
+            ("refs", |b| {
+                b.refs.push(BlockRef {
+                    kind: BlockRefKind::CausedBy,
+                    block_id: b.id,
+                })
+            }),
+        ];
+        for (field, mutate) in mutations {
+            let mut tampered = b1.clone();
+            mutate(&mut tampered);
+            assert!(
+                verify_chain(&[tampered, b2.clone()]).is_err(),
+                "mutating {field} went undetected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mutating_parent_hash_is_detected() {
+        let b1 = make_genesis_block();
+        let b2 = make_successor(&b1);
+        let mut b3 = make_successor(&b2);
+        let b4 = make_successor(&b3);
+        b3.parent_hash = BlockHash("1".repeat(64));
+        assert!(verify_chain(&[b1, b2, b3, b4]).is_err());
+    }
+
+    #[test]
+    fn test_legacy_chain_still_verifies() {
+        let mut b1 = make_genesis_block();
+        b1.hash_version = HASH_VERSION_LEGACY;
+        let b2 = make_successor(&b1);
+        assert_eq!(compute_block_hash(&b1).0, {
+            let mut h = Sha256::new();
+            h.update(b1.canonical_string().as_bytes());
+            format!("{:x}", h.finalize())
+        });
+        assert!(verify_chain(&[b1, b2]).is_ok());
+    }
+
+    #[test]
+    fn test_fields_version_chain_still_verifies() {
+        let mut b1 = make_genesis_block();
+        b1.hash_version = HASH_VERSION_FIELDS;
+        let mut b2 = make_successor(&b1);
+        b2.hash_version = HASH_VERSION_CURRENT;
+        b2.refs.push(BlockRef {
+            kind: BlockRefKind::CausedBy,
+            block_id: b1.id,
+        });
+        assert!(verify_chain(&[b1, b2]).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_hash_version_rejected() {
+        let mut b1 = make_genesis_block();
+        b1.hash_version = HASH_VERSION_CURRENT + 1;
+        assert!(verify_chain(&[b1]).is_err());
+    }
+
+    #[test]
+    fn test_bookkeeping_findings_do_not_fail_verification() {
+        let b1 = make_genesis_block();
+        let mut b2 = make_successor(&b1);
+        b2.sequence_number += 1;
+        b2.timestamp_utc = b1.timestamp_utc - chrono::Duration::seconds(1);
+        assert!(verify_chain(&[b1.clone(), b2.clone()]).is_ok());
+        let tenant = b1.tenant_id;
+        let findings = scan_chain(&tenant, &[b1, b2], &ChainHead::genesis());
+        assert_eq!(findings[0].anomalies.len(), 2);
+        assert!(!findings[0].anomalies.iter().any(ChainAnomaly::breaks_chain));
+    }
+
+    #[test]
+    fn test_verify_from_trusted_head() {
+        let b1 = make_genesis_block();
+        let b2 = make_successor(&b1);
+        let b3 = make_successor(&b2);
+        assert!(verify_chain_from(&[b2.clone(), b3.clone()], &ChainHead::of(&b1)).is_ok());
+        assert!(verify_chain_from(&[b3], &ChainHead::of(&b1)).is_err());
+        assert!(verify_chain(&[b2]).is_err());
+    }
 }
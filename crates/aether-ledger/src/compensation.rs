@@ -0,0 +1,235 @@
+//! Typed block references and compensation status (PRD §14).
+//!
+//! Blocks can point at earlier blocks of the same tenant: a `Compensation`
+//! block `compensates` the action it undoes, any block may record what it
+//! was `caused_by`, and an action can cite the `HumanReview` block that
+//! `approved_by` it. `validate_refs` enforces these rules before an append;
+//! `compensation_status` answers "was this block rolled back, and by what?"
+//! from the ledger alone.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use aether_core::error::{AetherError, ErrorCode, Result};
+use aether_core::ids::{LedgerBlockId, TenantId};
+use aether_core::ledger::{BlockRef, BlockRefKind, LedgerAction, LedgerBlock};
+
+use crate::query::LedgerQuery;
+use crate::storage::LedgerStorage;
+
+/// Whether a block has been undone by a `Compensation` block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CompensationStatus {
+    NotCompensated,
+    Compensated {
+        compensation_block_id: LedgerBlockId,
+        sequence_number: u64,
+        timestamp_utc: DateTime<Utc>,
+    },
+}
+
+/// Compensation status of `block_id` within `tenant_id`'s chain.
+///
+/// # Errors
+/// Returns `NotFound` if the block does not belong to the tenant.
+pub fn compensation_status<S: LedgerStorage + ?Sized>(
+    storage: &S,
+    tenant_id: &TenantId,
+    block_id: &LedgerBlockId,
+) -> Result<CompensationStatus> {
+    tenant_block(storage, tenant_id, block_id)?;
+    let reference = BlockRef {
+        kind: BlockRefKind::Compensates,
+        block_id: *block_id,
+    };
+    let page = storage.query(
+        tenant_id,
+        &LedgerQuery::new().referencing(reference).limit(1),
+    )?;
+    Ok(match page.blocks.first() {
+        Some(c) => CompensationStatus::Compensated {
+            compensation_block_id: c.id,
+            sequence_number: c.sequence_number,
+            timestamp_utc: c.timestamp_utc,
+        },
+        None => CompensationStatus::NotCompensated,
+    })
+}
+
+/// Check the references a new block will carry.
+///
+/// Every target must be an existing block of the same tenant. `Compensates`
+/// is only valid on `Compensation` blocks, a block carries at most one, and
+/// each block can be compensated once; `ApprovedBy` must point at a
+/// `HumanReview` block.
+///
+/// # Errors
+/// Returns `ValidationFailed` for a bad reference and `Conflict` if the
+/// target was already compensated.
+pub fn validate_refs<S: LedgerStorage + ?Sized>(
+    storage: &S,
+    tenant_id: &TenantId,
+    action: &LedgerAction,
+    refs: &[BlockRef],
+) -> Result<()> {
+    let mut compensates = refs.iter().filter(|r| r.kind == BlockRefKind::Compensates);
+    if let (Some(_), Some(extra)) = (compensates.next(), compensates.next()) {
+        return Err(invalid(
+            extra,
+            "a block may compensate only one block".into(),
+        ));
+    }
+    for r in refs {
+        let target = tenant_block(storage, tenant_id, &r.block_id).map_err(|e| match e.code() {
+            ErrorCode::NotFound => invalid(r, "target block not found for this tenant".into()),
+            _ => e,
+        })?;
+        match r.kind {
+            BlockRefKind::Compensates if *action != LedgerAction::Compensation => {
+                return Err(invalid(r, "only COMPENSATION blocks may compensate".into()));
+            }
+            BlockRefKind::Compensates => {
+                let status = compensation_status(storage, tenant_id, &r.block_id)?;
+                if let CompensationStatus::Compensated {
+                    compensation_block_id,
+                    ..
+                } = status
+                {
+                    return Err(AetherError::Conflict(format!(
+                        "block {} already compensated by {compensation_block_id}",
+                        r.block_id
+                    )));
+                }
+            }
+            BlockRefKind::ApprovedBy if target.action != LedgerAction::HumanReview => {
+                return Err(invalid(
+                    r,
+                    "approval must reference a HUMAN_REVIEW block".into(),
+                ));
+            }
+            BlockRefKind::ApprovedBy | BlockRefKind::CausedBy => {}
+        }
+    }
+    Ok(())
+}
+
+fn tenant_block<S: LedgerStorage + ?Sized>(
+    storage: &S,
+    tenant_id: &TenantId,
+    block_id: &LedgerBlockId,
+) -> Result<LedgerBlock> {
+    let block = storage.get_block(block_id)?;
+    if block.tenant_id != *tenant_id {
+        return Err(AetherError::not_found("LedgerBlock", block_id));
+    }
+    Ok(block)
+}
+
+fn invalid(r: &BlockRef, reason: String) -> AetherError {
+    AetherError::ValidationFailed {
+        field: format!("refs.{}", r.kind.as_str()),
+        reason: format!("{reason} ({})", r.block_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::LedgerBlockBuilder;
+    use crate::storage::InMemoryLedgerStorage;
+    use crate::writer::LedgerWriter;
+    use aether_core::ids::{AgentId, TaskId};
+
+    fn entry(tenant: TenantId, action: LedgerAction) -> LedgerBlockBuilder {
+        LedgerBlockBuilder::unlinked(tenant, AgentId::new(), TaskId::new(), action)
+    }
+
+    #[test]
+    fn test_compensation_is_provable() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let t = TenantId::new();
+        let deploy = writer.append(entry(t, LedgerAction::Deploy)).unwrap();
+        let status = compensation_status(writer.storage(), &t, &deploy.id).unwrap();
+        assert_eq!(status, CompensationStatus::NotCompensated);
+
+        let undo = writer
+            .append(entry(t, LedgerAction::Compensation).compensates(deploy.id))
+            .unwrap();
+        let status = compensation_status(writer.storage(), &t, &deploy.id).unwrap();
+        assert!(matches!(
+            status,
+            CompensationStatus::Compensated { compensation_block_id, .. }
+                if compensation_block_id == undo.id
+        ));
+    }
+
+    #[test]
+    fn test_double_compensation_conflicts() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let t = TenantId::new();
+        let deploy = writer.append(entry(t, LedgerAction::Deploy)).unwrap();
+        writer
+            .append(entry(t, LedgerAction::Compensation).compensates(deploy.id))
+            .unwrap();
+        let err = writer
+            .append(entry(t, LedgerAction::Compensation).compensates(deploy.id))
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Conflict);
+    }
+
+    #[test]
+    fn test_multiple_compensates_refs_rejected() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let t = TenantId::new();
+        let a = writer.append(entry(t, LedgerAction::Deploy)).unwrap();
+        let b = writer.append(entry(t, LedgerAction::Deploy)).unwrap();
+        let duplicate = entry(t, LedgerAction::Compensation)
+            .compensates(a.id)
+            .compensates(a.id);
+        let multiple = entry(t, LedgerAction::Compensation)
+            .compensates(a.id)
+            .compensates(b.id);
+        for builder in [duplicate, multiple] {
+            let err = writer.append(builder).unwrap_err();
+            assert_eq!(err.code(), ErrorCode::ValidationFailed);
+        }
+        assert_eq!(writer.storage().count(&t).unwrap(), 2);
+        let status = compensation_status(writer.storage(), &t, &a.id).unwrap();
+        assert_eq!(status, CompensationStatus::NotCompensated);
+    }
+
+    #[test]
+    fn test_invalid_references_rejected() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let (t, other) = (TenantId::new(), TenantId::new());
+        let deploy = writer.append(entry(t, LedgerAction::Deploy)).unwrap();
+        let foreign = writer
+            .append(entry(other, LedgerAction::HumanReview))
+            .unwrap();
+        let bad = [
+            entry(t, LedgerAction::ToolCall).compensates(deploy.id),
+            entry(t, LedgerAction::ToolCall).approved_by(deploy.id),
+            entry(t, LedgerAction::ToolCall).approved_by(foreign.id),
+            entry(t, LedgerAction::ToolCall).caused_by(LedgerBlockId::new()),
+        ];
+        for builder in bad {
+            let err = writer.append(builder).unwrap_err();
+            assert_eq!(err.code(), ErrorCode::ValidationFailed);
+        }
+        assert_eq!(writer.storage().count(&t).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_approval_reference_accepted() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let t = TenantId::new();
+        let review = writer.append(entry(t, LedgerAction::HumanReview)).unwrap();
+        let deploy = writer
+            .append(entry(t, LedgerAction::Deploy).approved_by(review.id))
+            .unwrap();
+        assert_eq!(deploy.ref_of(BlockRefKind::ApprovedBy), Some(review.id));
+        let other = TenantId::new();
+        assert!(compensation_status(writer.storage(), &other, &deploy.id).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! Trace diff between two task executions (PRD §14).
+//!
+//! Backs `GET /ledger/diff/{a}/{b}`. Steps are aligned by `(action, tool_id)`
+//! using a longest-common-subsequence match, so an extra tool call in one run
+//! shows up as a single inserted step instead of shifting every later step.
+//! Aligned steps are compared by input/output hash and by their time offset
+//! from the start of their task.
+
+use serde::{Deserialize, Serialize};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{LedgerBlockId, TaskId, TenantId, ToolId};
+use aether_core::ledger::{BlockHash, LedgerAction, LedgerBlock};
+
+use crate::query::{query_all, LedgerQuery};
+use crate::storage::LedgerStorage;
+
+/// How one aligned step differs between the two runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepChange {
+    /// Same action and tool with identical input and output.
+    Same,
+    /// Same action and tool, but input and/or output differ.
+    Changed,
+    /// Only present in the right-hand run.
+    Inserted,
+    /// Only present in the left-hand run.
+    Removed,
+}
+
+/// One side of an aligned step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub block_id: LedgerBlockId,
+    pub sequence_number: u64,
+    pub action: LedgerAction,
+    pub tool_id: Option<ToolId>,
+    pub input_hash: BlockHash,
+    pub output_hash: BlockHash,
+    /// Milliseconds since the first block of the task.
+    pub offset_ms: i64,
+}
+
+/// An aligned pair of steps (either side may be absent).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepDiff {
+    pub change: StepChange,
+    pub left: Option<TraceStep>,
+    pub right: Option<TraceStep>,
+    pub input_changed: bool,
+    pub output_changed: bool,
+    /// `right.offset_ms - left.offset_ms` for aligned steps.
+    pub offset_delta_ms: Option<i64>,
+}
+
+/// Step counts per change kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub same: usize,
+    pub changed: usize,
+    pub inserted: usize,
+    pub removed: usize,
+}
+
+/// Structured diff of two task executions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceDiff {
+    pub tenant_id: TenantId,
+    pub left_task: TaskId,
+    pub right_task: TaskId,
+    pub steps: Vec<StepDiff>,
+    pub summary: DiffSummary,
+    /// Wall-clock span of each run, first to last block.
+    pub left_duration_ms: i64,
+    pub right_duration_ms: i64,
+}
+
+impl TraceDiff {
+    /// Whether both runs performed the same steps with the same data.
+    pub fn is_identical(&self) -> bool {
+        self.steps.iter().all(|s| s.change == StepChange::Same)
+    }
+}
+
+/// Diff two tasks of the same tenant.
+///
+/// # Errors
+/// Returns `NotFound` if either task has no blocks for the tenant.
+pub fn diff_tasks<S: LedgerStorage + ?Sized>(
+    storage: &S,
+    tenant_id: &TenantId,
+    left_task: &TaskId,
+    right_task: &TaskId,
+) -> Result<TraceDiff> {
+    let task_blocks = |task: &TaskId| -> Result<Vec<LedgerBlock>> {
+        let selected = query_all(storage, tenant_id, &LedgerQuery::new().task(*task))?;
+        if selected.is_empty() {
+            return Err(AetherError::not_found("ledger task", task));
+        }
+        Ok(selected)
+    };
+    let (left, right) = (task_blocks(left_task)?, task_blocks(right_task)?);
+    let steps = diff_traces(&left, &right);
+    Ok(TraceDiff {
+        tenant_id: *tenant_id,
+        left_task: *left_task,
+        right_task: *right_task,
+        summary: summarize(&steps),
+        steps,
+        left_duration_ms: duration_ms(&left),
+        right_duration_ms: duration_ms(&right),
+    })
+}
+
+/// Align and compare two block sequences, each in chain order.
+pub fn diff_traces(left: &[LedgerBlock], right: &[LedgerBlock]) -> Vec<StepDiff> {
+    let (left, right) = (trace_steps(left), trace_steps(right));
+    let lcs = lcs_table(&left, &right);
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(left.len().max(right.len()));
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && same_kind(&left[i], &right[j]) {
+            out.push(aligned(left[i].clone(), right[j].clone()));
+            i += 1;
+            j += 1;
+        } else if j < right.len() && (i == left.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push(unaligned(
+                StepChange::Inserted,
+                None,
+                Some(right[j].clone()),
+            ));
+            j += 1;
+        } else {
+            out.push(unaligned(StepChange::Removed, Some(left[i].clone()), None));
+            i += 1;
+        }
+    }
+    out
+}
+
+fn trace_steps(blocks: &[LedgerBlock]) -> Vec<TraceStep> {
+    let Some(start) = blocks.first().map(|b| b.timestamp_utc) else {
+        return Vec::new();
+    };
+    blocks
+        .iter()
+        .map(|b| TraceStep {
+            block_id: b.id,
+            sequence_number: b.sequence_number,
+            action: b.action.clone(),
+            tool_id: b.tool_id,
+            input_hash: b.input_hash.clone(),
+            output_hash: b.output_hash.clone(),
+            offset_ms: (b.timestamp_utc - start).num_milliseconds(),
+        })
+        .collect()
+}
+
+fn same_kind(a: &TraceStep, b: &TraceStep) -> bool {
+    a.action == b.action && a.tool_id == b.tool_id
+}
+
+/// `table[i][j]` = LCS length of `left[i..]` and `right[j..]`.
+fn lcs_table(left: &[TraceStep], right: &[TraceStep]) -> Vec<Vec<usize>> {
+    let mut table = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            table[i][j] = if same_kind(&left[i], &right[j]) {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    table
+}
+
+fn aligned(left: TraceStep, right: TraceStep) -> StepDiff {
+    let input_changed = left.input_hash != right.input_hash;
+    let output_changed = left.output_hash != right.output_hash;
+    let change = if input_changed || output_changed {
+        StepChange::Changed
+    } else {
+        StepChange::Same
+    };
+    StepDiff {
+        change,
+        offset_delta_ms: Some(right.offset_ms - left.offset_ms),
+        left: Some(left),
+        right: Some(right),
+        input_changed,
+        output_changed,
+    }
+}
+
+fn unaligned(change: StepChange, left: Option<TraceStep>, right: Option<TraceStep>) -> StepDiff {
+    StepDiff {
+        change,
+        left,
+        right,
+        input_changed: false,
+        output_changed: false,
+        offset_delta_ms: None,
+    }
+}
+
+fn summarize(steps: &[StepDiff]) -> DiffSummary {
+    let mut summary = DiffSummary::default();
+    for step in steps {
+        match step.change {
+            StepChange::Same => summary.same += 1,
+            StepChange::Changed => summary.changed += 1,
+            StepChange::Inserted => summary.inserted += 1,
+            StepChange::Removed => summary.removed += 1,
+        }
+    }
+    summary
+}
+
+fn duration_ms(blocks: &[LedgerBlock]) -> i64 {
+    match (blocks.first(), blocks.last()) {
+        (Some(first), Some(last)) => (last.timestamp_utc - first.timestamp_utc).num_milliseconds(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::LedgerBlockBuilder;
+    use crate::storage::InMemoryLedgerStorage;
+    use crate::writer::LedgerWriter;
+    use aether_core::error::ErrorCode;
+    use aether_core::ids::AgentId;
+    use serde_json::json;
+
+    struct Run {
+        tenant: TenantId,
+        task: TaskId,
+    }
+
+    impl Run {
+        fn step(&self, action: LedgerAction, tool: Option<ToolId>, out: i64) -> LedgerBlockBuilder {
+            let b = LedgerBlockBuilder::unlinked(self.tenant, AgentId::new(), self.task, action)
+                .input(json!({"prompt": "same"}))
+                .output(json!({"value": out}));
+            match tool {
+                Some(t) => b.tool_id(t),
+                None => b,
+            }
+        }
+    }
+
+    fn changes(diff: &[StepDiff]) -> Vec<StepChange> {
+        diff.iter().map(|s| s.change).collect()
+    }
+
+    #[test]
+    fn test_identical_runs_are_same() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let tenant = TenantId::new();
+        let (a, b) = (
+            Run {
+                tenant,
+                task: TaskId::new(),
+            },
+            Run {
+                tenant,
+                task: TaskId::new(),
+            },
+        );
+        let search = ToolId::new();
+        for run in [&a, &b] {
+            writer
+                .append(run.step(LedgerAction::ToolCall, Some(search), 1))
+                .unwrap();
+            writer
+                .append(run.step(LedgerAction::MemoryWrite, None, 2))
+                .unwrap();
+        }
+        let diff = diff_tasks(writer.storage(), &tenant, &a.task, &b.task).unwrap();
+        assert!(diff.is_identical());
+        assert_eq!(diff.summary.same, 2);
+    }
+
+    #[test]
+    fn test_inserted_removed_and_changed_steps() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let tenant = TenantId::new();
+        let (a, b) = (
+            Run {
+                tenant,
+                task: TaskId::new(),
+            },
+            Run {
+                tenant,
+                task: TaskId::new(),
+            },
+        );
+        let (search, fetch) = (ToolId::new(), ToolId::new());
+        writer
+            .append(a.step(LedgerAction::ToolCall, Some(search), 1))
+            .unwrap();
+        writer
+            .append(a.step(LedgerAction::ToolCall, Some(fetch), 2))
+            .unwrap();
+        writer
+            .append(a.step(LedgerAction::MemoryWrite, None, 3))
+            .unwrap();
+        writer
+            .append(b.step(LedgerAction::ToolCall, Some(search), 1))
+            .unwrap();
+        writer
+            .append(b.step(LedgerAction::MemoryWrite, None, 4))
+            .unwrap();
+        writer
+            .append(b.step(LedgerAction::Deploy, None, 5))
+            .unwrap();
+
+        let diff = diff_tasks(writer.storage(), &tenant, &a.task, &b.task).unwrap();
+        assert_eq!(
+            changes(&diff.steps),
+            vec![
+                StepChange::Same,
+                StepChange::Removed,
+                StepChange::Changed,
+                StepChange::Inserted
+            ]
+        );
+        assert!(diff.steps[2].output_changed && !diff.steps[2].input_changed);
+        assert!(diff.steps[2].offset_delta_ms.is_some());
+        assert_eq!(
+            diff.summary,
+            DiffSummary {
+                same: 1,
+                changed: 1,
+                inserted: 1,
+                removed: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_serializes_for_api() {
+        let diff = diff_traces(&[], &[]);
+        assert!(diff.is_empty());
+        let change = serde_json::to_value(StepChange::Inserted).unwrap();
+        assert_eq!(change, json!("inserted"));
+    }
+
+    #[test]
+    fn test_other_tenant_task_not_found() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let a = Run {
+            tenant: TenantId::new(),
+            task: TaskId::new(),
+        };
+        writer
+            .append(a.step(LedgerAction::ToolCall, None, 1))
+            .unwrap();
+        let other = TenantId::new();
+        let err = diff_tasks(writer.storage(), &other, &a.task, &a.task).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+    }
+}
@@ -0,0 +1,392 @@
+//! Durable append-only ledger storage on local segment files (PRD §14).
+//!
+//! Layout: `{root}/{tenant_id}/{first_sequence:020}.seg`, one directory per
+//! tenant. Appends are write-ahead: a record reaches the segment file (and is
+//! fsynced per `FsyncPolicy`) before the block becomes visible to readers.
+//!
+//! On open, every segment is replayed. A torn record at the tail of a tenant's
+//! newest segment (crash mid-write) is truncated away; corruption anywhere else
+//! is reported as a storage error. A failed append is rolled back to the last
+//! good record before the error is returned; if even that fails, the tenant's
+//! log refuses further appends until the storage is reopened. Read-only mode
+//! never modifies files, so it is safe for offline audits of a copied ledger
+//! directory.
+//!
+//! Pruning works at segment granularity; see `tenant_log`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{LedgerBlockId, TenantId};
+use aether_core::ledger::LedgerBlock;
+
+use crate::prune::PruneCheckpoint;
+use crate::segment::io_error;
+use crate::storage::LedgerStorage;
+use crate::tenant_log::TenantLog;
+
+/// When appended records are flushed to stable storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// fsync after every append (zero data loss).
+    Always,
+    /// fsync after every N appends per tenant.
+    EveryN(u32),
+    /// Leave flushing to the OS.
+    Never,
+}
+
+/// Tuning for `FileLedgerStorage`.
+#[derive(Debug, Clone)]
+pub struct FileLedgerConfig {
+    pub fsync: FsyncPolicy,
+    /// Roll over to a new segment once the active one reaches this size.
+    pub max_segment_bytes: u64,
+}
+
+impl Default for FileLedgerConfig {
+    fn default() -> Self {
+        Self {
+            fsync: FsyncPolicy::Always,
+            max_segment_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Segment-file implementation of `LedgerStorage`.
+pub struct FileLedgerStorage {
+    root: PathBuf,
+    config: FileLedgerConfig,
+    read_only: bool,
+    tenants: RwLock<HashMap<TenantId, TenantLog>>,
+}
+
+impl FileLedgerStorage {
+    /// Open (or create) a writable ledger at `root`, recovering torn tails.
+    ///
+    /// # Errors
+    /// Returns `StorageError` on I/O failure or mid-chain corruption.
+    pub fn open(root: impl Into<PathBuf>, config: FileLedgerConfig) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root).map_err(io_error)?;
+        Self::load(root, config, false)
+    }
+
+    /// Open an existing ledger for reading only; appends are rejected.
+    ///
+    /// # Errors
+    /// Returns `StorageError` if `root` is unreadable or corrupt.
+    pub fn open_read_only(root: impl Into<PathBuf>) -> Result<Self> {
+        Self::load(root.into(), FileLedgerConfig::default(), true)
+    }
+
+    fn load(root: PathBuf, config: FileLedgerConfig, read_only: bool) -> Result<Self> {
+        let mut tenants = HashMap::new();
+        for entry in fs::read_dir(&root).map_err(io_error)? {
+            let dir = entry.map_err(io_error)?.path();
+            let Some(tenant_id) = dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.parse::<TenantId>().ok())
+            else {
+                continue;
+            };
+            tenants.insert(tenant_id, TenantLog::recover(&dir, read_only)?);
+        }
+        Ok(Self {
+            root,
+            config,
+            read_only,
+            tenants: RwLock::new(tenants),
+        })
+    }
+
+    fn tenant_dir(&self, tenant_id: &TenantId) -> PathBuf {
+        self.root.join(tenant_id.to_string())
+    }
+}
+
+fn lock_poisoned(e: impl std::fmt::Display) -> AetherError {
+    AetherError::internal(format!("ledger lock poisoned: {e}"))
+}
+
+impl LedgerStorage for FileLedgerStorage {
+    fn append(&self, block: LedgerBlock) -> Result<()> {
+        if self.read_only {
+            return Err(AetherError::StorageError("ledger opened read-only".into()));
+        }
+        let mut tenants = self.tenants.write().map_err(lock_poisoned)?;
+        let log = tenants.entry(block.tenant_id).or_default();
+        log.write(&self.tenant_dir(&block.tenant_id), &self.config, &block)?;
+        log.blocks.push(block);
+        Ok(())
+    }
+
+    fn get_blocks(&self, tenant_id: &TenantId) -> Result<Vec<LedgerBlock>> {
+        let tenants = self.tenants.read().map_err(lock_poisoned)?;
+        Ok(tenants
+            .get(tenant_id)
+            .map(|l| l.blocks.clone())
+            .unwrap_or_default())
+    }
+
+    fn get_block(&self, block_id: &LedgerBlockId) -> Result<LedgerBlock> {
+        let tenants = self.tenants.read().map_err(lock_poisoned)?;
+        tenants
+            .values()
+            .flat_map(|l| l.blocks.iter())
+            .find(|b| b.id == *block_id)
+            .cloned()
+            .ok_or_else(|| AetherError::not_found("LedgerBlock", block_id))
+    }
+
+    fn count(&self, tenant_id: &TenantId) -> Result<u64> {
+        let tenants = self.tenants.read().map_err(lock_poisoned)?;
+        Ok(tenants
+            .get(tenant_id)
+            .map(|l| l.blocks.len() as u64)
+            .unwrap_or(0))
+    }
+
+    fn head(&self, tenant_id: &TenantId) -> Result<Option<LedgerBlock>> {
+        let tenants = self.tenants.read().map_err(lock_poisoned)?;
+        Ok(tenants
+            .get(tenant_id)
+            .and_then(|l| l.blocks.last().cloned()))
+    }
+
+    fn prune_boundary(&self, tenant_id: &TenantId, through: u64) -> Result<Option<u64>> {
+        let tenants = self.tenants.read().map_err(lock_poisoned)?;
+        Ok(tenants
+            .get(tenant_id)
+            .and_then(|l| l.prune_boundary(through)))
+    }
+
+    fn prune(&self, checkpoint: &PruneCheckpoint) -> Result<u64> {
+        if self.read_only {
+            return Err(AetherError::StorageError("ledger opened read-only".into()));
+        }
+        let tenant_id = checkpoint.tenant_id;
+        let mut tenants = self.tenants.write().map_err(lock_poisoned)?;
+        tenants
+            .get_mut(&tenant_id)
+            .ok_or_else(|| AetherError::not_found("ledger tenant", tenant_id))?
+            .prune(&self.tenant_dir(&tenant_id), checkpoint)
+    }
+
+    fn prune_checkpoint(&self, tenant_id: &TenantId) -> Result<Option<PruneCheckpoint>> {
+        let tenants = self.tenants.read().map_err(lock_poisoned)?;
+        Ok(tenants.get(tenant_id).and_then(|l| l.checkpoint.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use crate::block::LedgerBlockBuilder;
+    use crate::prune::LedgerPruner;
+    use crate::segment::{encode_record, list_segments};
+    use crate::signing::{Ed25519Signer, LedgerKeyring};
+    use crate::storage::conformance;
+    use crate::storage::conformance::make_block;
+    use crate::verify::LedgerVerifier;
+    use crate::writer::LedgerWriter;
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::LedgerAction::ToolCall;
+
+    /// Temporary ledger directory removed on drop.
+    struct TempLedgerDir(PathBuf);
+
+    impl TempLedgerDir {
+        fn new() -> Self {
+            let name = format!("aether-ledger-{}", uuid::Uuid::new_v4());
+            Self(std::env::temp_dir().join(name))
+        }
+    }
+
+    impl Drop for TempLedgerDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn open(dir: &TempLedgerDir) -> FileLedgerStorage {
+        FileLedgerStorage::open(&dir.0, FileLedgerConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn test_conformance_suite() {
+        let dir = TempLedgerDir::new();
+        conformance::run_all(&open(&dir));
+    }
+
+    #[test]
+    fn test_blocks_survive_reopen() {
+        let dir = TempLedgerDir::new();
+        let t = TenantId::new();
+        {
+            let storage = open(&dir);
+            storage.append(make_block(t, 1)).unwrap();
+            storage.append(make_block(t, 2)).unwrap();
+        }
+        let reopened = open(&dir);
+        let blocks = reopened.get_blocks(&t).unwrap();
+        assert_eq!(
+            blocks.iter().map(|b| b.sequence_number).collect::<Vec<_>>(),
+            [1, 2]
+        );
+    }
+
+    #[test]
+    fn test_reopened_chain_still_verifies() {
+        let dir = TempLedgerDir::new();
+        let t = TenantId::new();
+        let b1 = make_block(t, 1);
+        let mut b2 = make_block(t, 2);
+        b2.parent_hash = crate::chain::compute_block_hash(&b1);
+        {
+            let storage = open(&dir);
+            storage.append(b1).unwrap();
+            storage.append(b2).unwrap();
+        }
+        let blocks = open(&dir).get_blocks(&t).unwrap();
+        assert!(crate::chain::verify_chain(&blocks).is_ok());
+    }
+
+    #[test]
+    fn test_torn_tail_truncated_on_recovery() {
+        let dir = TempLedgerDir::new();
+        let t = TenantId::new();
+        open(&dir).append(make_block(t, 1)).unwrap();
+        let segment = list_segments(&dir.0.join(t.to_string())).unwrap().remove(0);
+        let intact_len = fs::metadata(&segment).unwrap().len();
+        let partial = encode_record(&make_block(t, 2)).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&segment).unwrap();
+        file.write_all(&partial[..partial.len() / 2]).unwrap();
+
+        let recovered = open(&dir);
+        assert_eq!(recovered.count(&t).unwrap(), 1);
+        assert_eq!(fs::metadata(&segment).unwrap().len(), intact_len);
+        recovered.append(make_block(t, 2)).unwrap();
+        assert_eq!(open(&dir).count(&t).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_failed_write_rolled_back_before_next_append() {
+        let dir = TempLedgerDir::new();
+        let t = TenantId::new();
+        let storage = open(&dir);
+        storage.append(make_block(t, 1)).unwrap();
+        crate::tenant_log::FAIL_WRITE_AFTER.set(Some(7));
+        assert!(storage.append(make_block(t, 2)).is_err());
+        storage.append(make_block(t, 2)).unwrap();
+        storage.append(make_block(t, 3)).unwrap();
+        drop(storage);
+
+        let blocks = open(&dir).get_blocks(&t).unwrap();
+        assert_eq!(
+            blocks.iter().map(|b| b.sequence_number).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_mid_segment_corruption_is_not_truncated() {
+        let dir = TempLedgerDir::new();
+        let t = TenantId::new();
+        let storage = open(&dir);
+        for seq in 1..=3 {
+            storage.append(make_block(t, seq)).unwrap();
+        }
+        drop(storage);
+        let segment = list_segments(&dir.0.join(t.to_string())).unwrap().remove(0);
+        let mut bytes = fs::read(&segment).unwrap();
+        let record_len = encode_record(&make_block(t, 1)).unwrap().len();
+        bytes[record_len + record_len / 2] ^= 0xff;
+        fs::write(&segment, &bytes).unwrap();
+
+        let err = FileLedgerStorage::open(&dir.0, FileLedgerConfig::default())
+            .err()
+            .unwrap();
+        assert!(matches!(err, AetherError::StorageError(_)));
+        assert_eq!(fs::read(&segment).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_segments_roll_over() {
+        let dir = TempLedgerDir::new();
+        let config = FileLedgerConfig {
+            fsync: FsyncPolicy::EveryN(2),
+            max_segment_bytes: 1,
+        };
+        let t = TenantId::new();
+        let storage = FileLedgerStorage::open(&dir.0, config).unwrap();
+        for seq in 1..=3 {
+            storage.append(make_block(t, seq)).unwrap();
+        }
+        assert_eq!(list_segments(&dir.0.join(t.to_string())).unwrap().len(), 3);
+        assert_eq!(open(&dir).count(&t).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_read_only_rejects_append_and_keeps_torn_tail() {
+        let dir = TempLedgerDir::new();
+        let t = TenantId::new();
+        open(&dir).append(make_block(t, 1)).unwrap();
+        let segment = list_segments(&dir.0.join(t.to_string())).unwrap().remove(0);
+        let mut file = OpenOptions::new().append(true).open(&segment).unwrap();
+        file.write_all(&[0xde, 0xad]).unwrap();
+        let torn_len = fs::metadata(&segment).unwrap().len();
+
+        let audit = FileLedgerStorage::open_read_only(&dir.0).unwrap();
+        assert_eq!(audit.count(&t).unwrap(), 1);
+        assert!(audit.append(make_block(t, 2)).is_err());
+        assert_eq!(fs::metadata(&segment).unwrap().len(), torn_len);
+    }
+
+    #[test]
+    fn test_prune_drops_whole_segments_and_survives_reopen() {
+        let dir = TempLedgerDir::new();
+        let t = TenantId::new();
+        let record_len = encode_record(&make_block(t, 1)).unwrap().len() as u64;
+        // Two records per segment: segments start at 1, 3 and 5.
+        let config = FileLedgerConfig {
+            fsync: FsyncPolicy::Never,
+            max_segment_bytes: record_len * 5 / 2,
+        };
+        let storage = Arc::new(FileLedgerStorage::open(&dir.0, config).unwrap());
+        let signer = Arc::new(Ed25519Signer::from_seed("node", &[4; 32]));
+        let keyring = Arc::new(LedgerKeyring::new().with_node_signer(signer));
+        let writer = LedgerWriter::new(storage.clone()).with_keyring(keyring.clone());
+        for _ in 0..5 {
+            let (agent, task) = (AgentId::new(), TaskId::new());
+            writer
+                .append(LedgerBlockBuilder::unlinked(t, agent, task, ToolCall))
+                .unwrap();
+        }
+        let archive = Arc::new(crate::archive::InMemoryPruneArchive::new());
+        let pruner = LedgerPruner::new(storage, keyring.clone(), archive);
+        let outcome = pruner.prune_through(&t, 3).unwrap().unwrap();
+        assert_eq!(outcome.checkpoint.head.sequence_number, 2);
+        assert_eq!(list_segments(&dir.0.join(t.to_string())).unwrap().len(), 2);
+        drop((pruner, writer));
+
+        let reopened = open(&dir);
+        assert_eq!(reopened.count(&t).unwrap(), 3);
+        assert_eq!(
+            reopened.prune_checkpoint(&t).unwrap(),
+            Some(outcome.checkpoint)
+        );
+        let report = LedgerVerifier::new(reopened)
+            .verify_tenant_signed(&t, &keyring)
+            .unwrap();
+        assert_eq!(report.first_good_sequence, Some(3));
+    }
+}
@@ -2,16 +2,73 @@
 //!
 //! # Design
 //! - Blocks are SHA-256 hash-chained for tamper detection
-//! - Ed25519 signing optional (can be layered on later)
-//! - Tenant-isolated storage
-//! - In-memory storage for tests; PostgreSQL + Kafka for production
+//! - Merkle checkpoints over sequence ranges for compact inclusion proofs
+//! - Ed25519 block signatures via `LedgerKeyring` (per-tenant or node key)
+//! - Tenant-isolated storage with indexed, paginated queries
+//! - Typed block references (compensates, caused_by, approved_by)
+//! - Optional content-addressed payload store for task replay
+//! - Step-aligned trace diffs between two task executions
+//! - Signed export bundles that auditors verify offline
+//! - Forked simulation branches isolated from the real chain
+//! - Live per-tenant block subscriptions with resumable cursors
+//! - Retention pruning behind signed checkpoints the remaining chain verifies from,
+//!   archiving each pruned range before deleting it
+//! - In-memory storage for tests; segment files for single-node durability;
+//!   PostgreSQL + Kafka for production
 
+pub mod anomaly;
+pub mod archive;
 pub mod block;
+pub mod bundle;
 pub mod chain;
+pub mod compensation;
+pub mod diff;
+pub mod file_storage;
+pub mod merkle;
+pub mod payload;
+pub mod prune;
+pub mod query;
+pub mod replay;
+mod scan;
+pub mod segment;
+pub mod signing;
+pub mod signing_storage;
+pub mod simulate;
 pub mod storage;
+pub mod subscribe;
+mod tenant_log;
 pub mod verify;
+pub mod writer;
 
-pub use block::{LedgerBlockBuilder, block_to_ref};
-pub use chain::{compute_block_hash, verify_chain};
+pub use anomaly::ChainAnomaly;
+pub use archive::{InMemoryPruneArchive, PruneArchive};
+pub use block::{block_to_ref, LedgerBlockBuilder};
+pub use bundle::{
+    verify_bundle, BundleExporter, BundlePayload, BundleSignature, BundleVerification,
+    LedgerBundle, BUNDLE_FORMAT_VERSION,
+};
+pub use chain::{
+    chain_start, compute_block_hash, verify_chain, verify_chain_from, verify_chain_signed,
+    verify_chain_signed_from, ChainHead,
+};
+pub use compensation::{compensation_status, validate_refs, CompensationStatus};
+pub use diff::{diff_tasks, diff_traces, DiffSummary, StepChange, StepDiff, TraceDiff, TraceStep};
+pub use file_storage::{FileLedgerConfig, FileLedgerStorage, FsyncPolicy};
+pub use merkle::{
+    merkle_root, verify_block_inclusion, verify_inclusion, InclusionProof, MerkleCheckpoint,
+    MerkleCheckpointer, ProofStep, SiblingSide,
+};
+pub use payload::{InMemoryPayloadStore, PayloadStore};
+pub use prune::{LedgerPruner, PruneCheckpoint, PruneOutcome};
+pub use query::{query_all, LedgerQuery, QueryPage, DEFAULT_PAGE_SIZE};
+pub use replay::{ReplayEngine, ReplayStep, TaskReplay};
+pub use signing::{
+    sign_block, verify_block_signature, BlockSigner, Ed25519Signer, LedgerKeyring, PublicKeyRecord,
+    SignerRecord,
+};
+pub use signing_storage::SigningLedgerStorage;
+pub use simulate::{LedgerBranch, SimulationRecord};
 pub use storage::{InMemoryLedgerStorage, LedgerStorage};
-pub use verify::LedgerVerifier;
+pub use subscribe::{LedgerFeed, LedgerSubscription, DEFAULT_FEED_CAPACITY};
+pub use verify::{LedgerVerifier, VerificationReport};
+pub use writer::LedgerWriter;
@@ -0,0 +1,387 @@
+//! Merkle checkpoints and inclusion proofs over ledger ranges (PRD §14).
+//!
+//! A tenant's chain is cut into fixed-size sequence ranges
+//! (`[k·interval + 1, (k+1)·interval]`); each complete range gets a
+//! `MerkleCheckpoint` whose root can be published. Proving that one block
+//! happened then needs only the block, an `InclusionProof` and the root —
+//! not the tenant's whole chain.
+//!
+//! The tree follows RFC 6962: leaves and interior nodes are hashed with
+//! distinct prefixes, and an unbalanced tree splits at the largest power of
+//! two, so no leaf is ever duplicated.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{LedgerBlockId, TenantId};
+use aether_core::ledger::{BlockHash, LedgerBlock};
+
+use crate::chain::compute_block_hash;
+use crate::storage::LedgerStorage;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Published Merkle root over one complete sequence range of a tenant chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleCheckpoint {
+    pub tenant_id: TenantId,
+    pub first_sequence: u64,
+    pub last_sequence: u64,
+    pub root: BlockHash,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MerkleCheckpoint {
+    /// Whether `blocks` are exactly this checkpoint's range and hash to its root.
+    pub fn verify_blocks(&self, blocks: &[LedgerBlock]) -> bool {
+        let range: Vec<&LedgerBlock> = blocks
+            .iter()
+            .filter(|b| (self.first_sequence..=self.last_sequence).contains(&b.sequence_number))
+            .collect();
+        let expected_len = self.last_sequence.saturating_sub(self.first_sequence) + 1;
+        range.len() as u64 == expected_len
+            && range.iter().all(|b| b.tenant_id == self.tenant_id)
+            && merkle_root(
+                &range
+                    .iter()
+                    .map(|b| compute_block_hash(b))
+                    .collect::<Vec<_>>(),
+            ) == self.root
+    }
+}
+
+/// Which side of the running hash a proof sibling sits on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiblingSide {
+    Left,
+    Right,
+}
+
+/// One level of an inclusion proof, ordered leaf → root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofStep {
+    pub sibling: BlockHash,
+    pub side: SiblingSide,
+}
+
+/// Evidence that a block hash is a leaf under a checkpoint root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub block_id: LedgerBlockId,
+    /// `compute_block_hash` of the proven block.
+    pub block_hash: BlockHash,
+    pub leaf_index: u64,
+    pub leaf_count: u64,
+    pub path: Vec<ProofStep>,
+}
+
+/// Merkle root over block hashes (in chain order).
+///
+/// The empty tree's root is the hash of no input, as in RFC 6962.
+pub fn merkle_root(block_hashes: &[BlockHash]) -> BlockHash {
+    match block_hashes {
+        [] => to_hash(Sha256::digest([])),
+        [only] => leaf_hash(only),
+        _ => {
+            let k = split_point(block_hashes.len());
+            node_hash(
+                &merkle_root(&block_hashes[..k]),
+                &merkle_root(&block_hashes[k..]),
+            )
+        }
+    }
+}
+
+/// Audit path for the leaf at `index` (RFC 6962 `PATH`), ordered leaf → root.
+fn audit_path(index: usize, block_hashes: &[BlockHash]) -> Vec<ProofStep> {
+    if block_hashes.len() <= 1 {
+        return Vec::new();
+    }
+    let k = split_point(block_hashes.len());
+    let (left, right) = block_hashes.split_at(k);
+    let (mut path, sibling, side) = if index < k {
+        (
+            audit_path(index, left),
+            merkle_root(right),
+            SiblingSide::Right,
+        )
+    } else {
+        (
+            audit_path(index - k, right),
+            merkle_root(left),
+            SiblingSide::Left,
+        )
+    };
+    path.push(ProofStep { sibling, side });
+    path
+}
+
+/// Sibling sides a valid path must have for this leaf position.
+fn expected_sides(index: u64, count: u64) -> Vec<SiblingSide> {
+    if count <= 1 {
+        return Vec::new();
+    }
+    let k = split_point(count as usize) as u64;
+    let (mut sides, side) = if index < k {
+        (expected_sides(index, k), SiblingSide::Right)
+    } else {
+        (expected_sides(index - k, count - k), SiblingSide::Left)
+    };
+    sides.push(side);
+    sides
+}
+
+/// Check a proof against a published root. Needs no storage access.
+pub fn verify_inclusion(proof: &InclusionProof, root: &BlockHash) -> bool {
+    if proof.leaf_index >= proof.leaf_count {
+        return false;
+    }
+    let sides: Vec<SiblingSide> = proof.path.iter().map(|s| s.side).collect();
+    if sides != expected_sides(proof.leaf_index, proof.leaf_count) {
+        return false;
+    }
+    let computed = proof
+        .path
+        .iter()
+        .fold(leaf_hash(&proof.block_hash), |acc, step| match step.side {
+            SiblingSide::Left => node_hash(&step.sibling, &acc),
+            SiblingSide::Right => node_hash(&acc, &step.sibling),
+        });
+    computed == *root
+}
+
+/// Check that `block` itself (not just a claimed hash) is under `root`.
+pub fn verify_block_inclusion(
+    block: &LedgerBlock,
+    proof: &InclusionProof,
+    root: &BlockHash,
+) -> bool {
+    block.id == proof.block_id
+        && compute_block_hash(block) == proof.block_hash
+        && verify_inclusion(proof, root)
+}
+
+/// Cuts tenant chains into fixed-size ranges and proves membership in them.
+#[derive(Debug, Clone, Copy)]
+pub struct MerkleCheckpointer {
+    interval: u64,
+}
+
+impl MerkleCheckpointer {
+    /// `interval` = blocks per checkpoint range (minimum 1).
+    pub fn new(interval: u64) -> Self {
+        Self {
+            interval: interval.max(1),
+        }
+    }
+
+    /// Sequence range `[first, last]` that contains `sequence_number`.
+    pub fn range_for(&self, sequence_number: u64) -> (u64, u64) {
+        let first = (sequence_number.saturating_sub(1) / self.interval) * self.interval + 1;
+        (first, first + self.interval - 1)
+    }
+
+    /// Checkpoints for every complete range in `blocks` (sorted by sequence).
+    pub fn checkpoints(&self, blocks: &[LedgerBlock]) -> Vec<MerkleCheckpoint> {
+        let mut out = Vec::new();
+        let mut start = 0;
+        while start < blocks.len() {
+            let (first, last) = self.range_for(blocks[start].sequence_number);
+            let end = start + blocks[start..].partition_point(|b| b.sequence_number <= last);
+            let range = &blocks[start..end];
+            if range.len() as u64 == self.interval && range[0].sequence_number == first {
+                out.push(MerkleCheckpoint {
+                    tenant_id: range[0].tenant_id,
+                    first_sequence: first,
+                    last_sequence: last,
+                    root: merkle_root(&hashes(range)),
+                    created_at: Utc::now(),
+                });
+            }
+            start = end;
+        }
+        out
+    }
+
+    /// Build an inclusion proof for `block_id` and the checkpoint covering it.
+    ///
+    /// # Errors
+    /// Returns `NotFound` if the block is unknown or its range is not yet
+    /// complete (no checkpoint has been cut for it).
+    pub fn prove<S: LedgerStorage + ?Sized>(
+        &self,
+        storage: &S,
+        block_id: &LedgerBlockId,
+    ) -> Result<(InclusionProof, MerkleCheckpoint)> {
+        let block = storage.get_block(block_id)?;
+        let (first, last) = self.range_for(block.sequence_number);
+        let blocks = storage.get_blocks(&block.tenant_id)?;
+        let range: Vec<LedgerBlock> = blocks
+            .into_iter()
+            .filter(|b| (first..=last).contains(&b.sequence_number))
+            .collect();
+        let checkpoint = self.checkpoints(&range).pop().ok_or_else(|| {
+            AetherError::not_found("MerkleCheckpoint", format!("{first}..={last}"))
+        })?;
+        let index = range
+            .iter()
+            .position(|b| b.id == *block_id)
+            .ok_or_else(|| AetherError::not_found("LedgerBlock", block_id))?;
+        let leaves = hashes(&range);
+        let proof = InclusionProof {
+            block_id: *block_id,
+            block_hash: leaves[index].clone(),
+            leaf_index: index as u64,
+            leaf_count: leaves.len() as u64,
+            path: audit_path(index, &leaves),
+        };
+        Ok((proof, checkpoint))
+    }
+}
+
+fn hashes(blocks: &[LedgerBlock]) -> Vec<BlockHash> {
+    blocks.iter().map(compute_block_hash).collect()
+}
+
+/// Largest power of two strictly less than `n` (n ≥ 2).
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k * 2 < n {
+        k *= 2;
+    }
+    k
+}
+
+fn leaf_hash(block_hash: &BlockHash) -> BlockHash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(block_hash.0.as_bytes());
+    to_hash(hasher.finalize())
+}
+
+fn node_hash(left: &BlockHash, right: &BlockHash) -> BlockHash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left.0.as_bytes());
+    hasher.update(right.0.as_bytes());
+    to_hash(hasher.finalize())
+}
+
+fn to_hash(digest: impl std::fmt::LowerHex) -> BlockHash {
+    BlockHash(format!("{digest:x}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::LedgerBlockBuilder;
+    use crate::storage::InMemoryLedgerStorage;
+    use crate::writer::LedgerWriter;
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::LedgerAction;
+
+    fn leaves(n: usize) -> Vec<BlockHash> {
+        (0..n).map(|i| BlockHash(format!("{i:064x}"))).collect()
+    }
+
+    fn proof_for(index: usize, hashes: &[BlockHash]) -> InclusionProof {
+        InclusionProof {
+            block_id: LedgerBlockId::new(),
+            block_hash: hashes[index].clone(),
+            leaf_index: index as u64,
+            leaf_count: hashes.len() as u64,
+            path: audit_path(index, hashes),
+        }
+    }
+
+    fn writer_with_blocks(t: TenantId, n: usize) -> LedgerWriter<InMemoryLedgerStorage> {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        for i in 0..n {
+            let (agent, task) = (AgentId::new(), TaskId::new());
+            let entry = LedgerBlockBuilder::unlinked(t, agent, task, LedgerAction::ToolCall)
+                .input(serde_json::json!({ "i": i }));
+            writer.append(entry).unwrap();
+        }
+        writer
+    }
+
+    #[test]
+    fn test_every_leaf_proves_for_all_tree_sizes() {
+        for n in 1..=17 {
+            let hashes = leaves(n);
+            let root = merkle_root(&hashes);
+            for i in 0..n {
+                assert!(
+                    verify_inclusion(&proof_for(i, &hashes), &root),
+                    "n={n} i={i}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_tampered_proof_rejected() {
+        let hashes = leaves(6);
+        let root = merkle_root(&hashes);
+        let mut proof = proof_for(4, &hashes);
+        proof.path[0].sibling = BlockHash("f".repeat(64));
+        assert!(!verify_inclusion(&proof, &root));
+
+        let mut moved = proof_for(4, &hashes);
+        moved.leaf_index = 3;
+        assert!(!verify_inclusion(&moved, &root));
+    }
+
+    #[test]
+    fn test_wrong_root_rejected() {
+        let hashes = leaves(5);
+        let other_root = merkle_root(&leaves(4));
+        assert!(!verify_inclusion(&proof_for(0, &hashes), &other_root));
+    }
+
+    #[test]
+    fn test_checkpoints_cover_only_complete_ranges() {
+        let t = TenantId::new();
+        let writer = writer_with_blocks(t, 10);
+        let blocks = writer.storage().get_blocks(&t).unwrap();
+        let cps = MerkleCheckpointer::new(4).checkpoints(&blocks);
+        let ranges: Vec<(u64, u64)> = cps
+            .iter()
+            .map(|c| (c.first_sequence, c.last_sequence))
+            .collect();
+        assert_eq!(ranges, [(1, 4), (5, 8)]);
+    }
+
+    #[test]
+    fn test_prove_block_from_storage() {
+        let t = TenantId::new();
+        let writer = writer_with_blocks(t, 8);
+        let blocks = writer.storage().get_blocks(&t).unwrap();
+        let target = &blocks[5];
+        let checkpointer = MerkleCheckpointer::new(4);
+        let (proof, checkpoint) = checkpointer.prove(writer.storage(), &target.id).unwrap();
+        assert_eq!(
+            (checkpoint.first_sequence, checkpoint.last_sequence),
+            (5, 8)
+        );
+        assert!(verify_block_inclusion(target, &proof, &checkpoint.root));
+
+        let mut forged = target.clone();
+        forged.output_hash = BlockHash("e".repeat(64));
+        assert!(!verify_block_inclusion(&forged, &proof, &checkpoint.root));
+    }
+
+    #[test]
+    fn test_prove_incomplete_range_is_not_found() {
+        let t = TenantId::new();
+        let writer = writer_with_blocks(t, 6);
+        let last = writer.storage().head(&t).unwrap().unwrap();
+        assert!(MerkleCheckpointer::new(4)
+            .prove(writer.storage(), &last.id)
+            .is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! Content-addressed payload storage for ledger replay (PRD §14).
+//!
+//! Blocks only carry `input_hash`/`output_hash`. When a `PayloadStore` is
+//! attached to the `LedgerWriter`, the full JSON payloads are kept here,
+//! keyed by the same SHA-256 hash, so a task can later be replayed with its
+//! actual inputs and outputs. Payloads are scoped per tenant.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::TenantId;
+use aether_core::ledger::BlockHash;
+
+use crate::block::hash_value;
+
+/// Stores JSON payloads addressed by their ledger hash.
+pub trait PayloadStore: Send + Sync {
+    /// Store a payload and return its content hash.
+    fn put(&self, tenant_id: &TenantId, payload: &serde_json::Value) -> Result<BlockHash>;
+
+    /// Fetch a payload by hash; `None` if it was never stored.
+    fn get(&self, tenant_id: &TenantId, hash: &BlockHash) -> Result<Option<serde_json::Value>>;
+}
+
+impl<P: PayloadStore + ?Sized> PayloadStore for Arc<P> {
+    fn put(&self, tenant_id: &TenantId, payload: &serde_json::Value) -> Result<BlockHash> {
+        (**self).put(tenant_id, payload)
+    }
+
+    fn get(&self, tenant_id: &TenantId, hash: &BlockHash) -> Result<Option<serde_json::Value>> {
+        (**self).get(tenant_id, hash)
+    }
+}
+
+/// In-memory payload store — suitable for testing.
+#[derive(Default)]
+pub struct InMemoryPayloadStore {
+    /// tenant_id → hash → payload
+    payloads: RwLock<HashMap<TenantId, HashMap<String, serde_json::Value>>>,
+}
+
+impl InMemoryPayloadStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PayloadStore for InMemoryPayloadStore {
+    fn put(&self, tenant_id: &TenantId, payload: &serde_json::Value) -> Result<BlockHash> {
+        let hash = hash_value(payload);
+        let mut map = self
+            .payloads
+            .write()
+            .map_err(|e| AetherError::internal(format!("payload lock poisoned: {e}")))?;
+        map.entry(*tenant_id)
+            .or_default()
+            .entry(hash.0.clone())
+            .or_insert_with(|| payload.clone());
+        Ok(hash)
+    }
+
+    fn get(&self, tenant_id: &TenantId, hash: &BlockHash) -> Result<Option<serde_json::Value>> {
+        let map = self
+            .payloads
+            .read()
+            .map_err(|e| AetherError::internal(format!("payload lock poisoned: {e}")))?;
+        Ok(map.get(tenant_id).and_then(|m| m.get(&hash.0)).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_put_returns_content_hash() {
+        let store = InMemoryPayloadStore::new();
+        let t = TenantId::new();
+        let payload = json!({"query": "status"});
+        let hash = store.put(&t, &payload).unwrap();
+        assert_eq!(hash, hash_value(&payload));
+        assert_eq!(store.get(&t, &hash).unwrap(), Some(payload));
+    }
+
+    #[test]
+    fn test_payloads_are_tenant_scoped() {
+        let store = InMemoryPayloadStore::new();
+        let (t1, t2) = (TenantId::new(), TenantId::new());
+        let hash = store.put(&t1, &json!({"secret": 1})).unwrap();
+        assert_eq!(store.get(&t2, &hash).unwrap(), None);
+    }
+}
@@ -0,0 +1,394 @@
+//! Verifiable retention pruning (PRD §14).
+//!
+//! `verify_chain` expects a tenant's first block to link to genesis, so old
+//! blocks cannot simply be deleted. `LedgerPruner` first records a signed
+//! `PruneCheckpoint` naming the last pruned block's `(sequence, hash)` and a
+//! Merkle root over the pruned range, persists the range to a `PruneArchive`
+//! and checks the read-back copy against the root, and only then asks the
+//! backend to drop that prefix. Verification of the remaining chain starts
+//! from the checkpoint (`chain::chain_start`). The head block is never pruned, so
+//! appends keep linking normally.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Verifier};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::TenantId;
+use aether_core::ledger::{BlockHash, LedgerBlock};
+
+use crate::archive::PruneArchive;
+use crate::chain::{chain_start, compute_block_hash, verify_chain_from, ChainHead};
+use crate::merkle::merkle_root;
+use crate::signing::{decode_hex, encode_hex, LedgerKeyring};
+use crate::storage::LedgerStorage;
+
+/// Signed record of a pruned chain prefix; the new verification start.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruneCheckpoint {
+    pub tenant_id: TenantId,
+    /// First sequence number removed by this prune.
+    pub first_sequence: u64,
+    /// Last pruned block: the first remaining block must link to it.
+    pub head: ChainHead,
+    /// Merkle root over the pruned blocks' hashes, for archive checks.
+    pub pruned_root: BlockHash,
+    pub created_at: DateTime<Utc>,
+    pub key_id: String,
+    /// Hex-encoded Ed25519 signature over `digest`.
+    pub signature: String,
+}
+
+impl PruneCheckpoint {
+    /// SHA-256 over the checkpoint's JSON encoding with `signature` cleared.
+    ///
+    /// # Errors
+    /// Returns `SerializationError` if the checkpoint cannot be encoded.
+    pub fn digest(&self) -> Result<BlockHash> {
+        let unsigned = Self {
+            signature: String::new(),
+            ..self.clone()
+        };
+        let bytes = serde_json::to_vec(&unsigned)
+            .map_err(|e| AetherError::SerializationError(e.to_string()))?;
+        Ok(BlockHash(format!("{:x}", Sha256::digest(&bytes))))
+    }
+
+    /// Check the signature against a key trusted for the checkpoint's tenant.
+    ///
+    /// # Errors
+    /// Returns `LedgerIntegrityViolation` if the key is untrusted or the
+    /// signature does not match.
+    pub fn verify_signature(&self, keyring: &LedgerKeyring) -> Result<()> {
+        let violation = |reason: String| AetherError::LedgerIntegrityViolation {
+            block_id: format!("prune:{}", self.tenant_id),
+            reason,
+        };
+        let key = keyring
+            .verifying_key(&self.key_id, &self.tenant_id)
+            .ok_or_else(|| violation(format!("key '{}' is not trusted", self.key_id)))?;
+        let bytes: [u8; 64] = decode_hex(&self.signature)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| violation("malformed checkpoint signature".into()))?;
+        key.verify(self.digest()?.0.as_bytes(), &Signature::from_bytes(&bytes))
+            .map_err(|_| violation(format!("invalid checkpoint signature by '{}'", self.key_id)))
+    }
+
+    /// Whether `archived` is exactly the pruned range this checkpoint covers.
+    pub fn verify_archive(&self, archived: &[LedgerBlock]) -> bool {
+        let expected_len = self.head.sequence_number + 1 - self.first_sequence;
+        archived.len() as u64 == expected_len
+            && archived
+                .first()
+                .is_some_and(|b| b.sequence_number == self.first_sequence)
+            && archived.iter().all(|b| b.tenant_id == self.tenant_id)
+            && archived.last().map(compute_block_hash) == Some(self.head.hash.clone())
+            && merkle_root(&archived.iter().map(compute_block_hash).collect::<Vec<_>>())
+                == self.pruned_root
+    }
+}
+
+/// Result of one prune: the new checkpoint and the blocks it removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PruneOutcome {
+    pub checkpoint: PruneCheckpoint,
+    /// Removed blocks, in chain order, as persisted to the archive.
+    pub archived: Vec<LedgerBlock>,
+}
+
+/// Number of leading `blocks` a backend may drop for `checkpoint`.
+///
+/// # Errors
+/// Returns `ValidationFailed` if the checkpoint does not name a stored block
+/// of this chain, and `Conflict` if it would prune the head.
+pub(crate) fn prunable(blocks: &[LedgerBlock], checkpoint: &PruneCheckpoint) -> Result<usize> {
+    let invalid = |reason: &str| AetherError::ValidationFailed {
+        field: "prune_checkpoint".into(),
+        reason: reason.into(),
+    };
+    let through = checkpoint.head.sequence_number;
+    let pos = blocks
+        .iter()
+        .position(|b| b.sequence_number == through)
+        .ok_or_else(|| invalid("checkpoint block is not stored"))?;
+    if blocks[pos].tenant_id != checkpoint.tenant_id
+        || compute_block_hash(&blocks[pos]) != checkpoint.head.hash
+    {
+        return Err(invalid("checkpoint hash does not match the stored block"));
+    }
+    if pos + 1 == blocks.len() {
+        return Err(AetherError::Conflict(format!(
+            "cannot prune the head block of tenant {}",
+            checkpoint.tenant_id
+        )));
+    }
+    Ok(pos + 1)
+}
+
+/// Prunes old blocks behind signed checkpoints.
+pub struct LedgerPruner<S: LedgerStorage> {
+    storage: S,
+    keyring: Arc<LedgerKeyring>,
+    archive: Arc<dyn PruneArchive>,
+}
+
+impl<S: LedgerStorage> LedgerPruner<S> {
+    /// `keyring` must hold a signer for every tenant that will be pruned;
+    /// pruned blocks are persisted to `archive` before they are deleted.
+    pub fn new(storage: S, keyring: Arc<LedgerKeyring>, archive: Arc<dyn PruneArchive>) -> Self {
+        Self {
+            storage,
+            keyring,
+            archive,
+        }
+    }
+
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Prune blocks with `sequence_number <= through`.
+    ///
+    /// The head is always kept, and backends may round `through` down (the
+    /// file store only drops whole segments). Returns `None` when nothing
+    /// new can be pruned.
+    ///
+    /// # Errors
+    /// Returns `LedgerIntegrityViolation` if the chain does not verify from
+    /// its current start or the archive does not read back the pruned range
+    /// intact, `Internal` if no signer exists for the tenant, and propagates
+    /// archive and backend errors. Nothing is deleted on error.
+    pub fn prune_through(
+        &self,
+        tenant_id: &TenantId,
+        through: u64,
+    ) -> Result<Option<PruneOutcome>> {
+        let blocks = self.storage.get_blocks(tenant_id)?;
+        self.prune(tenant_id, blocks, through)
+    }
+
+    /// Prune every block timestamped strictly before `cutoff`.
+    ///
+    /// # Errors
+    /// As `prune_through`.
+    pub fn prune_before(
+        &self,
+        tenant_id: &TenantId,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Option<PruneOutcome>> {
+        let blocks = self.storage.get_blocks(tenant_id)?;
+        let older = blocks.partition_point(|b| b.timestamp_utc < cutoff);
+        match older.checked_sub(1).map(|i| blocks[i].sequence_number) {
+            Some(through) => self.prune(tenant_id, blocks, through),
+            None => Ok(None),
+        }
+    }
+
+    fn prune(
+        &self,
+        tenant_id: &TenantId,
+        blocks: Vec<LedgerBlock>,
+        through: u64,
+    ) -> Result<Option<PruneOutcome>> {
+        let start = chain_start(&self.storage, tenant_id)?;
+        let Some(head) = blocks.last() else {
+            return Ok(None);
+        };
+        let target = through.min(head.sequence_number.saturating_sub(1));
+        let Some(through) = self.storage.prune_boundary(tenant_id, target)? else {
+            return Ok(None);
+        };
+        if through <= start.sequence_number {
+            return Ok(None);
+        }
+        // Never discard evidence from a chain that no longer verifies.
+        verify_chain_from(&blocks, &start)?;
+        let pruned = &blocks[..blocks.partition_point(|b| b.sequence_number <= through)];
+        let checkpoint = self.sign(tenant_id, start.next_sequence(), pruned)?;
+        self.archive(&checkpoint, pruned)?;
+        self.storage.prune(&checkpoint)?;
+        Ok(Some(PruneOutcome {
+            checkpoint,
+            archived: pruned.to_vec(),
+        }))
+    }
+
+    /// Persist `pruned` and confirm the archive returns exactly that range.
+    fn archive(&self, checkpoint: &PruneCheckpoint, pruned: &[LedgerBlock]) -> Result<()> {
+        self.archive.store(checkpoint, pruned)?;
+        let stored = self.archive.load(checkpoint)?.unwrap_or_default();
+        if checkpoint.verify_archive(&stored) {
+            return Ok(());
+        }
+        Err(AetherError::LedgerIntegrityViolation {
+            block_id: format!("prune:{}", checkpoint.tenant_id),
+            reason: "archive did not return the pruned range intact".into(),
+        })
+    }
+
+    fn sign(
+        &self,
+        tenant_id: &TenantId,
+        first_sequence: u64,
+        pruned: &[LedgerBlock],
+    ) -> Result<PruneCheckpoint> {
+        let signer = self.keyring.signer_for(tenant_id).ok_or_else(|| {
+            AetherError::internal(format!("no ledger signing key for tenant {tenant_id}"))
+        })?;
+        let last = pruned
+            .last()
+            .ok_or_else(|| AetherError::internal("empty prune range"))?;
+        let mut checkpoint = PruneCheckpoint {
+            tenant_id: *tenant_id,
+            first_sequence,
+            head: ChainHead::of(last),
+            pruned_root: merkle_root(&pruned.iter().map(compute_block_hash).collect::<Vec<_>>()),
+            created_at: Utc::now(),
+            key_id: signer.key_id().to_string(),
+            signature: String::new(),
+        };
+        let signature = signer.sign(checkpoint.digest()?.0.as_bytes());
+        checkpoint.signature = encode_hex(&signature.to_bytes());
+        Ok(checkpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::InMemoryPruneArchive;
+    use crate::block::LedgerBlockBuilder;
+    use crate::chain::verify_chain;
+    use crate::signing::Ed25519Signer;
+    use crate::storage::InMemoryLedgerStorage;
+    use crate::verify::LedgerVerifier;
+    use crate::writer::LedgerWriter;
+    use aether_core::error::ErrorCode;
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::LedgerAction;
+
+    type Storage = Arc<InMemoryLedgerStorage>;
+
+    fn keyring(seed: u8) -> Arc<LedgerKeyring> {
+        let signer = Arc::new(Ed25519Signer::from_seed("node", &[seed; 32]));
+        Arc::new(LedgerKeyring::new().with_node_signer(signer))
+    }
+
+    fn chain(tenant: TenantId, len: usize) -> (Storage, LedgerPruner<Storage>) {
+        let storage = Arc::new(InMemoryLedgerStorage::new());
+        let writer = LedgerWriter::new(storage.clone()).with_keyring(keyring(1));
+        for _ in 0..len {
+            let (agent, task) = (AgentId::new(), TaskId::new());
+            let entry = LedgerBlockBuilder::unlinked(tenant, agent, task, LedgerAction::ToolCall);
+            writer.append(entry).unwrap();
+        }
+        let archive = Arc::new(InMemoryPruneArchive::new());
+        (
+            storage.clone(),
+            LedgerPruner::new(storage, keyring(1), archive),
+        )
+    }
+
+    #[test]
+    fn test_pruned_chain_verifies_from_checkpoint() {
+        let t = TenantId::new();
+        let (storage, pruner) = chain(t, 5);
+        let outcome = pruner.prune_through(&t, 3).unwrap().unwrap();
+        assert_eq!(outcome.archived.len(), 3);
+        assert!(outcome.checkpoint.verify_archive(&outcome.archived));
+
+        let remaining = storage.get_blocks(&t).unwrap();
+        assert_eq!(remaining.first().map(|b| b.sequence_number), Some(4));
+        assert!(verify_chain(&remaining).is_err());
+        let verifier = LedgerVerifier::new(storage.clone());
+        let signed = verifier.verify_tenant_signed(&t, &keyring(1)).unwrap();
+        assert!(signed.intact && !signed.unverified_checkpoint);
+        let scanned = verifier.scan_tenant(&t).unwrap();
+        assert!(scanned.intact && scanned.unverified_checkpoint);
+        assert!(verifier.verify_tenant(&t).unwrap().unverified_checkpoint);
+    }
+
+    #[test]
+    fn test_head_is_never_pruned() {
+        let t = TenantId::new();
+        let (storage, pruner) = chain(t, 3);
+        let outcome = pruner.prune_before(&t, Utc::now()).unwrap().unwrap();
+        assert_eq!(outcome.checkpoint.head.sequence_number, 2);
+        assert_eq!(storage.count(&t).unwrap(), 1);
+        assert!(pruner.prune_through(&t, u64::MAX).unwrap().is_none());
+
+        let head = storage.head(&t).unwrap().unwrap();
+        let at_head = PruneCheckpoint {
+            head: ChainHead::of(&head),
+            ..outcome.checkpoint
+        };
+        assert_eq!(
+            storage.prune(&at_head).unwrap_err().code(),
+            ErrorCode::Conflict
+        );
+    }
+
+    #[test]
+    fn test_repeated_prunes_chain_checkpoints() {
+        let t = TenantId::new();
+        let (storage, pruner) = chain(t, 6);
+        let first = pruner.prune_through(&t, 2).unwrap().unwrap();
+        let second = pruner.prune_through(&t, 4).unwrap().unwrap();
+        assert_eq!(
+            second.checkpoint.first_sequence,
+            first.checkpoint.head.next_sequence()
+        );
+        assert!(second.checkpoint.verify_archive(&second.archived));
+        assert!(!second.checkpoint.verify_archive(&first.archived));
+        assert_eq!(
+            storage.prune_checkpoint(&t).unwrap(),
+            Some(second.checkpoint)
+        );
+        assert!(LedgerVerifier::new(storage).verify_tenant(&t).is_ok());
+    }
+
+    #[test]
+    fn test_forged_checkpoint_fails_signed_verification() {
+        let t = TenantId::new();
+        let (storage, pruner) = chain(t, 4);
+        let outcome = pruner.prune_through(&t, 2).unwrap().unwrap();
+        assert!(outcome.checkpoint.verify_signature(&keyring(2)).is_err());
+        let mut forged = outcome.checkpoint;
+        forged.first_sequence = 2;
+        assert!(forged.verify_signature(&keyring(1)).is_err());
+        let verifier = LedgerVerifier::new(storage);
+        assert!(verifier.verify_tenant_signed(&t, &keyring(2)).is_err());
+    }
+
+    #[test]
+    fn test_prune_is_tenant_isolated() {
+        let t = TenantId::new();
+        let (storage, pruner) = chain(t, 3);
+        let other = TenantId::new();
+        let writer = LedgerWriter::new(storage.clone());
+        for _ in 0..3 {
+            let (agent, task) = (AgentId::new(), TaskId::new());
+            writer
+                .append(LedgerBlockBuilder::unlinked(
+                    other,
+                    agent,
+                    task,
+                    LedgerAction::Deploy,
+                ))
+                .unwrap();
+        }
+        let outcome = pruner.prune_through(&t, 2).unwrap().unwrap();
+        assert_eq!(storage.count(&other).unwrap(), 3);
+        assert!(storage.prune_checkpoint(&other).unwrap().is_none());
+
+        let foreign = PruneCheckpoint {
+            tenant_id: other,
+            ..outcome.checkpoint
+        };
+        let err = storage.prune(&foreign).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::ValidationFailed);
+    }
+}
@@ -0,0 +1,387 @@
+//! Filtered, paginated ledger queries (PRD §14).
+//!
+//! `LedgerQuery` filters a tenant's blocks by agent, task, action, tool,
+//! typed reference and time window, returning pages in sequence order. The
+//! `next_cursor` of a page is the last returned sequence number; pass it to
+//! `after` to resume.
+//! `BlockIndex` keeps per-tenant secondary indexes, including an ordered
+//! timestamp index for time windows, so backends can answer queries without
+//! scanning the whole chain.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use aether_core::error::Result;
+use aether_core::ids::{AgentId, LedgerBlockId, TaskId, TenantId, ToolId};
+use aether_core::ledger::{BlockRef, LedgerAction, LedgerBlock};
+
+use crate::storage::LedgerStorage;
+
+/// Page size used when a query does not set one.
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Filter over one tenant's blocks. Unset filters match everything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerQuery {
+    pub agent_id: Option<AgentId>,
+    pub task_id: Option<TaskId>,
+    pub action: Option<LedgerAction>,
+    pub tool_id: Option<ToolId>,
+    /// Only blocks carrying this typed reference.
+    pub references: Option<BlockRef>,
+    /// Inclusive lower bound on `timestamp_utc`.
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `timestamp_utc`.
+    pub until: Option<DateTime<Utc>>,
+    /// Only blocks with a greater sequence number (pagination cursor).
+    pub after_sequence: Option<u64>,
+    /// Maximum blocks per page (at least 1).
+    pub limit: usize,
+}
+
+impl Default for LedgerQuery {
+    fn default() -> Self {
+        Self {
+            agent_id: None,
+            task_id: None,
+            action: None,
+            tool_id: None,
+            references: None,
+            from: None,
+            until: None,
+            after_sequence: None,
+            limit: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+impl LedgerQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn agent(mut self, agent_id: AgentId) -> Self {
+        self.agent_id = Some(agent_id);
+        self
+    }
+
+    pub fn task(mut self, task_id: TaskId) -> Self {
+        self.task_id = Some(task_id);
+        self
+    }
+
+    pub fn action(mut self, action: LedgerAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn tool(mut self, tool_id: ToolId) -> Self {
+        self.tool_id = Some(tool_id);
+        self
+    }
+
+    /// Blocks that point at another block with `reference`.
+    pub fn referencing(mut self, reference: BlockRef) -> Self {
+        self.references = Some(reference);
+        self
+    }
+
+    /// Restrict to `from <= timestamp_utc < until`.
+    pub fn between(mut self, from: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        self.from = Some(from);
+        self.until = Some(until);
+        self
+    }
+
+    /// Resume after the `next_cursor` of a previous page.
+    pub fn after(mut self, sequence_number: u64) -> Self {
+        self.after_sequence = Some(sequence_number);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Whether `block` passes every filter except the cursor.
+    pub fn matches(&self, block: &LedgerBlock) -> bool {
+        self.agent_id.is_none_or(|a| block.agent_id == a)
+            && self.task_id.is_none_or(|t| block.task_id == t)
+            && self.action.as_ref().is_none_or(|a| block.action == *a)
+            && self.tool_id.is_none_or(|t| block.tool_id == Some(t))
+            && self
+                .references
+                .as_ref()
+                .is_none_or(|r| block.refs.contains(r))
+            && self.from.is_none_or(|f| block.timestamp_utc >= f)
+            && self.until.is_none_or(|u| block.timestamp_utc < u)
+    }
+
+    /// Select one page from blocks given in sequence order.
+    pub fn page<'a>(&self, blocks: impl IntoIterator<Item = &'a LedgerBlock>) -> QueryPage {
+        let limit = self.limit.max(1);
+        let after = self.after_sequence;
+        let mut selected: Vec<LedgerBlock> = blocks
+            .into_iter()
+            .filter(|b| after.is_none_or(|s| b.sequence_number > s) && self.matches(b))
+            .take(limit + 1)
+            .cloned()
+            .collect();
+        let more = selected.len() > limit;
+        selected.truncate(limit);
+        QueryPage {
+            next_cursor: more
+                .then(|| selected.last().map(|b| b.sequence_number))
+                .flatten(),
+            blocks: selected,
+        }
+    }
+}
+
+/// One page of query results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPage {
+    pub blocks: Vec<LedgerBlock>,
+    /// Cursor for the next page; `None` when there are no more matches.
+    pub next_cursor: Option<u64>,
+}
+
+/// Run `query` to completion, following cursors across pages.
+///
+/// # Errors
+/// Propagates storage errors.
+pub fn query_all<S: LedgerStorage + ?Sized>(
+    storage: &S,
+    tenant_id: &TenantId,
+    query: &LedgerQuery,
+) -> Result<Vec<LedgerBlock>> {
+    let mut query = query.clone();
+    let mut out = Vec::new();
+    loop {
+        let page = storage.query(tenant_id, &query)?;
+        out.extend(page.blocks);
+        match page.next_cursor {
+            Some(cursor) => query.after_sequence = Some(cursor),
+            None => return Ok(out),
+        }
+    }
+}
+
+/// A tenant's blocks in append order plus secondary indexes.
+#[derive(Default)]
+pub(crate) struct BlockIndex {
+    blocks: Vec<LedgerBlock>,
+    by_id: HashMap<LedgerBlockId, usize>,
+    by_agent: HashMap<AgentId, Vec<usize>>,
+    by_task: HashMap<TaskId, Vec<usize>>,
+    by_action: HashMap<&'static str, Vec<usize>>,
+    by_tool: HashMap<ToolId, Vec<usize>>,
+    by_ref: HashMap<BlockRef, Vec<usize>>,
+    by_time: BTreeMap<DateTime<Utc>, Vec<usize>>,
+}
+
+impl BlockIndex {
+    pub fn push(&mut self, block: LedgerBlock) {
+        let pos = self.blocks.len();
+        self.by_id.insert(block.id, pos);
+        self.by_agent.entry(block.agent_id).or_default().push(pos);
+        self.by_task.entry(block.task_id).or_default().push(pos);
+        self.by_action
+            .entry(block.action.as_str())
+            .or_default()
+            .push(pos);
+        if let Some(tool) = block.tool_id {
+            self.by_tool.entry(tool).or_default().push(pos);
+        }
+        for r in &block.refs {
+            self.by_ref.entry(r.clone()).or_default().push(pos);
+        }
+        self.by_time
+            .entry(block.timestamp_utc)
+            .or_default()
+            .push(pos);
+        self.blocks.push(block);
+    }
+
+    /// Remove blocks with `sequence_number <= through`, rebuilding the indexes.
+    pub fn prune_through(&mut self, through: u64) -> Vec<LedgerBlock> {
+        let mut kept = std::mem::take(&mut self.blocks);
+        let split = kept.partition_point(|b| b.sequence_number <= through);
+        let removed = kept.drain(..split).collect();
+        *self = Self::default();
+        for block in kept {
+            self.push(block);
+        }
+        removed
+    }
+
+    pub fn blocks(&self) -> &[LedgerBlock] {
+        &self.blocks
+    }
+
+    pub fn get(&self, block_id: &LedgerBlockId) -> Option<&LedgerBlock> {
+        self.by_id.get(block_id).map(|&pos| &self.blocks[pos])
+    }
+
+    /// Answer `query` from the narrowest matching index.
+    pub fn query(&self, query: &LedgerQuery) -> QueryPage {
+        let window = self.in_window(query);
+        let postings = [
+            window.as_ref().map(Some),
+            query.agent_id.map(|a| self.by_agent.get(&a)),
+            query.task_id.map(|t| self.by_task.get(&t)),
+            query
+                .action
+                .as_ref()
+                .map(|a| self.by_action.get(a.as_str())),
+            query.tool_id.map(|t| self.by_tool.get(&t)),
+            query.references.as_ref().map(|r| self.by_ref.get(r)),
+        ];
+        let narrowest = postings
+            .into_iter()
+            .flatten()
+            .min_by_key(|p| p.map_or(0, Vec::len));
+        match narrowest {
+            // A filter is set but no block carries that value.
+            Some(None) => QueryPage {
+                blocks: Vec::new(),
+                next_cursor: None,
+            },
+            Some(Some(positions)) => {
+                let start = self.first_after(positions, query.after_sequence);
+                query.page(positions[start..].iter().map(|&pos| &self.blocks[pos]))
+            }
+            None => {
+                let start = query.after_sequence.map_or(0, |seq| {
+                    self.blocks.partition_point(|b| b.sequence_number <= seq)
+                });
+                query.page(&self.blocks[start..])
+            }
+        }
+    }
+
+    /// Positions inside the query's time window, in sequence order; `None`
+    /// when the query sets no bound.
+    fn in_window(&self, query: &LedgerQuery) -> Option<Vec<usize>> {
+        if query.from.is_none() && query.until.is_none() {
+            return None;
+        }
+        if query
+            .from
+            .zip(query.until)
+            .is_some_and(|(from, until)| from >= until)
+        {
+            return Some(Vec::new());
+        }
+        let lower = query.from.map_or(Bound::Unbounded, Bound::Included);
+        let upper = query.until.map_or(Bound::Unbounded, Bound::Excluded);
+        let mut positions: Vec<usize> = self
+            .by_time
+            .range((lower, upper))
+            .flat_map(|(_, p)| p.iter().copied())
+            .collect();
+        // Timestamps need not follow sequence order, so restore it.
+        positions.sort_unstable();
+        Some(positions)
+    }
+
+    /// Index into `positions` of the first block past the cursor.
+    fn first_after(&self, positions: &[usize], after: Option<u64>) -> usize {
+        after.map_or(0, |seq| {
+            positions.partition_point(|&pos| self.blocks[pos].sequence_number <= seq)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::conformance::make_block;
+
+    fn indexed(tenant: TenantId, n: u64) -> (BlockIndex, Vec<LedgerBlock>) {
+        let mut index = BlockIndex::default();
+        let base = chrono::Utc::now();
+        let blocks: Vec<_> = (1..=n)
+            .map(|seq| LedgerBlock {
+                timestamp_utc: base + chrono::Duration::seconds(seq as i64),
+                ..make_block(tenant, seq)
+            })
+            .collect();
+        for b in &blocks {
+            index.push(b.clone());
+        }
+        (index, blocks)
+    }
+
+    #[test]
+    fn test_index_matches_scan() {
+        let (index, blocks) = indexed(TenantId::new(), 6);
+        let agent = blocks[2].agent_id;
+        let queries = [
+            LedgerQuery::new(),
+            LedgerQuery::new().agent(agent),
+            LedgerQuery::new().action(LedgerAction::ToolCall).after(3),
+            LedgerQuery::new().task(TaskId::new()),
+            LedgerQuery::new().limit(2).after(1),
+        ];
+        for q in queries {
+            let ids = |p: QueryPage| p.blocks.iter().map(|b| b.id).collect::<Vec<_>>();
+            assert_eq!(ids(index.query(&q)), ids(q.page(&blocks)));
+        }
+    }
+
+    #[test]
+    fn test_pages_follow_cursor() {
+        let (index, _) = indexed(TenantId::new(), 5);
+        let q = LedgerQuery::new().limit(2);
+        let first = index.query(&q);
+        assert_eq!(first.next_cursor, Some(2));
+        let second = index.query(&q.clone().after(2));
+        assert_eq!(second.blocks[0].sequence_number, 3);
+        let last = index.query(&q.after(4));
+        assert_eq!((last.blocks.len(), last.next_cursor), (1, None));
+    }
+
+    #[test]
+    fn test_time_window_is_half_open() {
+        let (index, blocks) = indexed(TenantId::new(), 3);
+        let q = LedgerQuery::new().between(blocks[1].timestamp_utc, blocks[2].timestamp_utc);
+        let ids: Vec<_> = index.query(&q).blocks.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![blocks[1].id]);
+    }
+
+    #[test]
+    fn test_time_index_matches_scan_out_of_order() {
+        let mut index = BlockIndex::default();
+        let base = chrono::Utc::now();
+        let offsets = [5, 1, 4, 2, 3, 1];
+        let blocks: Vec<_> = (1..=6)
+            .map(|seq| LedgerBlock {
+                timestamp_utc: base + chrono::Duration::seconds(offsets[seq as usize - 1]),
+                ..make_block(TenantId::new(), seq)
+            })
+            .collect();
+        for b in &blocks {
+            index.push(b.clone());
+        }
+        let at = |s| base + chrono::Duration::seconds(s);
+        let queries = [
+            LedgerQuery::new().between(at(1), at(3)),
+            LedgerQuery::new().between(at(2), at(6)).limit(2).after(1),
+            LedgerQuery {
+                until: Some(at(2)),
+                ..LedgerQuery::new()
+            },
+            LedgerQuery::new().between(at(4), at(4)),
+            LedgerQuery::new().between(at(4), at(2)),
+        ];
+        for q in queries {
+            let ids = |p: QueryPage| p.blocks.iter().map(|b| b.id).collect::<Vec<_>>();
+            assert_eq!(ids(index.query(&q)), ids(q.page(&blocks)));
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Task replay — reconstruct what an agent did from the ledger (PRD §14).
+//!
+//! Backs `POST /ledger/replay`. `ReplayEngine` verifies the tenant's chain,
+//! selects the blocks of one task in sequence order, and resolves each
+//! block's input/output from the `PayloadStore`, checking every payload
+//! against the hash recorded in the block.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{AgentId, LedgerBlockId, TaskId, TenantId, ToolId};
+use aether_core::ledger::{BlockHash, LedgerAction, LedgerBlock};
+
+use crate::block::hash_value;
+use crate::chain::{chain_start, verify_chain_from};
+use crate::payload::PayloadStore;
+use crate::storage::LedgerStorage;
+
+/// One recorded action with its resolved payloads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayStep {
+    pub block_id: LedgerBlockId,
+    pub sequence_number: u64,
+    pub timestamp_utc: DateTime<Utc>,
+    pub agent_id: AgentId,
+    pub action: LedgerAction,
+    pub tool_id: Option<ToolId>,
+    /// Full input; `None` if the payload was not stored when the block was written.
+    pub input: Option<serde_json::Value>,
+    /// Full output; `None` if the payload was not stored.
+    pub output: Option<serde_json::Value>,
+}
+
+/// Ordered execution trace of one task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskReplay {
+    pub tenant_id: TenantId,
+    pub task_id: TaskId,
+    pub steps: Vec<ReplayStep>,
+}
+
+/// Rebuilds task traces from ledger blocks and stored payloads.
+pub struct ReplayEngine<S: LedgerStorage> {
+    storage: S,
+    payloads: Arc<dyn PayloadStore>,
+}
+
+impl<S: LedgerStorage> ReplayEngine<S> {
+    pub fn new(storage: S, payloads: Arc<dyn PayloadStore>) -> Self {
+        Self { storage, payloads }
+    }
+
+    /// Replay every action recorded for `task_id`, in chain order.
+    ///
+    /// # Errors
+    /// Returns `NotFound` if the tenant has no blocks for the task, and
+    /// `LedgerIntegrityViolation` if the chain is broken or a stored payload
+    /// does not match its block's hash.
+    pub fn replay_task(&self, tenant_id: &TenantId, task_id: &TaskId) -> Result<TaskReplay> {
+        let blocks = self.storage.get_blocks(tenant_id)?;
+        verify_chain_from(&blocks, &chain_start(&self.storage, tenant_id)?)?;
+        let steps = blocks
+            .iter()
+            .filter(|b| b.task_id == *task_id)
+            .map(|b| self.step(b))
+            .collect::<Result<Vec<_>>>()?;
+        if steps.is_empty() {
+            return Err(AetherError::not_found("ledger task", task_id));
+        }
+        Ok(TaskReplay {
+            tenant_id: *tenant_id,
+            task_id: *task_id,
+            steps,
+        })
+    }
+
+    fn step(&self, block: &LedgerBlock) -> Result<ReplayStep> {
+        Ok(ReplayStep {
+            block_id: block.id,
+            sequence_number: block.sequence_number,
+            timestamp_utc: block.timestamp_utc,
+            agent_id: block.agent_id,
+            action: block.action.clone(),
+            tool_id: block.tool_id,
+            input: self.resolve(block, &block.input_hash, "input")?,
+            output: self.resolve(block, &block.output_hash, "output")?,
+        })
+    }
+
+    fn resolve(
+        &self,
+        block: &LedgerBlock,
+        hash: &BlockHash,
+        field: &str,
+    ) -> Result<Option<serde_json::Value>> {
+        let Some(payload) = self.payloads.get(&block.tenant_id, hash)? else {
+            return Ok(None);
+        };
+        if hash_value(&payload) != *hash {
+            return Err(AetherError::LedgerIntegrityViolation {
+                block_id: block.id.to_string(),
+                reason: format!("stored {field} payload does not match {field}_hash"),
+            });
+        }
+        Ok(Some(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::LedgerBlockBuilder;
+    use crate::payload::InMemoryPayloadStore;
+    use crate::storage::InMemoryLedgerStorage;
+    use crate::writer::LedgerWriter;
+    use aether_core::error::ErrorCode;
+    use serde_json::json;
+
+    /// Store that returns a fixed payload for every hash, simulating tampering.
+    struct TamperedStore;
+
+    impl PayloadStore for TamperedStore {
+        fn put(&self, _: &TenantId, payload: &serde_json::Value) -> Result<BlockHash> {
+            Ok(hash_value(payload))
+        }
+
+        fn get(&self, _: &TenantId, _: &BlockHash) -> Result<Option<serde_json::Value>> {
+            Ok(Some(json!({"forged": true})))
+        }
+    }
+
+    fn entry(tenant: TenantId, agent: AgentId, task: TaskId, n: u32) -> LedgerBlockBuilder {
+        LedgerBlockBuilder::unlinked(tenant, agent, task, LedgerAction::ToolCall)
+            .input(json!({"step": n}))
+            .output(json!({"result": n * 10}))
+    }
+
+    type Storage = Arc<InMemoryLedgerStorage>;
+
+    fn setup() -> (Storage, Arc<dyn PayloadStore>, LedgerWriter<Storage>) {
+        let storage = Arc::new(InMemoryLedgerStorage::new());
+        let payloads: Arc<dyn PayloadStore> = Arc::new(InMemoryPayloadStore::new());
+        let writer = LedgerWriter::new(storage.clone()).with_payload_store(payloads.clone());
+        (storage, payloads, writer)
+    }
+
+    #[test]
+    fn test_replay_returns_task_steps_with_payloads() {
+        let (storage, payloads, writer) = setup();
+        let (t, agent, task) = (TenantId::new(), AgentId::new(), TaskId::new());
+        writer.append(entry(t, agent, task, 1)).unwrap();
+        writer.append(entry(t, agent, TaskId::new(), 2)).unwrap();
+        writer.append(entry(t, agent, task, 3)).unwrap();
+
+        let replay = ReplayEngine::new(storage, payloads)
+            .replay_task(&t, &task)
+            .unwrap();
+        let seqs: Vec<u64> = replay.steps.iter().map(|s| s.sequence_number).collect();
+        assert_eq!(seqs, vec![1, 3]);
+        assert_eq!(replay.steps[1].input, Some(json!({"step": 3})));
+        assert_eq!(replay.steps[1].output, Some(json!({"result": 30})));
+    }
+
+    #[test]
+    fn test_missing_payloads_replay_as_none() {
+        let storage = Arc::new(InMemoryLedgerStorage::new());
+        let writer = LedgerWriter::new(storage.clone());
+        let (t, task) = (TenantId::new(), TaskId::new());
+        writer.append(entry(t, AgentId::new(), task, 1)).unwrap();
+
+        let engine = ReplayEngine::new(storage, Arc::new(InMemoryPayloadStore::new()));
+        let replay = engine.replay_task(&t, &task).unwrap();
+        assert_eq!(replay.steps[0].input, None);
+    }
+
+    #[test]
+    fn test_tampered_payload_is_rejected() {
+        let (storage, _, writer) = setup();
+        let (t, task) = (TenantId::new(), TaskId::new());
+        writer.append(entry(t, AgentId::new(), task, 1)).unwrap();
+
+        let err = ReplayEngine::new(storage, Arc::new(TamperedStore))
+            .replay_task(&t, &task)
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::LedgerIntegrityViolation);
+    }
+
+    #[test]
+    fn test_unknown_task_and_other_tenant_not_found() {
+        let (storage, payloads, writer) = setup();
+        let (t, task) = (TenantId::new(), TaskId::new());
+        writer.append(entry(t, AgentId::new(), task, 1)).unwrap();
+
+        let engine = ReplayEngine::new(storage, payloads);
+        let err = engine.replay_task(&t, &TaskId::new()).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        let err = engine.replay_task(&TenantId::new(), &task).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+    }
+}
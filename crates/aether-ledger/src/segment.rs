@@ -0,0 +1,199 @@
+//! Segment file record format for `FileLedgerStorage` (PRD §14).
+//!
+//! A segment is a flat sequence of records:
+//! `[u32 LE payload length][8-byte SHA-256 prefix of payload][JSON payload]`.
+//! The checksum lets recovery distinguish a torn tail write from a valid record.
+//! A pruned tenant directory also holds its latest `PruneCheckpoint` as JSON.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ledger::LedgerBlock;
+
+use crate::prune::PruneCheckpoint;
+
+/// File extension for ledger segments.
+pub const SEGMENT_EXTENSION: &str = "seg";
+
+/// File in a tenant directory holding its latest prune checkpoint.
+pub const CHECKPOINT_FILE: &str = "prune-checkpoint.json";
+
+const HEADER_LEN: usize = 4 + CHECKSUM_LEN;
+const CHECKSUM_LEN: usize = 8;
+
+/// Blocks decoded from one segment plus the byte offset of the last good record.
+pub struct DecodedSegment {
+    pub blocks: Vec<LedgerBlock>,
+    /// Length of the valid prefix; anything after it is a torn tail.
+    pub valid_len: u64,
+    /// Whether trailing bytes were discarded.
+    pub torn: bool,
+    /// Whether a valid record starts somewhere in the discarded bytes,
+    /// i.e. the damage is mid-segment rather than a torn tail.
+    pub records_after_damage: bool,
+}
+
+/// Encode one block as a segment record.
+pub fn encode_record(block: &LedgerBlock) -> Result<Vec<u8>> {
+    let payload =
+        serde_json::to_vec(block).map_err(|e| AetherError::SerializationError(e.to_string()))?;
+    let len = u32::try_from(payload.len())
+        .map_err(|_| AetherError::StorageError("ledger record exceeds 4 GiB".into()))?;
+    let mut record = Vec::with_capacity(HEADER_LEN + payload.len());
+    record.extend_from_slice(&len.to_le_bytes());
+    record.extend_from_slice(&checksum(&payload));
+    record.extend_from_slice(&payload);
+    Ok(record)
+}
+
+/// Decode every complete, checksum-valid record; stop at the first bad one.
+pub fn decode_records(bytes: &[u8]) -> DecodedSegment {
+    let mut blocks = Vec::new();
+    let mut offset = 0usize;
+    while let Some((block, next)) = decode_one(bytes, offset) {
+        blocks.push(block);
+        offset = next;
+    }
+    DecodedSegment {
+        blocks,
+        valid_len: offset as u64,
+        torn: offset < bytes.len(),
+        records_after_damage: (offset + 1..bytes.len()).any(|o| decode_one(bytes, o).is_some()),
+    }
+}
+
+fn decode_one(bytes: &[u8], offset: usize) -> Option<(LedgerBlock, usize)> {
+    let header = bytes.get(offset..offset + HEADER_LEN)?;
+    let len = u32::from_le_bytes(header[..4].try_into().ok()?) as usize;
+    let start = offset + HEADER_LEN;
+    let payload = bytes.get(start..start.checked_add(len)?)?;
+    if header[4..] != checksum(payload) {
+        return None;
+    }
+    let block = serde_json::from_slice(payload).ok()?;
+    Some((block, start + len))
+}
+
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let digest = Sha256::digest(payload);
+    let mut out = [0u8; CHECKSUM_LEN];
+    out.copy_from_slice(&digest[..CHECKSUM_LEN]);
+    out
+}
+
+/// Segment file name for a segment whose first block has `first_sequence`.
+pub fn segment_path(dir: &Path, first_sequence: u64) -> PathBuf {
+    dir.join(format!("{first_sequence:020}.{SEGMENT_EXTENSION}"))
+}
+
+/// List a tenant directory's segments, ordered by first sequence number.
+pub fn list_segments(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut segments: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(io_error)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == SEGMENT_EXTENSION))
+        .collect();
+    // Zero-padded names sort in sequence order.
+    segments.sort();
+    Ok(segments)
+}
+
+/// First sequence number encoded in a segment's file name.
+pub fn segment_first_sequence(path: &Path) -> Result<u64> {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| {
+            AetherError::StorageError(format!("bad ledger segment name {}", path.display()))
+        })
+}
+
+/// Delete every segment whose blocks all have `sequence_number <= through`.
+///
+/// `segments` holds the tenant's segment start sequences, ascending. A
+/// segment ends where the next one starts, so the newest is never removed.
+pub fn remove_segments_through(dir: &Path, segments: &mut Vec<u64>, through: u64) -> Result<()> {
+    let covered = segments
+        .get(1..)
+        .map_or(0, |next| next.partition_point(|&s| s <= through + 1));
+    for first in segments.drain(..covered) {
+        fs::remove_file(segment_path(dir, first)).map_err(io_error)?;
+    }
+    Ok(())
+}
+
+/// Atomically replace a tenant directory's prune checkpoint.
+pub fn write_checkpoint(dir: &Path, checkpoint: &PruneCheckpoint) -> Result<()> {
+    let bytes = serde_json::to_vec(checkpoint)
+        .map_err(|e| AetherError::SerializationError(e.to_string()))?;
+    let tmp = dir.join(format!("{CHECKPOINT_FILE}.tmp"));
+    let mut file = fs::File::create(&tmp).map_err(io_error)?;
+    file.write_all(&bytes).map_err(io_error)?;
+    file.sync_all().map_err(io_error)?;
+    fs::rename(&tmp, dir.join(CHECKPOINT_FILE)).map_err(io_error)
+}
+
+/// Read a tenant directory's prune checkpoint, if it was ever pruned.
+pub fn read_checkpoint(dir: &Path) -> Result<Option<PruneCheckpoint>> {
+    let path = dir.join(CHECKPOINT_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(&path).map_err(io_error)?;
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| AetherError::SerializationError(e.to_string()))
+}
+
+/// Map an I/O error into the ledger's storage error.
+pub fn io_error(e: std::io::Error) -> AetherError {
+    AetherError::StorageError(format!("ledger segment I/O: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::LedgerBlockBuilder;
+    use aether_core::ids::{AgentId, TaskId, TenantId};
+    use aether_core::ledger::{BlockHash, LedgerAction};
+
+    fn block(seq: u64) -> LedgerBlock {
+        let (tenant, agent, task) = (TenantId::new(), AgentId::new(), TaskId::new());
+        let genesis = BlockHash::genesis();
+        LedgerBlockBuilder::new(tenant, agent, task, LedgerAction::ToolCall, genesis, seq).build()
+    }
+
+    #[test]
+    fn test_roundtrip_records() {
+        let mut bytes = encode_record(&block(1)).unwrap();
+        bytes.extend(encode_record(&block(2)).unwrap());
+        let decoded = decode_records(&bytes);
+        assert_eq!(decoded.blocks.len(), 2);
+        assert_eq!(decoded.valid_len, bytes.len() as u64);
+        assert!(!decoded.torn);
+    }
+
+    #[test]
+    fn test_torn_tail_is_dropped() {
+        let first = encode_record(&block(1)).unwrap();
+        let second = encode_record(&block(2)).unwrap();
+        let mut bytes = first.clone();
+        bytes.extend_from_slice(&second[..second.len() / 2]);
+        let decoded = decode_records(&bytes);
+        assert_eq!(decoded.blocks.len(), 1);
+        assert_eq!(decoded.valid_len, first.len() as u64);
+        assert!(decoded.torn);
+    }
+
+    #[test]
+    fn test_corrupt_payload_fails_checksum() {
+        let mut bytes = encode_record(&block(1)).unwrap();
+        let last = bytes.len() - 2;
+        bytes[last] ^= 0xff;
+        assert!(decode_records(&bytes).blocks.is_empty());
+    }
+}
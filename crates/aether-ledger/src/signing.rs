@@ -0,0 +1,311 @@
+//! Ed25519 block signing (PRD §14).
+//!
+//! A hash chain alone can be recomputed by anyone with write access to storage.
+//! Signing the output of `compute_block_hash` with a tenant or node key makes
+//! every block attributable: only the holder of the private key can produce it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{LedgerBlockId, TenantId};
+use aether_core::ledger::LedgerBlock;
+
+use crate::chain::compute_block_hash;
+
+/// Produces Ed25519 signatures over block hashes.
+pub trait BlockSigner: Send + Sync {
+    /// Stable identifier recorded in `LedgerBlock::signer_key_id`.
+    fn key_id(&self) -> &str;
+
+    /// Sign an arbitrary message.
+    fn sign(&self, message: &[u8]) -> Signature;
+
+    /// Public half of the signing key.
+    fn verifying_key(&self) -> VerifyingKey;
+}
+
+/// In-process Ed25519 signer backed by a raw secret key.
+pub struct Ed25519Signer {
+    key_id: String,
+    key: SigningKey,
+}
+
+impl Ed25519Signer {
+    /// Build a signer from a 32-byte secret seed.
+    pub fn from_seed(key_id: impl Into<String>, seed: &[u8; 32]) -> Self {
+        Self {
+            key_id: key_id.into(),
+            key: SigningKey::from_bytes(seed),
+        }
+    }
+}
+
+impl BlockSigner for Ed25519Signer {
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn sign(&self, message: &[u8]) -> Signature {
+        self.key.sign(message)
+    }
+
+    fn verifying_key(&self) -> VerifyingKey {
+        self.key.verifying_key()
+    }
+}
+
+/// A public key trusted for verification, optionally bound to one tenant.
+#[derive(Debug, Clone)]
+struct TrustedKey {
+    key: VerifyingKey,
+    /// `None` = node key, valid for every tenant.
+    tenant_id: Option<TenantId>,
+}
+
+/// Signing and verification keys for the ledger.
+///
+/// Signers are resolved per tenant first, then fall back to the node key.
+/// Registering a signer also trusts its public key for verification.
+#[derive(Default)]
+pub struct LedgerKeyring {
+    node_signer: Option<Arc<dyn BlockSigner>>,
+    tenant_signers: HashMap<TenantId, Arc<dyn BlockSigner>>,
+    trusted: HashMap<String, TrustedKey>,
+}
+
+impl LedgerKeyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the node-wide signer used for tenants without their own key.
+    pub fn with_node_signer(mut self, signer: Arc<dyn BlockSigner>) -> Self {
+        self.trust_key(signer.key_id(), signer.verifying_key(), None);
+        self.node_signer = Some(signer);
+        self
+    }
+
+    /// Set a dedicated signer for one tenant.
+    pub fn with_tenant_signer(mut self, tenant_id: TenantId, signer: Arc<dyn BlockSigner>) -> Self {
+        self.trust_key(signer.key_id(), signer.verifying_key(), Some(tenant_id));
+        self.tenant_signers.insert(tenant_id, signer);
+        self
+    }
+
+    /// Trust a public key without holding its private half (offline audits).
+    ///
+    /// `tenant_id = None` trusts the key for every tenant.
+    pub fn trust_key(
+        &mut self,
+        key_id: impl Into<String>,
+        key: VerifyingKey,
+        tenant_id: Option<TenantId>,
+    ) {
+        self.trusted
+            .insert(key_id.into(), TrustedKey { key, tenant_id });
+    }
+
+    /// Resolve the signer for a tenant (tenant key, else node key).
+    pub fn signer_for(&self, tenant_id: &TenantId) -> Option<Arc<dyn BlockSigner>> {
+        self.tenant_signers
+            .get(tenant_id)
+            .or(self.node_signer.as_ref())
+            .cloned()
+    }
+
+    /// Build a verification-only keyring from exported public keys.
+    ///
+    /// # Errors
+    /// Returns `ValidationFailed` if a key is not a valid Ed25519 public key.
+    pub fn from_public_keys(keys: &[PublicKeyRecord]) -> Result<Self> {
+        let mut keyring = Self::new();
+        for record in keys {
+            let key = decode_hex(&record.public_key)
+                .and_then(|b| <[u8; 32]>::try_from(b).ok())
+                .and_then(|b| VerifyingKey::from_bytes(&b).ok())
+                .ok_or_else(|| AetherError::ValidationFailed {
+                    field: "public_key".into(),
+                    reason: format!("'{}' is not an Ed25519 public key", record.key_id),
+                })?;
+            keyring.trust_key(record.key_id.clone(), key, record.tenant_id);
+        }
+        Ok(keyring)
+    }
+
+    /// Public keys usable for `tenant_id` (its own keys and node keys), by key id.
+    pub fn public_keys_for(&self, tenant_id: &TenantId) -> Vec<PublicKeyRecord> {
+        let mut keys: Vec<PublicKeyRecord> = self
+            .trusted
+            .iter()
+            .filter(|(_, k)| k.tenant_id.is_none_or(|t| t == *tenant_id))
+            .map(|(key_id, k)| PublicKeyRecord {
+                key_id: key_id.clone(),
+                public_key: encode_hex(k.key.as_bytes()),
+                tenant_id: k.tenant_id,
+            })
+            .collect();
+        keys.sort_by(|a, b| a.key_id.cmp(&b.key_id));
+        keys
+    }
+
+    /// Look up a trusted verifying key usable for `tenant_id`.
+    pub fn verifying_key(&self, key_id: &str, tenant_id: &TenantId) -> Option<&VerifyingKey> {
+        self.trusted
+            .get(key_id)
+            .filter(|k| k.tenant_id.is_none_or(|t| t == *tenant_id))
+            .map(|k| &k.key)
+    }
+}
+
+/// A trusted public key in portable form (hex-encoded Ed25519).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicKeyRecord {
+    pub key_id: String,
+    pub public_key: String,
+    /// `None` = node key, valid for every tenant.
+    pub tenant_id: Option<TenantId>,
+}
+
+/// Which key signed a given block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerRecord {
+    pub block_id: LedgerBlockId,
+    pub sequence_number: u64,
+    pub key_id: String,
+}
+
+/// Sign a block in place over its `compute_block_hash` output.
+pub fn sign_block(block: &mut LedgerBlock, signer: &dyn BlockSigner) {
+    let hash = compute_block_hash(block);
+    let signature = signer.sign(hash.0.as_bytes());
+    block.signature = Some(encode_hex(&signature.to_bytes()));
+    block.signer_key_id = Some(signer.key_id().to_string());
+}
+
+/// Verify a single block's signature against the keyring.
+///
+/// # Errors
+/// Returns `LedgerIntegrityViolation` if the block is unsigned, signed by an
+/// unknown key, or the signature does not match the block hash.
+pub fn verify_block_signature(
+    block: &LedgerBlock,
+    keyring: &LedgerKeyring,
+) -> Result<SignerRecord> {
+    let violation = |reason: String| AetherError::LedgerIntegrityViolation {
+        block_id: block.id.to_string(),
+        reason,
+    };
+    let (Some(sig_hex), Some(key_id)) = (&block.signature, &block.signer_key_id) else {
+        return Err(violation(format!(
+            "block at seq {} is unsigned",
+            block.sequence_number
+        )));
+    };
+    let key = keyring
+        .verifying_key(key_id, &block.tenant_id)
+        .ok_or_else(|| violation(format!("key '{key_id}' is not trusted for this tenant")))?;
+    let bytes: [u8; 64] = decode_hex(sig_hex)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| violation("malformed signature encoding".into()))?;
+    let hash = compute_block_hash(block);
+    key.verify(hash.0.as_bytes(), &Signature::from_bytes(&bytes))
+        .map_err(|_| violation(format!("invalid signature by key '{key_id}'")))?;
+    Ok(SignerRecord {
+        block_id: block.id,
+        sequence_number: block.sequence_number,
+        key_id: key_id.clone(),
+    })
+}
+
+/// Lowercase hex encoding (matches the `BlockHash` representation).
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Decode lowercase/uppercase hex; `None` on odd length or invalid digits.
+pub(crate) fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::LedgerBlockBuilder;
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::{BlockHash, LedgerAction};
+
+    fn signer(id: &str, seed: u8) -> Arc<dyn BlockSigner> {
+        Arc::new(Ed25519Signer::from_seed(id, &[seed; 32]))
+    }
+
+    fn block(tenant: TenantId, seq: u64, parent: BlockHash) -> LedgerBlock {
+        let (agent, task) = (AgentId::new(), TaskId::new());
+        LedgerBlockBuilder::new(tenant, agent, task, LedgerAction::ToolCall, parent, seq)
+            .input(serde_json::json!({"seq": seq}))
+            .build()
+    }
+
+    #[test]
+    fn test_signed_block_verifies() {
+        let keyring = LedgerKeyring::new().with_node_signer(signer("node-1", 7));
+        let t = TenantId::new();
+        let mut b = block(t, 1, BlockHash::genesis());
+        sign_block(&mut b, keyring.signer_for(&t).unwrap().as_ref());
+        let record = verify_block_signature(&b, &keyring).unwrap();
+        assert_eq!(record.key_id, "node-1");
+    }
+
+    #[test]
+    fn test_unsigned_block_rejected() {
+        let keyring = LedgerKeyring::new().with_node_signer(signer("node-1", 7));
+        let b = block(TenantId::new(), 1, BlockHash::genesis());
+        assert!(verify_block_signature(&b, &keyring).is_err());
+    }
+
+    #[test]
+    fn test_wrong_key_rejected() {
+        let t = TenantId::new();
+        let mut b = block(t, 1, BlockHash::genesis());
+        sign_block(&mut b, signer("node-1", 7).as_ref());
+        // Same key id, different key material.
+        let keyring = LedgerKeyring::new().with_node_signer(signer("node-1", 8));
+        assert!(verify_block_signature(&b, &keyring).is_err());
+    }
+
+    #[test]
+    fn test_tampered_block_rejected() {
+        let keyring = LedgerKeyring::new().with_node_signer(signer("node-1", 7));
+        let t = TenantId::new();
+        let mut b = block(t, 1, BlockHash::genesis());
+        sign_block(&mut b, signer("node-1", 7).as_ref());
+        b.output_hash = BlockHash("f".repeat(64));
+        assert!(verify_block_signature(&b, &keyring).is_err());
+    }
+
+    #[test]
+    fn test_tenant_key_not_valid_for_other_tenant() {
+        let (t1, t2) = (TenantId::new(), TenantId::new());
+        let keyring = LedgerKeyring::new().with_tenant_signer(t1, signer("tenant-1", 3));
+        let mut b = block(t2, 1, BlockHash::genesis());
+        sign_block(&mut b, signer("tenant-1", 3).as_ref());
+        assert!(verify_block_signature(&b, &keyring).is_err());
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let bytes = [0u8, 1, 0xab, 0xff];
+        assert_eq!(decode_hex(&encode_hex(&bytes)).unwrap(), bytes);
+        assert!(decode_hex("abc").is_none());
+        assert!(decode_hex("zz").is_none());
+    }
+}
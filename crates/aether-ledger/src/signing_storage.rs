@@ -0,0 +1,157 @@
+//! Signing at append time (PRD §14).
+//!
+//! `SigningLedgerStorage` wraps any backend and signs each block with its
+//! tenant's key (or the node key) from a `LedgerKeyring` before storing it.
+
+use std::sync::Arc;
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{LedgerBlockId, TenantId};
+use aether_core::ledger::LedgerBlock;
+
+use crate::prune::PruneCheckpoint;
+use crate::query::{LedgerQuery, QueryPage};
+use crate::signing::{sign_block, LedgerKeyring};
+use crate::storage::LedgerStorage;
+
+/// Storage decorator that signs every block at append time.
+///
+/// Every other method forwards to the inner storage, so its indexed
+/// `head` and `query` paths stay in use.
+pub struct SigningLedgerStorage<S: LedgerStorage> {
+    inner: S,
+    keyring: Arc<LedgerKeyring>,
+}
+
+impl<S: LedgerStorage> SigningLedgerStorage<S> {
+    pub fn new(inner: S, keyring: Arc<LedgerKeyring>) -> Self {
+        Self { inner, keyring }
+    }
+}
+
+impl<S: LedgerStorage> LedgerStorage for SigningLedgerStorage<S> {
+    fn append(&self, mut block: LedgerBlock) -> Result<()> {
+        let signer = self.keyring.signer_for(&block.tenant_id).ok_or_else(|| {
+            AetherError::internal(format!(
+                "no ledger signing key for tenant {}",
+                block.tenant_id
+            ))
+        })?;
+        sign_block(&mut block, signer.as_ref());
+        self.inner.append(block)
+    }
+
+    fn get_blocks(&self, tenant_id: &TenantId) -> Result<Vec<LedgerBlock>> {
+        self.inner.get_blocks(tenant_id)
+    }
+
+    fn get_block(&self, block_id: &LedgerBlockId) -> Result<LedgerBlock> {
+        self.inner.get_block(block_id)
+    }
+
+    fn count(&self, tenant_id: &TenantId) -> Result<u64> {
+        self.inner.count(tenant_id)
+    }
+
+    fn head(&self, tenant_id: &TenantId) -> Result<Option<LedgerBlock>> {
+        self.inner.head(tenant_id)
+    }
+
+    fn query(&self, tenant_id: &TenantId, query: &LedgerQuery) -> Result<QueryPage> {
+        self.inner.query(tenant_id, query)
+    }
+
+    fn prune_boundary(&self, tenant_id: &TenantId, through: u64) -> Result<Option<u64>> {
+        self.inner.prune_boundary(tenant_id, through)
+    }
+
+    fn prune(&self, checkpoint: &PruneCheckpoint) -> Result<u64> {
+        self.inner.prune(checkpoint)
+    }
+
+    fn prune_checkpoint(&self, tenant_id: &TenantId) -> Result<Option<PruneCheckpoint>> {
+        self.inner.prune_checkpoint(tenant_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::LedgerBlockBuilder;
+    use crate::chain::{compute_block_hash, verify_chain_signed};
+    use crate::signing::{BlockSigner, Ed25519Signer};
+    use crate::storage::InMemoryLedgerStorage;
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::{BlockHash, LedgerAction};
+
+    fn signer(id: &str, seed: u8) -> Arc<dyn BlockSigner> {
+        Arc::new(Ed25519Signer::from_seed(id, &[seed; 32]))
+    }
+
+    fn block(tenant: TenantId, seq: u64, parent: BlockHash) -> LedgerBlock {
+        let (agent, task) = (AgentId::new(), TaskId::new());
+        LedgerBlockBuilder::new(tenant, agent, task, LedgerAction::ToolCall, parent, seq)
+            .input(serde_json::json!({"seq": seq}))
+            .build()
+    }
+
+    #[test]
+    fn test_signing_storage_signs_chain_with_tenant_key() {
+        let t = TenantId::new();
+        let keyring = Arc::new(
+            LedgerKeyring::new()
+                .with_node_signer(signer("node-1", 7))
+                .with_tenant_signer(t, signer("tenant-key", 9)),
+        );
+        let storage = SigningLedgerStorage::new(InMemoryLedgerStorage::new(), keyring.clone());
+        let b1 = block(t, 1, BlockHash::genesis());
+        let b2 = block(t, 2, compute_block_hash(&b1));
+        storage.append(b1).unwrap();
+        storage.append(b2).unwrap();
+
+        let records = verify_chain_signed(&storage.get_blocks(&t).unwrap(), &keyring).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.key_id == "tenant-key"));
+    }
+
+    /// Inner storage whose full scan fails, so only forwarded calls succeed.
+    struct NoScan(InMemoryLedgerStorage);
+
+    impl LedgerStorage for NoScan {
+        fn append(&self, block: LedgerBlock) -> Result<()> {
+            self.0.append(block)
+        }
+        fn get_blocks(&self, _tenant_id: &TenantId) -> Result<Vec<LedgerBlock>> {
+            Err(AetherError::internal("full scan"))
+        }
+        fn get_block(&self, block_id: &LedgerBlockId) -> Result<LedgerBlock> {
+            self.0.get_block(block_id)
+        }
+        fn count(&self, tenant_id: &TenantId) -> Result<u64> {
+            self.0.count(tenant_id)
+        }
+        fn head(&self, tenant_id: &TenantId) -> Result<Option<LedgerBlock>> {
+            self.0.head(tenant_id)
+        }
+        fn query(&self, tenant_id: &TenantId, query: &LedgerQuery) -> Result<QueryPage> {
+            self.0.query(tenant_id, query)
+        }
+    }
+
+    #[test]
+    fn test_signing_storage_forwards_indexed_reads() {
+        let t = TenantId::new();
+        let keyring = Arc::new(LedgerKeyring::new().with_node_signer(signer("node-1", 7)));
+        let storage = SigningLedgerStorage::new(NoScan(InMemoryLedgerStorage::new()), keyring);
+        storage.append(block(t, 1, BlockHash::genesis())).unwrap();
+        assert_eq!(storage.head(&t).unwrap().unwrap().sequence_number, 1);
+        assert_eq!(
+            storage
+                .query(&t, &LedgerQuery::default())
+                .unwrap()
+                .blocks
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,321 @@
+//! Simulated ledger branches (PRD §14).
+//!
+//! Backs `POST /ledger/simulate`. A `LedgerBranch` forks a tenant's chain at
+//! any block and is itself a `LedgerStorage`: reads see the real chain up to
+//! the fork point followed by the branch's own blocks, and appends go only to
+//! the branch. The base storage is never written, so simulated blocks are
+//! invisible to the real chain's `get_blocks`/`count`. Wrap a branch in a
+//! `LedgerWriter` to append with normal chaining, then `export` or `discard`.
+
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{LedgerBlockId, SimulationId, TenantId};
+use aether_core::ledger::LedgerBlock;
+
+use crate::chain::{verify_chain_from, ChainHead};
+use crate::prune::PruneCheckpoint;
+use crate::storage::LedgerStorage;
+
+/// Serializable record of a finished simulation branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationRecord {
+    pub simulation_id: SimulationId,
+    pub tenant_id: TenantId,
+    /// Real block the branch forked from (`None` = forked from genesis).
+    pub fork_block_id: Option<LedgerBlockId>,
+    /// Head the first simulated block links to.
+    pub fork_point: ChainHead,
+    pub blocks: Vec<LedgerBlock>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SimulationRecord {
+    /// Check the simulated blocks chain from the fork point.
+    ///
+    /// # Errors
+    /// Returns `LedgerIntegrityViolation` if the branch is broken.
+    pub fn verify(&self) -> Result<()> {
+        verify_chain_from(&self.blocks, &self.fork_point)
+    }
+}
+
+/// An isolated, hash-linked branch of one tenant's chain.
+pub struct LedgerBranch<S: LedgerStorage> {
+    simulation_id: SimulationId,
+    base: S,
+    tenant_id: TenantId,
+    fork_block_id: Option<LedgerBlockId>,
+    fork_point: ChainHead,
+    blocks: RwLock<Vec<LedgerBlock>>,
+    created_at: DateTime<Utc>,
+}
+
+impl<S: LedgerStorage> LedgerBranch<S> {
+    /// Fork `tenant_id`'s chain after `block_id`.
+    ///
+    /// # Errors
+    /// Returns `NotFound` if the block does not exist or belongs to another tenant.
+    pub fn fork(base: S, tenant_id: &TenantId, block_id: &LedgerBlockId) -> Result<Self> {
+        let block = base.get_block(block_id)?;
+        if block.tenant_id != *tenant_id {
+            return Err(AetherError::not_found("LedgerBlock", block_id));
+        }
+        Ok(Self::at(
+            base,
+            *tenant_id,
+            Some(block.id),
+            ChainHead::of(&block),
+        ))
+    }
+
+    /// Fork at the tenant's current head (genesis for an empty chain).
+    ///
+    /// # Errors
+    /// Propagates storage errors.
+    pub fn fork_at_head(base: S, tenant_id: &TenantId) -> Result<Self> {
+        let head = base.head(tenant_id)?;
+        let fork_point = head
+            .as_ref()
+            .map(ChainHead::of)
+            .unwrap_or_else(ChainHead::genesis);
+        Ok(Self::at(base, *tenant_id, head.map(|b| b.id), fork_point))
+    }
+
+    fn at(
+        base: S,
+        tenant_id: TenantId,
+        fork_block_id: Option<LedgerBlockId>,
+        fork_point: ChainHead,
+    ) -> Self {
+        Self {
+            simulation_id: SimulationId::new(),
+            base,
+            tenant_id,
+            fork_block_id,
+            fork_point,
+            blocks: RwLock::new(Vec::new()),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn simulation_id(&self) -> SimulationId {
+        self.simulation_id
+    }
+
+    pub fn fork_point(&self) -> &ChainHead {
+        &self.fork_point
+    }
+
+    /// Blocks appended to the branch (excluding the real prefix).
+    ///
+    /// # Errors
+    /// Returns `Internal` if the branch lock is poisoned.
+    pub fn simulated_blocks(&self) -> Result<Vec<LedgerBlock>> {
+        Ok(self.read()?.clone())
+    }
+
+    /// Close the branch and return its blocks for storage or analysis.
+    ///
+    /// # Errors
+    /// Returns `Internal` if the branch lock is poisoned.
+    pub fn export(self) -> Result<SimulationRecord> {
+        let blocks = self.blocks.into_inner().map_err(lock_poisoned)?;
+        Ok(SimulationRecord {
+            simulation_id: self.simulation_id,
+            tenant_id: self.tenant_id,
+            fork_block_id: self.fork_block_id,
+            fork_point: self.fork_point,
+            blocks,
+            created_at: self.created_at,
+        })
+    }
+
+    /// Drop the branch and everything appended to it.
+    pub fn discard(self) {}
+
+    fn read(&self) -> Result<std::sync::RwLockReadGuard<'_, Vec<LedgerBlock>>> {
+        self.blocks.read().map_err(lock_poisoned)
+    }
+
+    /// Real blocks visible in the branch: the prefix up to the fork point.
+    fn prefix(&self) -> Result<Vec<LedgerBlock>> {
+        let mut blocks = self.base.get_blocks(&self.tenant_id)?;
+        blocks.retain(|b| b.sequence_number <= self.fork_point.sequence_number);
+        Ok(blocks)
+    }
+}
+
+impl<S: LedgerStorage> LedgerStorage for LedgerBranch<S> {
+    fn append(&self, block: LedgerBlock) -> Result<()> {
+        if block.tenant_id != self.tenant_id {
+            return Err(AetherError::Forbidden(format!(
+                "simulation {} belongs to another tenant",
+                self.simulation_id
+            )));
+        }
+        // Check and push under one lock so concurrent appends cannot both
+        // link to the same head.
+        let mut blocks = self.blocks.write().map_err(lock_poisoned)?;
+        let head = blocks
+            .last()
+            .map(ChainHead::of)
+            .unwrap_or_else(|| self.fork_point.clone());
+        if block.parent_hash != head.hash || block.sequence_number != head.next_sequence() {
+            return Err(AetherError::Conflict(format!(
+                "simulated block must link to seq {} of simulation {}",
+                head.sequence_number, self.simulation_id
+            )));
+        }
+        blocks.push(block);
+        Ok(())
+    }
+
+    fn get_blocks(&self, tenant_id: &TenantId) -> Result<Vec<LedgerBlock>> {
+        if *tenant_id != self.tenant_id {
+            return self.base.get_blocks(tenant_id);
+        }
+        let mut blocks = self.prefix()?;
+        blocks.extend(self.read()?.iter().cloned());
+        Ok(blocks)
+    }
+
+    fn get_block(&self, block_id: &LedgerBlockId) -> Result<LedgerBlock> {
+        if let Some(b) = self.read()?.iter().find(|b| b.id == *block_id) {
+            return Ok(b.clone());
+        }
+        let block = self.base.get_block(block_id)?;
+        let past_fork = block.tenant_id == self.tenant_id
+            && block.sequence_number > self.fork_point.sequence_number;
+        if past_fork {
+            return Err(AetherError::not_found("LedgerBlock", block_id));
+        }
+        Ok(block)
+    }
+
+    fn count(&self, tenant_id: &TenantId) -> Result<u64> {
+        if *tenant_id != self.tenant_id {
+            return self.base.count(tenant_id);
+        }
+        Ok(self.prefix()?.len() as u64 + self.read()?.len() as u64)
+    }
+
+    fn head(&self, tenant_id: &TenantId) -> Result<Option<LedgerBlock>> {
+        if *tenant_id != self.tenant_id {
+            return self.base.head(tenant_id);
+        }
+        if let Some(last) = self.read()?.last() {
+            return Ok(Some(last.clone()));
+        }
+        Ok(self.prefix()?.pop())
+    }
+
+    /// The real chain's checkpoint also anchors the branch's prefix.
+    fn prune_checkpoint(&self, tenant_id: &TenantId) -> Result<Option<PruneCheckpoint>> {
+        self.base.prune_checkpoint(tenant_id)
+    }
+}
+
+fn lock_poisoned<E: std::fmt::Display>(e: E) -> AetherError {
+    AetherError::internal(format!("simulation lock poisoned: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::block::LedgerBlockBuilder;
+    use crate::chain::verify_chain;
+    use crate::storage::InMemoryLedgerStorage;
+    use crate::writer::LedgerWriter;
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::LedgerAction;
+
+    fn entry(tenant: TenantId) -> LedgerBlockBuilder {
+        LedgerBlockBuilder::unlinked(
+            tenant,
+            AgentId::new(),
+            TaskId::new(),
+            LedgerAction::ToolCall,
+        )
+    }
+
+    fn real_chain(len: usize) -> (Arc<InMemoryLedgerStorage>, TenantId, Vec<LedgerBlock>) {
+        let storage = Arc::new(InMemoryLedgerStorage::new());
+        let writer = LedgerWriter::new(storage.clone());
+        let t = TenantId::new();
+        let blocks = (0..len).map(|_| writer.append(entry(t)).unwrap()).collect();
+        (storage, t, blocks)
+    }
+
+    #[test]
+    fn test_branch_is_invisible_to_real_chain() {
+        let (storage, t, blocks) = real_chain(3);
+        let branch = LedgerBranch::fork(storage.clone(), &t, &blocks[1].id).unwrap();
+        let sim = LedgerWriter::new(branch);
+        let first = sim.append(entry(t)).unwrap();
+        sim.append(entry(t)).unwrap();
+
+        assert_eq!(first.sequence_number, 3);
+        assert_eq!(first.parent_hash, ChainHead::of(&blocks[1]).hash);
+        assert_eq!(storage.count(&t).unwrap(), 3);
+        assert!(storage.get_block(&first.id).is_err());
+
+        let view = sim.storage();
+        assert_eq!(view.count(&t).unwrap(), 4);
+        assert!(verify_chain(&view.get_blocks(&t).unwrap()).is_ok());
+        assert!(view.get_block(&blocks[2].id).is_err());
+    }
+
+    #[test]
+    fn test_export_verifies_from_fork_point() {
+        let (storage, t, _) = real_chain(2);
+        let sim = LedgerWriter::new(LedgerBranch::fork_at_head(storage, &t).unwrap());
+        sim.append(entry(t)).unwrap();
+        let branch = sim.into_storage();
+        let record = branch.export().unwrap();
+        assert_eq!(record.blocks.len(), 1);
+        assert_eq!(record.fork_point.sequence_number, 2);
+        assert!(record.verify().is_ok());
+    }
+
+    #[test]
+    fn test_branch_rejects_other_tenant_and_unlinked_blocks() {
+        let (storage, t, blocks) = real_chain(1);
+        let (other_storage, other, _) = real_chain(1);
+        assert!(LedgerBranch::fork(storage.clone(), &other, &blocks[0].id).is_err());
+        let branch = LedgerBranch::fork_at_head(storage, &t).unwrap();
+        let foreign = other_storage.head(&other).unwrap().unwrap();
+        assert!(branch.append(foreign).is_err());
+        let unlinked = entry(t).build();
+        assert!(branch.append(unlinked).is_err());
+        branch.discard();
+    }
+
+    #[test]
+    fn test_concurrent_appends_to_same_head_admit_one() {
+        let (storage, t, _) = real_chain(1);
+        let branch = Arc::new(LedgerBranch::fork_at_head(storage, &t).unwrap());
+        let head = branch.fork_point().clone();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let branch = branch.clone();
+                let block = entry(t)
+                    .link(head.hash.clone(), head.next_sequence())
+                    .build();
+                std::thread::spawn(move || branch.append(block).is_ok())
+            })
+            .collect();
+        let admitted = handles
+            .into_iter()
+            .filter_map(|h| h.join().ok())
+            .filter(|&ok| ok)
+            .count();
+        assert_eq!(admitted, 1);
+        assert_eq!(branch.simulated_blocks().unwrap().len(), 1);
+    }
+}
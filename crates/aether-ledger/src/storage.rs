@@ -2,15 +2,19 @@
 //!
 //! Production: backed by PostgreSQL + Kafka write buffer.
 //! This module provides the in-memory implementation for testing
-//! and a Storage trait for swapping implementations.
+//! and a Storage trait for swapping implementations. The durable
+//! segment-file backend lives in `file_storage`.
 
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
 
 use aether_core::error::{AetherError, Result};
 use aether_core::ids::{LedgerBlockId, TenantId};
 use aether_core::ledger::LedgerBlock;
 
+use crate::prune::{prunable, PruneCheckpoint};
+use crate::query::{BlockIndex, LedgerQuery, QueryPage};
+
 /// Storage trait — allows swapping real DB for test double.
 pub trait LedgerStorage: Send + Sync {
     /// Append a block. Must be called with monotonically increasing sequence numbers.
@@ -24,20 +28,108 @@ pub trait LedgerStorage: Send + Sync {
 
     /// Count total blocks for a tenant.
     fn count(&self, tenant_id: &TenantId) -> Result<u64>;
+
+    /// Last block of a tenant's chain, if any.
+    fn head(&self, tenant_id: &TenantId) -> Result<Option<LedgerBlock>> {
+        Ok(self.get_blocks(tenant_id)?.pop())
+    }
+
+    /// One page of a tenant's blocks matching `query`, in sequence order.
+    ///
+    /// The default scans `get_blocks`; indexed backends should override it.
+    fn query(&self, tenant_id: &TenantId, query: &LedgerQuery) -> Result<QueryPage> {
+        Ok(query.page(&self.get_blocks(tenant_id)?))
+    }
+
+    /// Largest sequence `<= through` this backend can prune up to, or `None`
+    /// if no prefix ending there can be dropped. Backends that store blocks
+    /// in larger units round down to a unit boundary.
+    fn prune_boundary(&self, _tenant_id: &TenantId, through: u64) -> Result<Option<u64>> {
+        Ok(Some(through))
+    }
+
+    /// Drop every block up to and including `checkpoint.head` and keep the
+    /// checkpoint as the tenant's new verification start. Returns the number
+    /// of blocks removed. Never removes the head block.
+    ///
+    /// The default rejects pruning; backends that support retention override it.
+    fn prune(&self, checkpoint: &PruneCheckpoint) -> Result<u64> {
+        Err(AetherError::StorageError(format!(
+            "ledger backend cannot prune tenant {}",
+            checkpoint.tenant_id
+        )))
+    }
+
+    /// The tenant's latest prune checkpoint, if its chain was ever pruned.
+    fn prune_checkpoint(&self, _tenant_id: &TenantId) -> Result<Option<PruneCheckpoint>> {
+        Ok(None)
+    }
+}
+
+/// Shared storage — lets a `LedgerWriter` and readers use the same backend.
+impl<S: LedgerStorage + ?Sized> LedgerStorage for Arc<S> {
+    fn append(&self, block: LedgerBlock) -> Result<()> {
+        (**self).append(block)
+    }
+
+    fn get_blocks(&self, tenant_id: &TenantId) -> Result<Vec<LedgerBlock>> {
+        (**self).get_blocks(tenant_id)
+    }
+
+    fn get_block(&self, block_id: &LedgerBlockId) -> Result<LedgerBlock> {
+        (**self).get_block(block_id)
+    }
+
+    fn count(&self, tenant_id: &TenantId) -> Result<u64> {
+        (**self).count(tenant_id)
+    }
+
+    fn head(&self, tenant_id: &TenantId) -> Result<Option<LedgerBlock>> {
+        (**self).head(tenant_id)
+    }
+
+    fn query(&self, tenant_id: &TenantId, query: &LedgerQuery) -> Result<QueryPage> {
+        (**self).query(tenant_id, query)
+    }
+
+    fn prune_boundary(&self, tenant_id: &TenantId, through: u64) -> Result<Option<u64>> {
+        (**self).prune_boundary(tenant_id, through)
+    }
+
+    fn prune(&self, checkpoint: &PruneCheckpoint) -> Result<u64> {
+        (**self).prune(checkpoint)
+    }
+
+    fn prune_checkpoint(&self, tenant_id: &TenantId) -> Result<Option<PruneCheckpoint>> {
+        (**self).prune_checkpoint(tenant_id)
+    }
 }
 
 /// In-memory ledger storage — suitable for testing.
+///
+/// Blocks are indexed by id, agent, task, action and tool, so `get_block`
+/// and `query` do not scan whole chains.
 pub struct InMemoryLedgerStorage {
-    /// tenant_id → ordered list of blocks
-    blocks: RwLock<HashMap<String, Vec<LedgerBlock>>>,
+    /// tenant_id → indexed blocks in append order
+    tenants: RwLock<HashMap<TenantId, BlockIndex>>,
+    /// block id → owning tenant
+    owners: RwLock<HashMap<LedgerBlockId, TenantId>>,
+    /// tenant_id → latest prune checkpoint
+    checkpoints: RwLock<HashMap<TenantId, PruneCheckpoint>>,
 }
 
 impl InMemoryLedgerStorage {
     pub fn new() -> Self {
         Self {
-            blocks: RwLock::new(HashMap::new()),
+            tenants: RwLock::new(HashMap::new()),
+            owners: RwLock::new(HashMap::new()),
+            checkpoints: RwLock::new(HashMap::new()),
         }
     }
+
+    fn read(&self) -> Result<RwLockReadGuard<'_, HashMap<TenantId, BlockIndex>>> {
+        self.tenants.read().map_err(lock_poisoned)
+    }
 }
 
 impl Default for InMemoryLedgerStorage {
@@ -46,54 +138,100 @@ impl Default for InMemoryLedgerStorage {
     }
 }
 
+fn lock_poisoned<E: std::fmt::Display>(e: E) -> AetherError {
+    AetherError::internal(format!("ledger lock poisoned: {e}"))
+}
+
 impl LedgerStorage for InMemoryLedgerStorage {
     fn append(&self, block: LedgerBlock) -> Result<()> {
-        let key = block.tenant_id.to_string();
-        let mut store = self.blocks.write().map_err(|e| {
-            AetherError::internal(format!("ledger lock poisoned: {e}"))
-        })?;
-        store.entry(key).or_default().push(block);
+        let mut tenants = self.tenants.write().map_err(lock_poisoned)?;
+        let mut owners = self.owners.write().map_err(lock_poisoned)?;
+        owners.insert(block.id, block.tenant_id);
+        tenants.entry(block.tenant_id).or_default().push(block);
         Ok(())
     }
 
     fn get_blocks(&self, tenant_id: &TenantId) -> Result<Vec<LedgerBlock>> {
-        let store = self.blocks.read().map_err(|e| {
-            AetherError::internal(format!("ledger lock poisoned: {e}"))
-        })?;
-        Ok(store.get(&tenant_id.to_string()).cloned().unwrap_or_default())
+        Ok(self
+            .read()?
+            .get(tenant_id)
+            .map(|i| i.blocks().to_vec())
+            .unwrap_or_default())
     }
 
     fn get_block(&self, block_id: &LedgerBlockId) -> Result<LedgerBlock> {
-        let store = self.blocks.read().map_err(|e| {
-            AetherError::internal(format!("ledger lock poisoned: {e}"))
-        })?;
-        for blocks in store.values() {
-            if let Some(b) = blocks.iter().find(|b| b.id == *block_id) {
-                return Ok(b.clone());
-            }
-        }
-        Err(AetherError::not_found("LedgerBlock", block_id))
+        let owner = self
+            .owners
+            .read()
+            .map_err(lock_poisoned)?
+            .get(block_id)
+            .copied();
+        let tenants = self.read()?;
+        owner
+            .and_then(|t| tenants.get(&t)?.get(block_id).cloned())
+            .ok_or_else(|| AetherError::not_found("LedgerBlock", block_id))
     }
 
     fn count(&self, tenant_id: &TenantId) -> Result<u64> {
-        let store = self.blocks.read().map_err(|e| {
-            AetherError::internal(format!("ledger lock poisoned: {e}"))
-        })?;
-        Ok(store
-            .get(&tenant_id.to_string())
-            .map(|v| v.len() as u64)
+        Ok(self
+            .read()?
+            .get(tenant_id)
+            .map(|i| i.blocks().len() as u64)
             .unwrap_or(0))
     }
+
+    fn head(&self, tenant_id: &TenantId) -> Result<Option<LedgerBlock>> {
+        Ok(self
+            .read()?
+            .get(tenant_id)
+            .and_then(|i| i.blocks().last().cloned()))
+    }
+
+    fn query(&self, tenant_id: &TenantId, query: &LedgerQuery) -> Result<QueryPage> {
+        Ok(self
+            .read()?
+            .get(tenant_id)
+            .map(|i| i.query(query))
+            .unwrap_or(QueryPage {
+                blocks: Vec::new(),
+                next_cursor: None,
+            }))
+    }
+
+    fn prune(&self, checkpoint: &PruneCheckpoint) -> Result<u64> {
+        let mut tenants = self.tenants.write().map_err(lock_poisoned)?;
+        let index = tenants
+            .get_mut(&checkpoint.tenant_id)
+            .ok_or_else(|| AetherError::not_found("ledger tenant", checkpoint.tenant_id))?;
+        prunable(index.blocks(), checkpoint)?;
+        let removed = index.prune_through(checkpoint.head.sequence_number);
+        let mut owners = self.owners.write().map_err(lock_poisoned)?;
+        for block in &removed {
+            owners.remove(&block.id);
+        }
+        let mut checkpoints = self.checkpoints.write().map_err(lock_poisoned)?;
+        checkpoints.insert(checkpoint.tenant_id, checkpoint.clone());
+        Ok(removed.len() as u64)
+    }
+
+    fn prune_checkpoint(&self, tenant_id: &TenantId) -> Result<Option<PruneCheckpoint>> {
+        let checkpoints = self.checkpoints.read().map_err(lock_poisoned)?;
+        Ok(checkpoints.get(tenant_id).cloned())
+    }
 }
 
+/// Behaviour every `LedgerStorage` implementation must satisfy.
+///
+/// Each backend's tests call `run_all` (or the individual checks) so the
+/// in-memory and durable stores are held to the same contract.
 #[cfg(test)]
-mod tests {
+pub(crate) mod conformance {
     use super::*;
-    use aether_core::ids::{AgentId, LedgerBlockId, TaskId, TenantId};
-    use aether_core::ledger::{BlockHash, LedgerAction, LedgerBlock};
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::{BlockHash, LedgerAction, HASH_VERSION_CURRENT};
     use chrono::Utc;
 
-    fn make_block(tenant_id: TenantId, seq: u64) -> LedgerBlock {
+    pub fn make_block(tenant_id: TenantId, seq: u64) -> LedgerBlock {
         LedgerBlock {
             id: LedgerBlockId::new(),
             sequence_number: seq,
@@ -107,21 +245,30 @@ mod tests {
             output_hash: BlockHash("b".repeat(64)),
             parent_hash: BlockHash::genesis(),
             signature: None,
+            signer_key_id: None,
+            hash_version: HASH_VERSION_CURRENT,
+            refs: Vec::new(),
         }
     }
 
-    #[test]
-    fn test_append_and_count() {
-        let storage = InMemoryLedgerStorage::new();
+    pub fn run_all(storage: &dyn LedgerStorage) {
+        append_and_count(storage);
+        get_block_by_id(storage);
+        get_block_not_found(storage);
+        tenant_isolation(storage);
+        blocks_returned_in_append_order(storage);
+        head_is_last_block(storage);
+        query_filters_and_pages(storage);
+    }
+
+    pub fn append_and_count(storage: &dyn LedgerStorage) {
         let t = TenantId::new();
         storage.append(make_block(t, 1)).unwrap();
         storage.append(make_block(t, 2)).unwrap();
         assert_eq!(storage.count(&t).unwrap(), 2);
     }
 
-    #[test]
-    fn test_get_block_by_id() {
-        let storage = InMemoryLedgerStorage::new();
+    pub fn get_block_by_id(storage: &dyn LedgerStorage) {
         let t = TenantId::new();
         let block = make_block(t, 1);
         let id = block.id;
@@ -130,21 +277,106 @@ mod tests {
         assert_eq!(fetched.id, id);
     }
 
-    #[test]
-    fn test_get_block_not_found() {
-        let storage = InMemoryLedgerStorage::new();
+    pub fn get_block_not_found(storage: &dyn LedgerStorage) {
         let missing = LedgerBlockId::new();
         assert!(storage.get_block(&missing).is_err());
     }
 
-    #[test]
-    fn test_tenant_isolation() {
-        let storage = InMemoryLedgerStorage::new();
+    pub fn tenant_isolation(storage: &dyn LedgerStorage) {
         let t1 = TenantId::new();
         let t2 = TenantId::new();
         storage.append(make_block(t1, 1)).unwrap();
         // t2 has no blocks
         assert_eq!(storage.count(&t2).unwrap(), 0);
         assert_eq!(storage.count(&t1).unwrap(), 1);
+        assert!(storage.get_blocks(&t2).unwrap().is_empty());
+    }
+
+    pub fn blocks_returned_in_append_order(storage: &dyn LedgerStorage) {
+        let t = TenantId::new();
+        for seq in 1..=5 {
+            storage.append(make_block(t, seq)).unwrap();
+        }
+        let seqs: Vec<u64> = storage
+            .get_blocks(&t)
+            .unwrap()
+            .iter()
+            .map(|b| b.sequence_number)
+            .collect();
+        assert_eq!(seqs, [1, 2, 3, 4, 5]);
+    }
+
+    pub fn query_filters_and_pages(storage: &dyn LedgerStorage) {
+        let (t, other) = (TenantId::new(), TenantId::new());
+        let task = TaskId::new();
+        for seq in 1..=5 {
+            let block = make_block(t, seq);
+            let task_id = if seq % 2 == 1 { task } else { block.task_id };
+            storage.append(LedgerBlock { task_id, ..block }).unwrap();
+        }
+        storage
+            .append(LedgerBlock {
+                task_id: task,
+                ..make_block(other, 1)
+            })
+            .unwrap();
+
+        let q = LedgerQuery::new().task(task).limit(2);
+        let page = storage.query(&t, &q).unwrap();
+        let seqs: Vec<u64> = page.blocks.iter().map(|b| b.sequence_number).collect();
+        assert_eq!((seqs, page.next_cursor), (vec![1, 3], Some(3)));
+        let rest = storage.query(&t, &q.after(3)).unwrap();
+        assert_eq!(rest.blocks.len(), 1);
+        assert_eq!(
+            (rest.blocks[0].sequence_number, rest.next_cursor),
+            (5, None)
+        );
+        let unknown_agent = LedgerQuery::new().agent(AgentId::new());
+        assert!(storage.query(&t, &unknown_agent).unwrap().blocks.is_empty());
+    }
+
+    pub fn head_is_last_block(storage: &dyn LedgerStorage) {
+        let t = TenantId::new();
+        assert!(storage.head(&t).unwrap().is_none());
+        storage.append(make_block(t, 1)).unwrap();
+        let last = make_block(t, 2);
+        let last_id = last.id;
+        storage.append(last).unwrap();
+        assert_eq!(storage.head(&t).unwrap().map(|b| b.id), Some(last_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_count() {
+        conformance::append_and_count(&InMemoryLedgerStorage::new());
+    }
+
+    #[test]
+    fn test_get_block_by_id() {
+        conformance::get_block_by_id(&InMemoryLedgerStorage::new());
+    }
+
+    #[test]
+    fn test_get_block_not_found() {
+        conformance::get_block_not_found(&InMemoryLedgerStorage::new());
+    }
+
+    #[test]
+    fn test_tenant_isolation() {
+        conformance::tenant_isolation(&InMemoryLedgerStorage::new());
+    }
+
+    #[test]
+    fn test_query_filters_and_pages() {
+        conformance::query_filters_and_pages(&InMemoryLedgerStorage::new());
+    }
+
+    #[test]
+    fn test_conformance_suite() {
+        conformance::run_all(&InMemoryLedgerStorage::new());
     }
 }
@@ -0,0 +1,299 @@
+//! Streaming ledger subscriptions (PRD §14).
+//!
+//! A `LedgerFeed` holds one broadcast channel per tenant; a `LedgerWriter`
+//! with a feed publishes every block right after it is stored. A
+//! `LedgerSubscription` first replays matching blocks after its cursor from
+//! storage, then follows the live channel. Whenever the live stream skips
+//! ahead (the subscriber lagged and the channel dropped messages), the gap is
+//! refilled from storage, so blocks are always delivered once and in
+//! sequence order. Filters are ordinary `LedgerQuery` filters (action, agent,
+//! task, tool, reference). A tenant's channel is dropped once its last
+//! subscriber goes away.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::TenantId;
+use aether_core::ledger::LedgerBlock;
+
+use crate::query::{query_all, LedgerQuery};
+use crate::storage::LedgerStorage;
+
+/// Blocks buffered per tenant before slow subscribers start lagging.
+pub const DEFAULT_FEED_CAPACITY: usize = 1024;
+
+type Channels = HashMap<TenantId, broadcast::Sender<LedgerBlock>>;
+
+/// Per-tenant broadcast of newly appended blocks.
+pub struct LedgerFeed {
+    capacity: usize,
+    channels: Mutex<Channels>,
+}
+
+impl Default for LedgerFeed {
+    fn default() -> Self {
+        Self::new(DEFAULT_FEED_CAPACITY)
+    }
+}
+
+impl LedgerFeed {
+    /// `capacity` = live blocks buffered per tenant (minimum 1).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Announce a stored block to its tenant's subscribers.
+    ///
+    /// Never fails: the block is already durable, and subscribers that miss
+    /// it recover from storage.
+    pub fn publish(&self, block: &LedgerBlock) {
+        let mut channels = self.channels.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(sender) = channels.get(&block.tenant_id) else {
+            return;
+        };
+        if sender.receiver_count() == 0 {
+            channels.remove(&block.tenant_id);
+        } else {
+            // Receivers may drop concurrently; a failed send is harmless.
+            let _ = sender.send(block.clone());
+        }
+    }
+
+    /// Subscribe to `tenant_id`'s blocks matching `query`.
+    ///
+    /// Delivery starts after `query.after_sequence` (or from the start of
+    /// the stored chain); `query.limit` only sizes storage catch-up pages.
+    ///
+    /// # Errors
+    /// Returns `Conflict` if the cursor points into a pruned prefix, and
+    /// propagates storage errors.
+    pub fn subscribe<S: LedgerStorage>(
+        &self,
+        storage: S,
+        tenant_id: &TenantId,
+        query: LedgerQuery,
+    ) -> Result<LedgerSubscription<S>> {
+        // Subscribe before reading storage so no append falls between the two.
+        let mut channels = self.lock()?;
+        // Sweep tenants whose subscribers have all gone and that have not
+        // published since.
+        channels.retain(|_, sender| sender.receiver_count() > 0);
+        let receiver = channels
+            .entry(*tenant_id)
+            .or_insert_with(|| broadcast::channel(self.capacity).0)
+            .subscribe();
+        drop(channels);
+        let cursor = query.after_sequence.unwrap_or(0);
+        if let Some(checkpoint) = storage.prune_checkpoint(tenant_id)? {
+            if query.after_sequence.is_some() && cursor < checkpoint.head.sequence_number {
+                return Err(AetherError::Conflict(format!(
+                    "cursor {cursor} predates the prune checkpoint at seq {}",
+                    checkpoint.head.sequence_number
+                )));
+            }
+        }
+        let mut subscription = LedgerSubscription {
+            storage,
+            tenant_id: *tenant_id,
+            query,
+            receiver,
+            seen: cursor,
+            backlog: VecDeque::new(),
+        };
+        subscription.catch_up()?;
+        Ok(subscription)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Channels>> {
+        self.channels
+            .lock()
+            .map_err(|e| AetherError::internal(format!("ledger feed lock poisoned: {e}")))
+    }
+}
+
+/// An ordered stream of one tenant's matching blocks.
+pub struct LedgerSubscription<S: LedgerStorage> {
+    storage: S,
+    tenant_id: TenantId,
+    query: LedgerQuery,
+    receiver: broadcast::Receiver<LedgerBlock>,
+    /// Highest sequence number examined, matching or not.
+    seen: u64,
+    /// Matching blocks read from storage but not yet delivered.
+    backlog: VecDeque<LedgerBlock>,
+}
+
+impl<S: LedgerStorage> LedgerSubscription<S> {
+    /// Next matching block, waiting for a new append if necessary.
+    ///
+    /// Returns `None` once the feed is dropped.
+    ///
+    /// # Errors
+    /// Propagates storage errors from catch-up reads.
+    pub async fn next(&mut self) -> Result<Option<LedgerBlock>> {
+        loop {
+            if let Some(block) = self.backlog.pop_front() {
+                return Ok(Some(block));
+            }
+            match self.receiver.recv().await {
+                Ok(block) if block.sequence_number <= self.seen => {}
+                Ok(block) if block.sequence_number == self.seen + 1 => {
+                    self.seen = block.sequence_number;
+                    if self.query.matches(&block) {
+                        return Ok(Some(block));
+                    }
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => self.catch_up()?,
+                Err(RecvError::Closed) => return Ok(None),
+            }
+        }
+    }
+
+    /// Resume point: pass it to `LedgerQuery::after` to continue after the
+    /// last block this subscription delivered.
+    pub fn cursor(&self) -> u64 {
+        self.backlog
+            .front()
+            .map_or(self.seen, |b| b.sequence_number.saturating_sub(1))
+    }
+
+    /// Queue every stored match between the cursor and the current head.
+    fn catch_up(&mut self) -> Result<()> {
+        let Some(head) = self.storage.head(&self.tenant_id)? else {
+            return Ok(());
+        };
+        let through = head.sequence_number;
+        if through <= self.seen {
+            return Ok(());
+        }
+        let query = self.query.clone().after(self.seen);
+        let blocks = query_all(&self.storage, &self.tenant_id, &query)?;
+        self.backlog.extend(
+            blocks
+                .into_iter()
+                .take_while(|b| b.sequence_number <= through),
+        );
+        self.seen = through;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::block::LedgerBlockBuilder;
+    use crate::storage::InMemoryLedgerStorage;
+    use crate::writer::LedgerWriter;
+    use aether_core::ids::{AgentId, TaskId};
+    use aether_core::ledger::LedgerAction;
+
+    type Storage = Arc<InMemoryLedgerStorage>;
+
+    fn setup(capacity: usize) -> (Storage, Arc<LedgerFeed>, LedgerWriter<Storage>) {
+        let storage = Arc::new(InMemoryLedgerStorage::new());
+        let feed = Arc::new(LedgerFeed::new(capacity));
+        let writer = LedgerWriter::new(storage.clone()).with_feed(feed.clone());
+        (storage, feed, writer)
+    }
+
+    fn entry(tenant: TenantId, action: LedgerAction) -> LedgerBlockBuilder {
+        LedgerBlockBuilder::unlinked(tenant, AgentId::new(), TaskId::new(), action)
+    }
+
+    async fn next_seq(sub: &mut LedgerSubscription<Storage>) -> u64 {
+        let next = tokio::time::timeout(Duration::from_secs(1), sub.next()).await;
+        next.unwrap().unwrap().unwrap().sequence_number
+    }
+
+    #[tokio::test]
+    async fn test_replays_stored_then_follows_live_appends() {
+        let (storage, feed, writer) = setup(16);
+        let t = TenantId::new();
+        writer.append(entry(t, LedgerAction::ToolCall)).unwrap();
+        writer.append(entry(t, LedgerAction::Deploy)).unwrap();
+        let query = LedgerQuery::new().action(LedgerAction::ToolCall);
+        let mut sub = feed.subscribe(storage, &t, query).unwrap();
+        writer.append(entry(t, LedgerAction::ToolCall)).unwrap();
+        writer.append(entry(t, LedgerAction::MemoryWrite)).unwrap();
+        writer.append(entry(t, LedgerAction::ToolCall)).unwrap();
+
+        assert_eq!(next_seq(&mut sub).await, 1);
+        assert_eq!(next_seq(&mut sub).await, 3);
+        assert_eq!(next_seq(&mut sub).await, 5);
+        assert_eq!(sub.cursor(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_resumes_after_cursor() {
+        let (storage, feed, writer) = setup(16);
+        let t = TenantId::new();
+        for _ in 0..4 {
+            writer.append(entry(t, LedgerAction::ToolCall)).unwrap();
+        }
+        let mut sub = feed
+            .subscribe(storage, &t, LedgerQuery::new().after(2))
+            .unwrap();
+        assert_eq!(sub.cursor(), 2);
+        assert_eq!(next_seq(&mut sub).await, 3);
+        assert_eq!(next_seq(&mut sub).await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_lagged_subscriber_recovers_from_storage() {
+        let (storage, feed, writer) = setup(1);
+        let t = TenantId::new();
+        let mut sub = feed.subscribe(storage, &t, LedgerQuery::new()).unwrap();
+        for _ in 0..5 {
+            writer.append(entry(t, LedgerAction::ToolCall)).unwrap();
+        }
+        for expected in 1..=5 {
+            assert_eq!(next_seq(&mut sub).await, expected);
+        }
+    }
+
+    #[test]
+    fn test_channel_dropped_with_last_subscriber() {
+        let (storage, feed, writer) = setup(16);
+        let (t, other) = (TenantId::new(), TenantId::new());
+        let first = feed
+            .subscribe(storage.clone(), &t, LedgerQuery::new())
+            .unwrap();
+        let second = feed
+            .subscribe(storage.clone(), &t, LedgerQuery::new())
+            .unwrap();
+        drop(first);
+        writer.append(entry(t, LedgerAction::ToolCall)).unwrap();
+        assert_eq!(feed.lock().unwrap().len(), 1);
+        drop(second);
+        writer.append(entry(t, LedgerAction::ToolCall)).unwrap();
+        assert!(feed.lock().unwrap().is_empty());
+
+        let idle = feed
+            .subscribe(storage.clone(), &other, LedgerQuery::new())
+            .unwrap();
+        drop(idle);
+        let _live = feed.subscribe(storage, &t, LedgerQuery::new()).unwrap();
+        assert_eq!(feed.lock().unwrap().keys().collect::<Vec<_>>(), [&t]);
+    }
+
+    #[tokio::test]
+    async fn test_other_tenant_blocks_not_delivered() {
+        let (storage, feed, writer) = setup(16);
+        let (t, other) = (TenantId::new(), TenantId::new());
+        let mut sub = feed.subscribe(storage, &t, LedgerQuery::new()).unwrap();
+        writer.append(entry(other, LedgerAction::ToolCall)).unwrap();
+        let waited = tokio::time::timeout(Duration::from_millis(50), sub.next()).await;
+        assert!(waited.is_err());
+        writer.append(entry(t, LedgerAction::ToolCall)).unwrap();
+        assert_eq!(next_seq(&mut sub).await, 1);
+    }
+}
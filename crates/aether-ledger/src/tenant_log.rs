@@ -0,0 +1,229 @@
+//! Per-tenant state of `FileLedgerStorage` (PRD §14).
+//!
+//! A `TenantLog` is one tenant's replayed chain, the start sequence of each
+//! segment on disk, the open segment appends go to, and the latest prune
+//! checkpoint. Pruning drops whole segments, never the active one. The
+//! checkpoint is written before any segment is deleted, so recovery can
+//! finish an interrupted prune by discarding blocks the checkpoint covers.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ledger::LedgerBlock;
+
+use crate::file_storage::{FileLedgerConfig, FsyncPolicy};
+use crate::prune::{prunable, PruneCheckpoint};
+use crate::segment::{
+    decode_records, encode_record, io_error, list_segments, read_checkpoint,
+    remove_segments_through, segment_first_sequence, segment_path, write_checkpoint,
+};
+
+#[cfg(test)]
+thread_local! {
+    /// Test hook: the next write stores only this many bytes, then fails.
+    pub(crate) static FAIL_WRITE_AFTER: std::cell::Cell<Option<usize>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// Active segment being appended to.
+pub(crate) struct ActiveSegment {
+    pub file: File,
+    pub bytes: u64,
+    pub unsynced: u32,
+}
+
+impl ActiveSegment {
+    /// Create the segment file starting at `first_sequence` in `dir`.
+    fn create(dir: &Path, first_sequence: u64) -> Result<Self> {
+        fs::create_dir_all(dir).map_err(io_error)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(segment_path(dir, first_sequence))
+            .map_err(io_error)?;
+        Ok(Self {
+            file,
+            bytes: 0,
+            unsynced: 0,
+        })
+    }
+
+    /// Append `record`, fsyncing per `fsync`.
+    fn write(&mut self, record: &[u8], fsync: FsyncPolicy) -> Result<()> {
+        write_bytes(&mut self.file, record).map_err(io_error)?;
+        self.bytes += record.len() as u64;
+        self.unsynced += 1;
+        let sync = match fsync {
+            FsyncPolicy::Always => true,
+            FsyncPolicy::EveryN(n) => self.unsynced >= n.max(1),
+            FsyncPolicy::Never => false,
+        };
+        if sync {
+            self.file.sync_data().map_err(io_error)?;
+            self.unsynced = 0;
+        }
+        Ok(())
+    }
+
+    /// Cut the file back to `len` bytes, discarding a failed write.
+    pub fn truncate(&mut self, len: u64) -> Result<()> {
+        self.file.set_len(len).map_err(io_error)?;
+        self.file.sync_data().map_err(io_error)?;
+        self.bytes = len;
+        Ok(())
+    }
+}
+
+/// One tenant's replayed chain plus its open segment.
+#[derive(Default)]
+pub(crate) struct TenantLog {
+    pub blocks: Vec<LedgerBlock>,
+    pub active: Option<ActiveSegment>,
+    /// First sequence number of each segment on disk, ascending.
+    pub segments: Vec<u64>,
+    pub checkpoint: Option<PruneCheckpoint>,
+    /// A failed write could not be rolled back; appends are refused.
+    pub poisoned: bool,
+}
+
+impl TenantLog {
+    /// Replay a tenant's segments, truncating a torn tail on the last one.
+    ///
+    /// Bad bytes are only a torn tail if no valid record follows them;
+    /// otherwise truncating would drop acknowledged blocks, so the segment
+    /// is reported as corrupt.
+    pub fn recover(dir: &Path, read_only: bool) -> Result<Self> {
+        let segments = list_segments(dir)?;
+        let mut log = Self::default();
+        for (i, path) in segments.iter().enumerate() {
+            let bytes = fs::read(path).map_err(io_error)?;
+            let decoded = decode_records(&bytes);
+            let is_last = i + 1 == segments.len();
+            let torn_tail = is_last && !decoded.records_after_damage;
+            if decoded.torn && !torn_tail {
+                return Err(AetherError::StorageError(format!(
+                    "corrupt ledger segment {}",
+                    path.display()
+                )));
+            }
+            if decoded.torn && !read_only {
+                tracing::warn!(segment = %path.display(), "truncating torn ledger tail");
+                let file = OpenOptions::new()
+                    .write(true)
+                    .open(path)
+                    .map_err(io_error)?;
+                file.set_len(decoded.valid_len).map_err(io_error)?;
+                file.sync_all().map_err(io_error)?;
+            }
+            log.blocks.extend(decoded.blocks);
+            log.segments.push(segment_first_sequence(path)?);
+            if is_last && !read_only {
+                let file = OpenOptions::new()
+                    .append(true)
+                    .open(path)
+                    .map_err(io_error)?;
+                log.active = Some(ActiveSegment {
+                    file,
+                    bytes: decoded.valid_len,
+                    unsynced: 0,
+                });
+            }
+        }
+        log.apply_checkpoint(dir, read_only)?;
+        Ok(log)
+    }
+
+    /// Write one record to the active segment in `dir`, rolling over if full.
+    ///
+    /// A failed write is truncated away before the error is returned, so
+    /// later appends never land behind a partial record; if that fails too,
+    /// the log refuses appends until reopened.
+    pub fn write(
+        &mut self,
+        dir: &Path,
+        config: &FileLedgerConfig,
+        block: &LedgerBlock,
+    ) -> Result<()> {
+        if self.poisoned {
+            return Err(AetherError::StorageError(format!(
+                "ledger for tenant {} failed to roll back a write; reopen to recover",
+                block.tenant_id
+            )));
+        }
+        let record = encode_record(block)?;
+        let full = self.active.as_ref().is_none_or(|a| {
+            a.bytes > 0 && a.bytes + record.len() as u64 > config.max_segment_bytes
+        });
+        if full {
+            if let Some(old) = self.active.take() {
+                old.file.sync_all().map_err(io_error)?;
+            }
+            self.active = Some(ActiveSegment::create(dir, block.sequence_number)?);
+            self.segments.push(block.sequence_number);
+        }
+        let active = self
+            .active
+            .as_mut()
+            .ok_or_else(|| AetherError::internal("ledger segment not open"))?;
+        let start = active.bytes;
+        let Err(e) = active.write(&record, config.fsync) else {
+            return Ok(());
+        };
+        if let Err(rollback) = active.truncate(start) {
+            tracing::error!(error = %rollback, "ledger write rollback failed");
+            self.poisoned = true;
+        }
+        Err(e)
+    }
+
+    /// Hide blocks covered by the prune checkpoint, finishing an interrupted prune.
+    fn apply_checkpoint(&mut self, dir: &Path, read_only: bool) -> Result<()> {
+        if let Some(checkpoint) = read_checkpoint(dir)? {
+            let through = checkpoint.head.sequence_number;
+            self.blocks.retain(|b| b.sequence_number > through);
+            if !read_only {
+                remove_segments_through(dir, &mut self.segments, through)?;
+            }
+            self.checkpoint = Some(checkpoint);
+        }
+        Ok(())
+    }
+
+    /// The end of the newest complete segment at or before `through`.
+    pub fn prune_boundary(&self, through: u64) -> Option<u64> {
+        self.segments
+            .iter()
+            .skip(1)
+            .map(|next| next - 1)
+            .take_while(|&end| end <= through)
+            .last()
+    }
+
+    /// Persist `checkpoint`, then delete the segments it covers.
+    pub fn prune(&mut self, dir: &Path, checkpoint: &PruneCheckpoint) -> Result<u64> {
+        let count = prunable(&self.blocks, checkpoint)?;
+        let through = checkpoint.head.sequence_number;
+        if !self.segments.contains(&(through + 1)) {
+            return Err(AetherError::ValidationFailed {
+                field: "prune_checkpoint".into(),
+                reason: format!("seq {through} does not end a ledger segment"),
+            });
+        }
+        write_checkpoint(dir, checkpoint)?;
+        remove_segments_through(dir, &mut self.segments, through)?;
+        self.blocks.drain(..count);
+        self.checkpoint = Some(checkpoint.clone());
+        Ok(count as u64)
+    }
+}
+
+fn write_bytes(file: &mut File, record: &[u8]) -> std::io::Result<()> {
+    #[cfg(test)]
+    if let Some(n) = FAIL_WRITE_AFTER.take() {
+        file.write_all(&record[..n.min(record.len())])?;
+        return Err(std::io::Error::other("injected write failure"));
+    }
+    file.write_all(record)
+}
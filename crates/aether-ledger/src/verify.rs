@@ -1,12 +1,23 @@
 //! High-level verification API — combines chain + storage (PRD §14).
 //!
 //! `LedgerVerifier` is the entry point for verifying a tenant's full chain
-//! or auditing specific block ranges.
+//! or auditing specific block ranges. `verify_tenant` fails fast on the first
+//! broken link; `scan_tenant` and `scan_from` walk every block and report all
+//! anomalies plus the range that can still be trusted. A pruned chain is
+//! verified from its `PruneCheckpoint` instead of genesis; only the signed
+//! mode authenticates the checkpoint, and the others flag their report with
+//! `unverified_checkpoint`.
+
+use serde::{Deserialize, Serialize};
 
 use aether_core::error::Result;
 use aether_core::ids::TenantId;
+use aether_core::ledger::LedgerBlock;
 
-use crate::chain::verify_chain;
+use crate::anomaly::ChainAnomaly;
+use crate::chain::{verify_chain_from, verify_chain_signed_from, ChainHead};
+use crate::scan::scan_chain;
+use crate::signing::{LedgerKeyring, SignerRecord};
 use crate::storage::LedgerStorage;
 
 /// Verifies a tenant's full ledger chain from storage.
@@ -21,36 +32,156 @@ impl<S: LedgerStorage> LedgerVerifier<S> {
 
     /// Fetch all blocks for a tenant and verify the hash chain.
     ///
+    /// Starts from the stored prune checkpoint, if any, without checking its
+    /// signature and sets `unverified_checkpoint`; `verify_tenant_signed`
+    /// authenticates it.
+    ///
     /// # Errors
     /// Returns `LedgerIntegrityViolation` if chain is broken.
     pub fn verify_tenant(&self, tenant_id: &TenantId) -> Result<VerificationReport> {
+        let (start, unverified) = self.stored_start(tenant_id)?;
         let blocks = self.storage.get_blocks(tenant_id)?;
-        let count = blocks.len() as u64;
-        verify_chain(&blocks)?;
-        Ok(VerificationReport {
-            tenant_id: *tenant_id,
-            blocks_verified: count,
-            intact: true,
+        verify_chain_from(&blocks, &start)?;
+        let mut report = VerificationReport::intact(*tenant_id, &blocks, Vec::new());
+        report.unverified_checkpoint = unverified;
+        Ok(report)
+    }
+
+    /// Verify the hash chain and every block's Ed25519 signature, plus the
+    /// prune checkpoint's signature if the chain was pruned.
+    ///
+    /// # Errors
+    /// Returns `LedgerIntegrityViolation` if the chain is broken or any block
+    /// or checkpoint is unsigned or wrongly signed.
+    pub fn verify_tenant_signed(
+        &self,
+        tenant_id: &TenantId,
+        keyring: &LedgerKeyring,
+    ) -> Result<VerificationReport> {
+        let start = match self.storage.prune_checkpoint(tenant_id)? {
+            Some(checkpoint) => {
+                checkpoint.verify_signature(keyring)?;
+                checkpoint.head
+            }
+            None => ChainHead::genesis(),
+        };
+        let blocks = self.storage.get_blocks(tenant_id)?;
+        let signers = verify_chain_signed_from(&blocks, &start, keyring)?;
+        Ok(VerificationReport::intact(*tenant_id, &blocks, signers))
+    }
+
+    /// Scan the whole stored chain (from its prune checkpoint, if any) and
+    /// collect every anomaly instead of failing.
+    ///
+    /// Like `verify_tenant`, the checkpoint's signature is not checked and
+    /// the report sets `unverified_checkpoint`.
+    ///
+    /// # Errors
+    /// Only storage errors; integrity problems are reported, with `intact`
+    /// set to false.
+    pub fn scan_tenant(&self, tenant_id: &TenantId) -> Result<VerificationReport> {
+        let (start, unverified) = self.stored_start(tenant_id)?;
+        let mut report = self.scan_from(tenant_id, &start)?;
+        report.unverified_checkpoint = unverified;
+        Ok(report)
+    }
+
+    /// Scan the blocks after a trusted head, e.g. one recorded at a checkpoint.
+    ///
+    /// Blocks at or below `trusted.sequence_number` are skipped; the first
+    /// scanned block must link to `trusted.hash`.
+    ///
+    /// # Errors
+    /// Only storage errors.
+    pub fn scan_from(
+        &self,
+        tenant_id: &TenantId,
+        trusted: &ChainHead,
+    ) -> Result<VerificationReport> {
+        let blocks = self.storage.get_blocks(tenant_id)?;
+        let start = blocks
+            .iter()
+            .position(|b| b.sequence_number > trusted.sequence_number)
+            .unwrap_or(blocks.len());
+        Ok(VerificationReport::scanned(
+            *tenant_id,
+            &blocks[start..],
+            trusted,
+        ))
+    }
+
+    /// `chain_start`, plus whether it is an unauthenticated prune checkpoint.
+    fn stored_start(&self, tenant_id: &TenantId) -> Result<(ChainHead, bool)> {
+        Ok(match self.storage.prune_checkpoint(tenant_id)? {
+            Some(checkpoint) => (checkpoint.head, true),
+            None => (ChainHead::genesis(), false),
         })
     }
 }
 
 /// Result of a ledger verification.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerificationReport {
     pub tenant_id: TenantId,
     pub blocks_verified: u64,
+    /// False if any anomaly was found (only possible from the scan modes).
     pub intact: bool,
+    /// Which key signed each block (only populated by signed verification).
+    pub signers: Vec<SignerRecord>,
+    /// Every problem found, in chain order (only populated by the scan modes).
+    pub anomalies: Vec<ChainAnomaly>,
+    /// Sequence of the first block in the trusted prefix, if any block is trusted.
+    pub first_good_sequence: Option<u64>,
+    /// Sequence of the last block before the first anomaly.
+    pub last_good_sequence: Option<u64>,
+    /// True when verification started from a stored prune checkpoint whose
+    /// signature was not checked, so the pruned prefix is taken on trust.
+    #[serde(default)]
+    pub unverified_checkpoint: bool,
+}
+
+impl VerificationReport {
+    fn intact(tenant_id: TenantId, blocks: &[LedgerBlock], signers: Vec<SignerRecord>) -> Self {
+        Self {
+            tenant_id,
+            blocks_verified: blocks.len() as u64,
+            intact: true,
+            signers,
+            anomalies: Vec::new(),
+            first_good_sequence: blocks.first().map(|b| b.sequence_number),
+            last_good_sequence: blocks.last().map(|b| b.sequence_number),
+            unverified_checkpoint: false,
+        }
+    }
+
+    fn scanned(tenant_id: TenantId, blocks: &[LedgerBlock], start: &ChainHead) -> Self {
+        let findings = scan_chain(&tenant_id, blocks, start);
+        let good_len = findings.first().map_or(blocks.len(), |f| f.index);
+        let good = &blocks[..good_len];
+        Self {
+            tenant_id,
+            blocks_verified: blocks.len() as u64,
+            intact: findings.is_empty(),
+            signers: Vec::new(),
+            anomalies: findings.into_iter().flat_map(|f| f.anomalies).collect(),
+            first_good_sequence: good.first().map(|b| b.sequence_number),
+            last_good_sequence: good.last().map(|b| b.sequence_number),
+            unverified_checkpoint: false,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use aether_core::ids::{AgentId, LedgerBlockId, TaskId, TenantId};
-    use aether_core::ledger::{BlockHash, LedgerAction, LedgerBlock};
-    use crate::chain::compute_block_hash;
+    use crate::chain::ChainHead;
+    use crate::signing::Ed25519Signer;
+    use crate::signing_storage::SigningLedgerStorage;
     use crate::storage::InMemoryLedgerStorage;
+    use aether_core::ids::{AgentId, LedgerBlockId, TaskId, TenantId};
+    use aether_core::ledger::{BlockHash, LedgerAction, LedgerBlock, HASH_VERSION_CURRENT};
     use chrono::Utc;
+    use std::sync::Arc;
 
     fn make_block(tenant: TenantId, seq: u64, parent: BlockHash) -> LedgerBlock {
         LedgerBlock {
@@ -66,6 +197,9 @@ mod tests {
             output_hash: BlockHash("b".repeat(64)),
             parent_hash: parent,
             signature: None,
+            signer_key_id: None,
+            hash_version: HASH_VERSION_CURRENT,
+            refs: Vec::new(),
         }
     }
 
@@ -91,7 +225,7 @@ mod tests {
 
         let verifier = LedgerVerifier::new(storage);
         let report = verifier.verify_tenant(&t).unwrap();
-        assert!(report.intact);
+        assert!(report.intact && !report.unverified_checkpoint);
         assert_eq!(report.blocks_verified, 2);
     }
 
@@ -108,4 +242,86 @@ mod tests {
         let verifier = LedgerVerifier::new(storage);
         assert!(verifier.verify_tenant(&t).is_err());
     }
+
+    #[test]
+    fn test_signed_verification_reports_signers() {
+        let t = TenantId::new();
+        let signer = Arc::new(Ed25519Signer::from_seed("node", &[1; 32]));
+        let keyring = Arc::new(LedgerKeyring::new().with_node_signer(signer));
+        let storage = SigningLedgerStorage::new(InMemoryLedgerStorage::new(), keyring.clone());
+        let b1 = make_block(t, 1, BlockHash::genesis());
+        let b2 = make_block(t, 2, crate::chain::compute_block_hash(&b1));
+        storage.append(b1).unwrap();
+        storage.append(b2).unwrap();
+
+        let verifier = LedgerVerifier::new(storage);
+        let report = verifier.verify_tenant_signed(&t, &keyring).unwrap();
+        assert_eq!(report.signers.len(), 2);
+        assert_eq!(report.signers[1].key_id, "node");
+    }
+
+    #[test]
+    fn test_signed_verification_rejects_unsigned_chain() {
+        let storage = InMemoryLedgerStorage::new();
+        let t = TenantId::new();
+        storage
+            .append(make_block(t, 1, BlockHash::genesis()))
+            .unwrap();
+        let keyring = LedgerKeyring::new();
+
+        let verifier = LedgerVerifier::new(storage);
+        assert!(verifier.verify_tenant_signed(&t, &keyring).is_err());
+    }
+
+    #[test]
+    fn test_scan_reports_all_anomalies_and_good_prefix() {
+        let storage = InMemoryLedgerStorage::new();
+        let t = TenantId::new();
+        let b1 = make_block(t, 1, BlockHash::genesis());
+        let b2 = make_block(t, 2, crate::chain::compute_block_hash(&b1));
+        let b3 = make_block(t, 3, BlockHash("0".repeat(64)));
+        let b4 = make_block(t, 5, crate::chain::compute_block_hash(&b3));
+        for b in [b1, b2, b3, b4] {
+            storage.append(b).unwrap();
+        }
+
+        let report = LedgerVerifier::new(storage).scan_tenant(&t).unwrap();
+        assert!(!report.intact);
+        assert_eq!(report.blocks_verified, 4);
+        assert_eq!(report.anomalies.len(), 2);
+        assert!(matches!(
+            report.anomalies[0],
+            ChainAnomaly::ParentMismatch { .. }
+        ));
+        assert!(matches!(
+            report.anomalies[1],
+            ChainAnomaly::SequenceGap { expected: 4, .. }
+        ));
+        assert_eq!(
+            (report.first_good_sequence, report.last_good_sequence),
+            (Some(1), Some(2))
+        );
+    }
+
+    #[test]
+    fn test_scan_from_trusted_head_skips_prefix() {
+        let storage = InMemoryLedgerStorage::new();
+        let t = TenantId::new();
+        // b1 is corrupt, but the audit starts from a head trusted past it.
+        let b1 = make_block(t, 1, BlockHash("1".repeat(64)));
+        let trusted = ChainHead::of(&b1);
+        let b2 = make_block(t, 2, trusted.hash.clone());
+        storage.append(b1).unwrap();
+        storage.append(b2).unwrap();
+
+        let verifier = LedgerVerifier::new(storage);
+        assert!(!verifier.scan_tenant(&t).unwrap().intact);
+        let report = verifier.scan_from(&t, &trusted).unwrap();
+        assert!(report.intact);
+        assert_eq!(report.blocks_verified, 1);
+        assert_eq!(
+            (report.first_good_sequence, report.last_good_sequence),
+            (Some(2), Some(2))
+        );
+    }
 }
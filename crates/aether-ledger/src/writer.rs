@@ -0,0 +1,307 @@
+//! Chained, serialized appends on top of `LedgerStorage` (PRD §14).
+//!
+//! `LedgerWriter` owns chain positioning: it assigns `sequence_number` and
+//! `parent_hash` from the tenant's current head, so callers never compute them.
+//! Appends for one tenant are serialized behind a per-tenant lock; different
+//! tenants append in parallel.
+//!
+//! With a `PayloadStore` attached, each block's input and output payloads are
+//! stored before the block itself, so every appended block can be replayed.
+//! With a `LedgerFeed` attached, each stored block is published to live
+//! subscribers while the tenant lock is still held, so they see chain order.
+//!
+//! The head is cached after the first read from storage, so every append for
+//! a tenant must go through the same writer — direct `LedgerStorage::append`
+//! calls would not be seen and would fork the chain.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::TenantId;
+use aether_core::ledger::LedgerBlock;
+
+use crate::block::LedgerBlockBuilder;
+use crate::chain::ChainHead;
+use crate::compensation::validate_refs;
+use crate::payload::PayloadStore;
+use crate::signing::{sign_block, LedgerKeyring};
+use crate::storage::LedgerStorage;
+use crate::subscribe::LedgerFeed;
+
+/// Cached head for one tenant; `None` until first loaded from storage.
+type HeadSlot = Arc<Mutex<Option<ChainHead>>>;
+
+/// Serializes appends per tenant and links each block to the current head.
+pub struct LedgerWriter<S: LedgerStorage> {
+    storage: S,
+    keyring: Option<Arc<LedgerKeyring>>,
+    payloads: Option<Arc<dyn PayloadStore>>,
+    feed: Option<Arc<LedgerFeed>>,
+    heads: Mutex<HashMap<TenantId, HeadSlot>>,
+}
+
+impl<S: LedgerStorage> LedgerWriter<S> {
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage,
+            keyring: None,
+            payloads: None,
+            feed: None,
+            heads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sign every appended block with the tenant's key from `keyring`.
+    pub fn with_keyring(mut self, keyring: Arc<LedgerKeyring>) -> Self {
+        self.keyring = Some(keyring);
+        self
+    }
+
+    /// Keep every block's full input/output payloads in `payloads` for replay.
+    pub fn with_payload_store(mut self, payloads: Arc<dyn PayloadStore>) -> Self {
+        self.payloads = Some(payloads);
+        self
+    }
+
+    /// Publish every appended block to `feed` subscribers.
+    pub fn with_feed(mut self, feed: Arc<LedgerFeed>) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    /// Underlying storage (read access for verifiers and queries).
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Consume the writer, returning its storage.
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+
+    /// Current head of a tenant's chain.
+    ///
+    /// # Errors
+    /// Propagates storage errors from the initial head load.
+    pub fn head(&self, tenant_id: &TenantId) -> Result<ChainHead> {
+        let slot = self.slot(tenant_id)?;
+        let mut head = lock(&slot)?;
+        self.loaded_head(tenant_id, &mut head)
+    }
+
+    /// Append a block at the tenant's current head.
+    ///
+    /// # Errors
+    /// Returns storage or signing errors, or the `validate_refs` error for a
+    /// bad block reference; the head is unchanged on failure.
+    pub fn append(&self, builder: LedgerBlockBuilder) -> Result<LedgerBlock> {
+        self.append_checked(builder, None)
+    }
+
+    /// Append only if the tenant's head still equals `expected_head`.
+    ///
+    /// # Errors
+    /// Returns `Conflict` if another append moved the head since the caller
+    /// read it, so the caller can re-read and decide whether to retry.
+    pub fn compare_and_append(
+        &self,
+        builder: LedgerBlockBuilder,
+        expected_head: &ChainHead,
+    ) -> Result<LedgerBlock> {
+        self.append_checked(builder, Some(expected_head))
+    }
+
+    fn append_checked(
+        &self,
+        builder: LedgerBlockBuilder,
+        expected: Option<&ChainHead>,
+    ) -> Result<LedgerBlock> {
+        let tenant_id = builder.tenant_id();
+        let slot = self.slot(&tenant_id)?;
+        let mut guard = lock(&slot)?;
+        let head = self.loaded_head(&tenant_id, &mut guard)?;
+        if let Some(expected) = expected.filter(|e| **e != head) {
+            return Err(AetherError::Conflict(format!(
+                "ledger head for tenant {tenant_id} is at seq {}, expected seq {}",
+                head.sequence_number, expected.sequence_number
+            )));
+        }
+        if let Some(id) = builder
+            .preset_id()
+            .filter(|id| self.storage.get_block(id).is_ok())
+        {
+            return Err(AetherError::Conflict(format!(
+                "ledger block {id} already exists"
+            )));
+        }
+        validate_refs(&self.storage, &tenant_id, builder.action(), builder.refs())?;
+        self.store_payloads(&tenant_id, &builder)?;
+        let sequence_number = head.next_sequence();
+        let mut block = builder.link(head.hash, sequence_number).build();
+        self.sign(&mut block)?;
+        self.storage.append(block.clone())?;
+        *guard = Some(ChainHead::of(&block));
+        if let Some(feed) = &self.feed {
+            feed.publish(&block);
+        }
+        Ok(block)
+    }
+
+    fn sign(&self, block: &mut LedgerBlock) -> Result<()> {
+        let Some(keyring) = &self.keyring else {
+            return Ok(());
+        };
+        let signer = keyring.signer_for(&block.tenant_id).ok_or_else(|| {
+            AetherError::internal(format!(
+                "no ledger signing key for tenant {}",
+                block.tenant_id
+            ))
+        })?;
+        sign_block(block, signer.as_ref());
+        Ok(())
+    }
+
+    fn store_payloads(&self, tenant_id: &TenantId, builder: &LedgerBlockBuilder) -> Result<()> {
+        let Some(store) = &self.payloads else {
+            return Ok(());
+        };
+        for payload in builder.payloads() {
+            store.put(tenant_id, payload)?;
+        }
+        Ok(())
+    }
+
+    fn slot(&self, tenant_id: &TenantId) -> Result<HeadSlot> {
+        let mut heads = lock(&self.heads)?;
+        Ok(heads.entry(*tenant_id).or_default().clone())
+    }
+
+    fn loaded_head(
+        &self,
+        tenant_id: &TenantId,
+        cached: &mut Option<ChainHead>,
+    ) -> Result<ChainHead> {
+        if let Some(head) = cached {
+            return Ok(head.clone());
+        }
+        let head = self
+            .storage
+            .head(tenant_id)?
+            .map(|b| ChainHead::of(&b))
+            .unwrap_or_else(ChainHead::genesis);
+        *cached = Some(head.clone());
+        Ok(head)
+    }
+}
+
+fn lock<T>(m: &Mutex<T>) -> Result<std::sync::MutexGuard<'_, T>> {
+    m.lock()
+        .map_err(|e| AetherError::internal(format!("ledger writer lock poisoned: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::{verify_chain, verify_chain_signed};
+    use crate::signing::Ed25519Signer;
+    use crate::storage::InMemoryLedgerStorage;
+    use aether_core::error::ErrorCode;
+    use aether_core::ids::{AgentId, LedgerBlockId, TaskId};
+    use aether_core::ledger::LedgerAction;
+
+    fn entry(tenant: TenantId) -> LedgerBlockBuilder {
+        LedgerBlockBuilder::unlinked(
+            tenant,
+            AgentId::new(),
+            TaskId::new(),
+            LedgerAction::ToolCall,
+        )
+    }
+
+    #[test]
+    fn test_append_assigns_sequence_and_parent() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let t = TenantId::new();
+        let b1 = writer.append(entry(t)).unwrap();
+        let b2 = writer.append(entry(t)).unwrap();
+        assert_eq!((b1.sequence_number, b2.sequence_number), (1, 2));
+        assert_eq!(writer.head(&t).unwrap(), ChainHead::of(&b2));
+        assert!(verify_chain(&writer.storage().get_blocks(&t).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_compare_and_append_rejects_stale_head() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let t = TenantId::new();
+        let stale = writer.head(&t).unwrap();
+        writer.compare_and_append(entry(t), &stale).unwrap();
+        let err = writer.compare_and_append(entry(t), &stale).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Conflict);
+        assert_eq!(writer.storage().count(&t).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_resumes_from_existing_storage_head() {
+        let storage = Arc::new(InMemoryLedgerStorage::new());
+        let t = TenantId::new();
+        LedgerWriter::new(storage.clone()).append(entry(t)).unwrap();
+        let b2 = LedgerWriter::new(storage.clone()).append(entry(t)).unwrap();
+        assert_eq!(b2.sequence_number, 2);
+        assert!(verify_chain(&storage.get_blocks(&t).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_tenants_have_independent_sequences() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let (t1, t2) = (TenantId::new(), TenantId::new());
+        writer.append(entry(t1)).unwrap();
+        writer.append(entry(t1)).unwrap();
+        assert_eq!(writer.append(entry(t2)).unwrap().sequence_number, 1);
+    }
+
+    #[test]
+    fn test_concurrent_appends_stay_linear() {
+        let writer = Arc::new(LedgerWriter::new(InMemoryLedgerStorage::new()));
+        let t = TenantId::new();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let writer = writer.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        writer.append(entry(t)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        let blocks = writer.storage().get_blocks(&t).unwrap();
+        let seqs: Vec<u64> = blocks.iter().map(|b| b.sequence_number).collect();
+        assert_eq!(seqs, (1..=200).collect::<Vec<_>>());
+        assert!(verify_chain(&blocks).is_ok());
+    }
+
+    #[test]
+    fn test_preset_id_used_once() {
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let t = TenantId::new();
+        let id = LedgerBlockId::new();
+        assert_eq!(writer.append(entry(t).id(id)).unwrap().id, id);
+        let err = writer.append(entry(TenantId::new()).id(id)).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Conflict);
+    }
+
+    #[test]
+    fn test_keyring_signs_appended_blocks() {
+        let signer = Arc::new(Ed25519Signer::from_seed("node", &[5; 32]));
+        let keyring = Arc::new(LedgerKeyring::new().with_node_signer(signer));
+        let writer = LedgerWriter::new(InMemoryLedgerStorage::new()).with_keyring(keyring.clone());
+        let t = TenantId::new();
+        writer.append(entry(t)).unwrap();
+        writer.append(entry(t)).unwrap();
+        let blocks = writer.storage().get_blocks(&t).unwrap();
+        assert_eq!(verify_chain_signed(&blocks, &keyring).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,358 @@
+//! Human approval requests for held tool calls (PRD §11).
+//!
+//! When a rule's effect is `RequireApproval`, the `ApprovalGate` files an
+//! `ApprovalRequest` naming the tool, a hash of the call's arguments, the
+//! requesting agent and an expiry. A user holding at least the rule's
+//! `approver_role` approves or rejects it; an approval is a single-use
+//! token that lets exactly that call through once.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{AgentId, ApprovalId, LedgerBlockId, TaskId, TenantId, ToolId, UserId};
+use aether_core::ledger::LedgerAction;
+use aether_core::tenant::UserRole;
+use aether_ledger::LedgerBlockBuilder;
+
+use crate::evaluation::{EvaluationContext, PolicyDecision};
+
+/// Source of the current time for the approval lifecycle.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The host's system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// The user approving or rejecting a request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reviewer {
+    pub user_id: UserId,
+    pub role: UserRole,
+}
+
+/// Where a request is in its lifecycle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    Pending,
+    /// Approved and not yet used.
+    Approved {
+        reviewer: UserId,
+        review_block: LedgerBlockId,
+    },
+    Rejected {
+        reviewer: UserId,
+        review_block: LedgerBlockId,
+        reason: String,
+    },
+    /// Approved and used by one call.
+    Redeemed {
+        reviewer: UserId,
+        review_block: LedgerBlockId,
+        redeemed_at: DateTime<Utc>,
+    },
+}
+
+impl ApprovalStatus {
+    /// The status after `reviewer` approves, or rejects with a reason.
+    pub fn reviewed(
+        reviewer: UserId,
+        review_block: LedgerBlockId,
+        rejection: Option<String>,
+    ) -> Self {
+        match rejection {
+            None => Self::Approved {
+                reviewer,
+                review_block,
+            },
+            Some(reason) => Self::Rejected {
+                reviewer,
+                review_block,
+                reason,
+            },
+        }
+    }
+}
+
+/// A held tool call awaiting a human decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub id: ApprovalId,
+    pub tenant_id: TenantId,
+    pub agent_id: AgentId,
+    pub task_id: TaskId,
+    pub tool_id: ToolId,
+    /// `args_hash` of the held call's arguments.
+    pub args_hash: String,
+    /// Rule that required the approval.
+    pub rule_id: String,
+    pub reason: String,
+    pub approver_role: UserRole,
+    pub requested_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub status: ApprovalStatus,
+}
+
+impl ApprovalRequest {
+    /// A pending request for the tool call in `ctx` that `decision` held,
+    /// filed at `now` and expiring `ttl_secs` later. It is bound to the
+    /// arguments in `ctx`, the ones policy evaluated.
+    ///
+    /// # Errors
+    /// `ValidationFailed` if `ctx` is not a tool call or carries no
+    /// arguments.
+    pub fn new(
+        ctx: &EvaluationContext,
+        decision: &PolicyDecision,
+        approver_role: UserRole,
+        ttl_secs: u64,
+        now: DateTime<Utc>,
+    ) -> Result<Self> {
+        let (tool_id, agent_id, task_id) = tool_call(ctx)?;
+        let args_hash = call_args_hash(ctx)?;
+        Ok(Self {
+            id: ApprovalId::new(),
+            tenant_id: ctx.tenant_id,
+            agent_id,
+            task_id,
+            tool_id,
+            args_hash,
+            rule_id: decision.matched_rule.clone(),
+            reason: decision.reason.clone(),
+            approver_role,
+            requested_at: now,
+            expires_at: Self::expiry(now, ttl_secs),
+            status: ApprovalStatus::Pending,
+        })
+    }
+
+    /// Expiry `ttl_secs` after `from`, saturating at the maximum time.
+    pub fn expiry(from: DateTime<Utc>, ttl_secs: u64) -> DateTime<Utc> {
+        i64::try_from(ttl_secs)
+            .ok()
+            .and_then(Duration::try_seconds)
+            .and_then(|ttl| from.checked_add_signed(ttl))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    pub fn is_expired(&self, at: DateTime<Utc>) -> bool {
+        at >= self.expires_at
+    }
+
+    /// Whether `reviewer` may decide this request at `now`.
+    ///
+    /// # Errors
+    /// `Forbidden` if the reviewer's role is too low; `Conflict` if the
+    /// request is already decided or has expired.
+    pub fn check_reviewable(&self, reviewer: &Reviewer, now: DateTime<Utc>) -> Result<()> {
+        let id = self.id;
+        if reviewer.role < self.approver_role {
+            return Err(AetherError::Forbidden(format!(
+                "approval {id} requires role {:?}, reviewer has {:?}",
+                self.approver_role, reviewer.role
+            )));
+        }
+        if self.status != ApprovalStatus::Pending {
+            return Err(AetherError::Conflict(format!(
+                "approval {id} is already decided"
+            )));
+        }
+        if self.is_expired(now) {
+            return Err(AetherError::Conflict(format!("approval {id} has expired")));
+        }
+        Ok(())
+    }
+
+    /// Whether this request was filed for `tool_id` called by `agent_id`
+    /// with arguments hashing to `args_hash`, held by `rule_id`.
+    ///
+    /// # Errors
+    /// `Forbidden` if it covers a different call.
+    pub fn check_covers(
+        &self,
+        tool_id: ToolId,
+        agent_id: AgentId,
+        args_hash: &str,
+        rule_id: &str,
+    ) -> Result<()> {
+        let covers = self.tool_id == tool_id
+            && self.agent_id == agent_id
+            && self.args_hash == args_hash
+            && self.rule_id == rule_id;
+        if !covers {
+            let id = self.id;
+            return Err(AetherError::Forbidden(format!(
+                "approval {id} does not cover this call"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Spend an approved request at `at`.
+    ///
+    /// # Errors
+    /// `Forbidden` unless approved; `Conflict` if already used or expired.
+    pub fn redeem(&mut self, at: DateTime<Utc>) -> Result<()> {
+        let id = self.id;
+        match &self.status {
+            ApprovalStatus::Approved { .. } if self.is_expired(at) => {
+                Err(AetherError::Conflict(format!("approval {id} has expired")))
+            }
+            ApprovalStatus::Approved {
+                reviewer,
+                review_block,
+            } => {
+                self.status = ApprovalStatus::Redeemed {
+                    reviewer: *reviewer,
+                    review_block: *review_block,
+                    redeemed_at: at,
+                };
+                Ok(())
+            }
+            ApprovalStatus::Pending => Err(AetherError::Forbidden(format!(
+                "approval {id} is still pending"
+            ))),
+            ApprovalStatus::Rejected { .. } => Err(AetherError::Forbidden(format!(
+                "approval {id} was rejected"
+            ))),
+            ApprovalStatus::Redeemed { .. } => Err(AetherError::Conflict(format!(
+                "approval {id} was already used"
+            ))),
+        }
+    }
+
+    /// The `HumanReview` ledger entry recording `reviewer`'s verdict.
+    pub fn review_entry(&self, reviewer: &Reviewer, rejection: Option<&str>) -> LedgerBlockBuilder {
+        let verdict = if rejection.is_some() {
+            "rejected"
+        } else {
+            "approved"
+        };
+        self.ledger_entry()
+            .input(json!({
+                "approval_id": self.id,
+                "rule_id": self.rule_id,
+                "args_hash": self.args_hash,
+                "reason": self.reason,
+            }))
+            .output(json!({
+                "verdict": verdict,
+                "reviewer": reviewer.user_id,
+                "role": reviewer.role,
+                "reason": rejection,
+            }))
+    }
+
+    /// The `HumanReview` ledger entry recording that a redeemed approval
+    /// let its call through, referencing the review that approved it;
+    /// `None` unless redeemed.
+    pub fn redemption_entry(&self) -> Option<LedgerBlockBuilder> {
+        let ApprovalStatus::Redeemed {
+            review_block,
+            redeemed_at,
+            ..
+        } = &self.status
+        else {
+            return None;
+        };
+        let entry = self
+            .ledger_entry()
+            .approved_by(*review_block)
+            .input(json!({
+                "approval_id": self.id,
+                "rule_id": self.rule_id,
+                "args_hash": self.args_hash,
+            }))
+            .output(json!({"verdict": "redeemed", "redeemed_at": redeemed_at}));
+        Some(entry)
+    }
+
+    fn ledger_entry(&self) -> LedgerBlockBuilder {
+        let (agent, task) = (self.agent_id, self.task_id);
+        LedgerBlockBuilder::unlinked(self.tenant_id, agent, task, LedgerAction::HumanReview)
+            .tool_id(self.tool_id)
+    }
+
+    /// The `HumanReview` ledger block recording the decision, once made.
+    pub fn review_block(&self) -> Option<LedgerBlockId> {
+        match &self.status {
+            ApprovalStatus::Pending => None,
+            ApprovalStatus::Approved { review_block, .. }
+            | ApprovalStatus::Rejected { review_block, .. }
+            | ApprovalStatus::Redeemed { review_block, .. } => Some(*review_block),
+        }
+    }
+}
+
+/// Tool, agent and task of the call in `ctx`.
+///
+/// # Errors
+/// `ValidationFailed` for a resource other than a tool call.
+pub(crate) fn tool_call(ctx: &EvaluationContext) -> Result<(ToolId, AgentId, TaskId)> {
+    ctx.resource
+        .tool_call()
+        .ok_or_else(|| AetherError::ValidationFailed {
+            field: "resource".into(),
+            reason: "human approval is only supported for tool calls".into(),
+        })
+}
+
+/// `args_hash` of the tool-call arguments in `ctx`.
+///
+/// # Errors
+/// `ValidationFailed` if `ctx` carries no arguments.
+pub(crate) fn call_args_hash(ctx: &EvaluationContext) -> Result<String> {
+    ctx.resource
+        .arguments()
+        .map(args_hash)
+        .ok_or_else(|| AetherError::ValidationFailed {
+            field: "resource.arguments".into(),
+            reason: "a held tool call must carry its arguments".into(),
+        })
+}
+
+/// SHA-256 hex digest of tool arguments.
+///
+/// `serde_json` serializes object keys in sorted order, so argument maps
+/// that differ only in key order hash the same.
+pub fn args_hash(args: &serde_json::Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(args.to_string().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args_hash_ignores_key_order() {
+        let a: serde_json::Value = serde_json::from_str(r#"{"b": 1, "a": [2]}"#).unwrap();
+        assert_eq!(args_hash(&a), args_hash(&json!({"a": [2], "b": 1})));
+        assert_ne!(args_hash(&a), args_hash(&json!({"a": [2], "b": 2})));
+    }
+
+    #[test]
+    fn test_expiry_saturates() {
+        let now = Utc::now();
+        assert_eq!(
+            ApprovalRequest::expiry(now, u64::MAX),
+            DateTime::<Utc>::MAX_UTC
+        );
+        assert_eq!(
+            ApprovalRequest::expiry(now, 60),
+            now + Duration::seconds(60)
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Human-in-the-loop gate around the policy engine (PRD §11, §14).
+//!
+//! `ApprovalGate::evaluate` files an `ApprovalRequest` whenever the engine
+//! returns `PendingApproval`; `approve` and `reject` decide it (see
+//! `approval_review`).
+//!
+//! `redeem` re-evaluates the same call with the approval token: if policy
+//! still asks for approval and the token covers this exact tool, agent and
+//! argument hash, the call is allowed once, the token is spent and a
+//! `HumanReview` block referencing the approving review (`ApprovedBy`)
+//! records the redemption. A deny from policy is never overridden by an
+//! approval.
+//!
+//! Request, expiry, review and redemption times all come from the gate's
+//! `Clock`, never from the caller's `EvaluationContext`, so a caller cannot
+//! stretch an approval's lifetime by back- or forward-dating its context.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{ApprovalId, TenantId};
+use aether_core::tenant::UserRole;
+use aether_ledger::{LedgerBlockBuilder, LedgerStorage, LedgerWriter};
+
+use crate::approval::{
+    call_args_hash, tool_call, ApprovalRequest, ApprovalStatus, Clock, SystemClock,
+};
+use crate::approval_store::ApprovalStore;
+use crate::engine::PolicyEngine;
+use crate::evaluation::{EvaluationContext, PolicyDecision};
+
+/// A decision plus the approval request it created or spent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateDecision {
+    pub decision: PolicyDecision,
+    pub approval: Option<ApprovalRequest>,
+}
+
+/// Policy evaluation with an approval workflow for held calls.
+pub struct ApprovalGate<S: LedgerStorage, A: ApprovalStore> {
+    engine: Arc<PolicyEngine>,
+    approvals: A,
+    ledger: Arc<LedgerWriter<S>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<S: LedgerStorage, A: ApprovalStore> ApprovalGate<S, A> {
+    pub fn new(engine: Arc<PolicyEngine>, approvals: A, ledger: Arc<LedgerWriter<S>>) -> Self {
+        Self {
+            engine,
+            approvals,
+            ledger,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Replace the system clock, e.g. with a test clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn approvals(&self) -> &A {
+        &self.approvals
+    }
+
+    pub fn engine(&self) -> &PolicyEngine {
+        &self.engine
+    }
+
+    pub fn ledger(&self) -> &LedgerWriter<S> {
+        &self.ledger
+    }
+
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// Decide a tool call; a pending decision files an approval request, or
+    /// returns the open one already filed for the same call.
+    ///
+    /// # Errors
+    /// Returns `ValidationFailed` if approval is required for a non-tool
+    /// resource or a call without arguments, and propagates store errors.
+    pub fn evaluate(&self, ctx: &EvaluationContext) -> Result<GateDecision> {
+        let decision = self.engine.decide(ctx);
+        if !decision.is_pending() {
+            return Ok(GateDecision {
+                decision,
+                approval: None,
+            });
+        }
+        let (approver_role, ttl_secs) = self.requirement(&ctx.tenant_id, &decision)?;
+        let now = self.clock.now();
+        let request = ApprovalRequest::new(ctx, &decision, approver_role, ttl_secs, now)?;
+        let approval = match self.approvals.find_open(&request, now)? {
+            Some(open) => open,
+            None => {
+                self.approvals.insert(request.clone())?;
+                request
+            }
+        };
+        Ok(GateDecision {
+            decision,
+            approval: Some(approval),
+        })
+    }
+
+    /// Re-evaluate a held call, spending `approval_id` if policy still
+    /// requires approval.
+    ///
+    /// # Errors
+    /// `Forbidden` if the approval was rejected, is still pending, or covers
+    /// a different call; `Conflict` if it was already used or has expired;
+    /// `ValidationFailed` if `ctx` carries no arguments; ledger errors, in
+    /// which case the approval is not spent.
+    pub fn redeem(
+        &self,
+        ctx: &EvaluationContext,
+        approval_id: &ApprovalId,
+    ) -> Result<GateDecision> {
+        let decision = self.engine.decide(ctx);
+        if !decision.is_pending() {
+            return Ok(GateDecision {
+                decision,
+                approval: None,
+            });
+        }
+        let (tool_id, agent_id, _) = tool_call(ctx)?;
+        let hash = call_args_hash(ctx)?;
+        let (at, mut approved) = (self.clock.now(), ApprovalStatus::Pending);
+        let request = self
+            .approvals
+            .update(&ctx.tenant_id, approval_id, &mut |r| {
+                r.check_covers(tool_id, agent_id, &hash, &decision.matched_rule)?;
+                approved = r.status.clone();
+                r.redeem(at)
+            })?;
+        let entry = request.redemption_entry().ok_or_else(|| {
+            AetherError::internal(format!("approval {} was not redeemed", request.id))
+        })?;
+        self.record(&request, approved, entry)?;
+        let mut allowed =
+            PolicyDecision::allow(&request.rule_id).with_constraints(decision.constraints.clone());
+        allowed.reason = format!("approved by human review (approval {})", request.id);
+        Ok(GateDecision {
+            decision: allowed,
+            approval: Some(request),
+        })
+    }
+
+    /// Approver role and TTL of the rule that returned `decision`.
+    fn requirement(
+        &self,
+        tenant_id: &TenantId,
+        decision: &PolicyDecision,
+    ) -> Result<(UserRole, u64)> {
+        let rule_id = &decision.matched_rule;
+        self.engine
+            .rules_for(tenant_id)
+            .filter(|r| &r.id == rule_id)
+            .find_map(|r| r.effect.approval_requirement())
+            .ok_or_else(|| AetherError::internal(format!("rule {rule_id} requires no approval")))
+    }
+
+    /// Append the ledger entry for a transition `request` already made from
+    /// `previous`. If the append fails the transition is undone, unless the
+    /// request has moved on since, so the store never holds a verdict or
+    /// redemption the ledger does not record.
+    pub(crate) fn record(
+        &self,
+        request: &ApprovalRequest,
+        previous: ApprovalStatus,
+        entry: LedgerBlockBuilder,
+    ) -> Result<()> {
+        let Err(err) = self.ledger.append(entry) else {
+            return Ok(());
+        };
+        let undo = self
+            .approvals
+            .update(&request.tenant_id, &request.id, &mut |r| {
+                if r.status == request.status {
+                    r.status = previous.clone();
+                }
+                Ok(())
+            });
+        if let Err(undo) = undo {
+            tracing::error!(approval = %request.id, error = %undo, "approval undo failed");
+        }
+        Err(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aether_core::error::ErrorCode;
+    use aether_core::ids::TenantId;
+    use aether_core::ledger::{BlockRefKind, LedgerAction};
+    use aether_core::tenant::UserRole;
+    use chrono::Duration;
+    use serde_json::json;
+
+    use crate::testing::{critical_call, gate, held, reviewer, ManualClock};
+
+    #[test]
+    fn test_approved_call_allowed_exactly_once() {
+        let gate = gate();
+        let ctx = critical_call(TenantId::new()).with_arguments(json!({"target": "prod"}));
+        let id = held(&gate, &ctx);
+        assert_eq!(gate.approvals().pending(&ctx.tenant_id).unwrap().len(), 1);
+
+        let approved = gate
+            .approve(&ctx.tenant_id, &id, &reviewer(UserRole::Owner))
+            .unwrap();
+        let review_block = approved.review_block().unwrap();
+        let block = gate.ledger.storage().get_block(&review_block).unwrap();
+        assert_eq!(block.action, LedgerAction::HumanReview);
+        // The store ran the change twice; the verdict is still recorded once.
+        assert_eq!(gate.ledger.storage().count(&ctx.tenant_id).unwrap(), 1);
+
+        let redeemed = gate.redeem(&ctx, &id).unwrap();
+        assert!(redeemed.decision.is_allowed());
+        let blocks = gate.ledger.storage().get_blocks(&ctx.tenant_id).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            blocks[1].ref_of(BlockRefKind::ApprovedBy),
+            Some(review_block)
+        );
+        assert_eq!(
+            redeemed.decision.matched_rule,
+            "critical-tool-human-approval"
+        );
+        let err = gate.redeem(&ctx, &id).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Conflict);
+    }
+
+    #[test]
+    fn test_repeated_call_reuses_open_request() {
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        let gate = gate().with_clock(clock.clone());
+        let ctx = critical_call(TenantId::new());
+        let id = held(&gate, &ctx);
+        assert_eq!(held(&gate, &ctx), id);
+        assert_eq!(gate.approvals().pending(&ctx.tenant_id).unwrap().len(), 1);
+        // Other arguments, or an expired request, need a new one.
+        assert_ne!(
+            held(&gate, &ctx.clone().with_arguments(json!({"n": 1}))),
+            id
+        );
+        clock.advance(Duration::hours(2));
+        assert_ne!(held(&gate, &ctx), id);
+    }
+
+    #[test]
+    fn test_approval_bound_to_arguments() {
+        let gate = gate();
+        let ctx = critical_call(TenantId::new());
+        let ctx = ctx.with_arguments(json!({"path": "/tmp/a"}));
+        let id = held(&gate, &ctx);
+        gate.approve(&ctx.tenant_id, &id, &reviewer(UserRole::Owner))
+            .unwrap();
+        let other = ctx.clone().with_arguments(json!({"path": "/etc/passwd"}));
+        let err = gate.redeem(&other, &id).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Forbidden);
+        // The token is not spent by a mismatched attempt.
+        assert!(gate.redeem(&ctx, &id).is_ok());
+        // A call without arguments cannot be bound to an approval.
+        let bare = ctx.with_arguments(serde_json::Value::Null);
+        assert_eq!(
+            gate.evaluate(&bare).unwrap_err().code(),
+            ErrorCode::ValidationFailed
+        );
+    }
+
+    #[test]
+    fn test_rejected_and_expired_requests() {
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        let gate = gate().with_clock(clock.clone());
+        let ctx = critical_call(TenantId::new());
+        let id = held(&gate, &ctx);
+        gate.reject(&ctx.tenant_id, &id, &reviewer(UserRole::Owner), "not today")
+            .unwrap();
+        let err = gate.redeem(&ctx, &id).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Forbidden);
+
+        // Expiry runs on the gate's clock; a back-dated context changes nothing.
+        let backdated = critical_call(ctx.tenant_id).at(Utc::now() - Duration::hours(2));
+        let approved = held(&gate, &backdated);
+        let stale = held(&gate, &ctx);
+        gate.approve(&ctx.tenant_id, &approved, &reviewer(UserRole::Owner))
+            .unwrap();
+        clock.advance(Duration::hours(2));
+        let err = gate
+            .approve(&ctx.tenant_id, &stale, &reviewer(UserRole::Owner))
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Conflict);
+        let err = gate.redeem(&backdated, &approved).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Conflict);
+    }
+
+    #[test]
+    fn test_policy_deny_is_not_overridden() {
+        let gate = gate();
+        let ctx = critical_call(TenantId::new());
+        let id = held(&gate, &ctx);
+        gate.approve(&ctx.tenant_id, &id, &reviewer(UserRole::Owner))
+            .unwrap();
+        let mut broke = ctx.clone();
+        broke.subject.budget_remaining_fraction = 0.0;
+        let outcome = gate.redeem(&broke, &id).unwrap();
+        assert!(!outcome.decision.is_allowed() && outcome.approval.is_none());
+    }
+}
@@ -0,0 +1,178 @@
+//! Human review of held tool calls (PRD §11, §14).
+//!
+//! `approve` and `reject` ask the engine whether the reviewer may
+//! `HumanReviewApprove`, check their role against the request's approver
+//! role, store the verdict with the request and then record it as a
+//! `HumanReview` ledger block; the store update never touches the ledger,
+//! and a verdict whose block cannot be appended is rolled back.
+
+use chrono::{DateTime, Utc};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{ApprovalId, LedgerBlockId, TenantId};
+use aether_ledger::LedgerStorage;
+
+use crate::approval::{ApprovalRequest, ApprovalStatus, Reviewer};
+use crate::approval_gate::ApprovalGate;
+use crate::approval_store::ApprovalStore;
+use crate::evaluation::{EvaluationContext, PolicyResource};
+use crate::rules::PolicyAction;
+
+impl<S: LedgerStorage, A: ApprovalStore> ApprovalGate<S, A> {
+    /// Approve a pending request.
+    ///
+    /// # Errors
+    /// `Forbidden` if policy denies the reviewer `HumanReviewApprove` or
+    /// their role is below the request's approver role, `Conflict` if it
+    /// is already decided or expired, `NotFound` for another tenant's
+    /// request, and ledger errors.
+    pub fn approve(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApprovalId,
+        reviewer: &Reviewer,
+    ) -> Result<ApprovalRequest> {
+        self.review(tenant_id, id, reviewer, None)
+    }
+
+    /// Reject a pending request; errors as for `approve`.
+    ///
+    /// # Errors
+    /// See `approve`.
+    pub fn reject(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApprovalId,
+        reviewer: &Reviewer,
+        reason: impl Into<String>,
+    ) -> Result<ApprovalRequest> {
+        self.review(tenant_id, id, reviewer, Some(reason.into()))
+    }
+
+    fn review(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApprovalId,
+        reviewer: &Reviewer,
+        rejection: Option<String>,
+    ) -> Result<ApprovalRequest> {
+        let now = self.now();
+        self.authorize_review(tenant_id, id, reviewer, now)?;
+        let review_block = LedgerBlockId::new();
+        let verdict = ApprovalStatus::reviewed(reviewer.user_id, review_block, rejection.clone());
+        let request = self.approvals().update(tenant_id, id, &mut |request| {
+            request.check_reviewable(reviewer, now)?;
+            request.status = verdict.clone();
+            Ok(())
+        })?;
+        let entry = request
+            .review_entry(reviewer, rejection.as_deref())
+            .id(review_block);
+        self.record(&request, ApprovalStatus::Pending, entry)?;
+        Ok(request)
+    }
+
+    /// Ask the engine whether `reviewer` may review approvals at all; the
+    /// request's own approver role is checked on top of this.
+    fn authorize_review(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApprovalId,
+        reviewer: &Reviewer,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let (action, resource) = (
+            PolicyAction::HumanReviewApprove,
+            PolicyResource::Approval { approval_id: *id },
+        );
+        let role = reviewer.role.clone();
+        let ctx = EvaluationContext::for_user(*tenant_id, reviewer.user_id, role, action, resource);
+        let decision = self.engine().decide(&ctx.at(now));
+        if !decision.is_allowed() {
+            return Err(AetherError::Forbidden(format!(
+                "reviewer {} may not review approval {id}: {}",
+                reviewer.user_id, decision.reason
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use aether_core::error::ErrorCode;
+    use aether_core::tenant::UserRole;
+    use aether_ledger::{InMemoryLedgerStorage, LedgerKeyring, LedgerWriter};
+
+    use crate::condition::RuleCondition;
+    use crate::defaults::default_rules;
+    use crate::engine::PolicyEngine;
+    use crate::rules::{PolicyEffect, PolicyRule};
+    use crate::testing::{critical_call, gate, held, reviewer, test_gate};
+
+    #[test]
+    fn test_reviewer_role_must_meet_rule() {
+        let gate = gate();
+        let ctx = critical_call(TenantId::new());
+        let id = held(&gate, &ctx);
+        let err = gate
+            .approve(&ctx.tenant_id, &id, &reviewer(UserRole::Admin))
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Forbidden);
+        let request = gate.approvals().get(&ctx.tenant_id, &id).unwrap();
+        assert_eq!(request.status, ApprovalStatus::Pending);
+    }
+
+    #[test]
+    fn test_engine_authorizes_reviewers() {
+        let condition = RuleCondition::action(PolicyAction::HumanReviewApprove);
+        let mut rules = default_rules();
+        rules.insert(
+            0,
+            PolicyRule::new("no-reviews", "", condition, PolicyEffect::deny("frozen")),
+        );
+        let ledger = LedgerWriter::new(InMemoryLedgerStorage::new());
+        let gate = test_gate(PolicyEngine::with_rules(rules), ledger);
+        let ctx = critical_call(TenantId::new());
+        let id = held(&gate, &ctx);
+        let err = gate
+            .approve(&ctx.tenant_id, &id, &reviewer(UserRole::Owner))
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Forbidden);
+        assert!(gate
+            .ledger()
+            .storage()
+            .get_blocks(&ctx.tenant_id)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unrecorded_verdict_rolled_back() {
+        // A keyring without signers fails every append.
+        let keyring = Arc::new(LedgerKeyring::new());
+        let ledger = LedgerWriter::new(InMemoryLedgerStorage::new()).with_keyring(keyring);
+        let gate = test_gate(PolicyEngine::default(), ledger);
+        let ctx = critical_call(TenantId::new());
+        let id = held(&gate, &ctx);
+        assert!(gate
+            .approve(&ctx.tenant_id, &id, &reviewer(UserRole::Owner))
+            .is_err());
+        let request = gate.approvals().get(&ctx.tenant_id, &id).unwrap();
+        assert_eq!(request.status, ApprovalStatus::Pending);
+    }
+
+    #[test]
+    fn test_other_tenant_cannot_review() {
+        let gate = gate();
+        let ctx = critical_call(TenantId::new());
+        let id = held(&gate, &ctx);
+        let err = gate
+            .approve(&TenantId::new(), &id, &reviewer(UserRole::Owner))
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+    }
+}
@@ -0,0 +1,135 @@
+//! Persistence for approval requests (PRD §11).
+//!
+//! Requests are scoped by tenant: a lookup with another tenant's id finds
+//! nothing. `update` is the only way to change a stored request, so status
+//! transitions are atomic.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use chrono::{DateTime, Utc};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::{ApprovalId, TenantId};
+
+use crate::approval::{ApprovalRequest, ApprovalStatus};
+
+/// Persistence for approval requests, scoped by tenant.
+pub trait ApprovalStore: Send + Sync {
+    /// Store a new request.
+    fn insert(&self, request: ApprovalRequest) -> Result<()>;
+
+    /// Fetch one of `tenant_id`'s requests.
+    ///
+    /// # Errors
+    /// `NotFound` if the tenant has no such request.
+    fn get(&self, tenant_id: &TenantId, id: &ApprovalId) -> Result<ApprovalRequest>;
+
+    /// Atomically modify a request: `change` runs on a copy that is stored
+    /// only if it returns `Ok`, and no other update interleaves.
+    ///
+    /// # Errors
+    /// `NotFound`, or whatever `change` returns.
+    fn update(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApprovalId,
+        change: &mut dyn FnMut(&mut ApprovalRequest) -> Result<()>,
+    ) -> Result<ApprovalRequest>;
+
+    /// `tenant_id`'s requests still awaiting a decision, oldest first.
+    fn pending(&self, tenant_id: &TenantId) -> Result<Vec<ApprovalRequest>>;
+
+    /// A pending request, unexpired at `now`, filed for the same tool,
+    /// agent, argument hash and rule as `request` in its tenant.
+    fn find_open(
+        &self,
+        request: &ApprovalRequest,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ApprovalRequest>> {
+        let (tool, agent) = (request.tool_id, request.agent_id);
+        Ok(self.pending(&request.tenant_id)?.into_iter().find(|open| {
+            !open.is_expired(now)
+                && open
+                    .check_covers(tool, agent, &request.args_hash, &request.rule_id)
+                    .is_ok()
+        }))
+    }
+}
+
+type Requests = HashMap<(TenantId, ApprovalId), ApprovalRequest>;
+
+/// In-memory approval store (tests and single-node deployments).
+#[derive(Default)]
+pub struct InMemoryApprovalStore {
+    requests: RwLock<Requests>,
+}
+
+impl InMemoryApprovalStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> Result<RwLockReadGuard<'_, Requests>> {
+        self.requests
+            .read()
+            .map_err(|e| AetherError::internal(format!("approval store lock poisoned: {e}")))
+    }
+
+    fn write(&self) -> Result<RwLockWriteGuard<'_, Requests>> {
+        self.requests
+            .write()
+            .map_err(|e| AetherError::internal(format!("approval store lock poisoned: {e}")))
+    }
+}
+
+impl ApprovalStore for InMemoryApprovalStore {
+    fn insert(&self, request: ApprovalRequest) -> Result<()> {
+        let mut requests = self.write()?;
+        match requests.entry((request.tenant_id, request.id)) {
+            Entry::Occupied(_) => Err(AetherError::Conflict(format!(
+                "approval {} already exists",
+                request.id
+            ))),
+            Entry::Vacant(slot) => {
+                slot.insert(request);
+                Ok(())
+            }
+        }
+    }
+
+    fn get(&self, tenant_id: &TenantId, id: &ApprovalId) -> Result<ApprovalRequest> {
+        self.read()?
+            .get(&(*tenant_id, *id))
+            .cloned()
+            .ok_or_else(|| AetherError::not_found("approval_request", id.to_string()))
+    }
+
+    fn update(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApprovalId,
+        change: &mut dyn FnMut(&mut ApprovalRequest) -> Result<()>,
+    ) -> Result<ApprovalRequest> {
+        let mut requests = self.write()?;
+        let stored = requests
+            .get_mut(&(*tenant_id, *id))
+            .ok_or_else(|| AetherError::not_found("approval_request", id.to_string()))?;
+        let mut updated = stored.clone();
+        change(&mut updated)?;
+        *stored = updated.clone();
+        Ok(updated)
+    }
+
+    fn pending(&self, tenant_id: &TenantId) -> Result<Vec<ApprovalRequest>> {
+        let mut pending: Vec<ApprovalRequest> = self
+            .read()?
+            .values()
+            .filter(|r| r.tenant_id == *tenant_id && r.status == ApprovalStatus::Pending)
+            .cloned()
+            .collect();
+        pending.sort_by_key(|r| r.requested_at);
+        Ok(pending)
+    }
+}
@@ -0,0 +1,141 @@
+//! Path expressions selecting values from tool-call arguments (PRD §11).
+//!
+//! Paths are `$`-rooted (the `$` is optional) dotted keys with `[n]`
+//! indices; `*` and `[*]` select every member, so `$.targets[*].host` picks
+//! the host of every target.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// A parsed path expression, written in documents as its source.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ArgumentPath {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl ArgumentPath {
+    /// Parse `$.a.b[0]`-style paths.
+    ///
+    /// # Errors
+    /// Describes the first malformed segment.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let body = source.strip_prefix('$').unwrap_or(source);
+        let body = body.strip_prefix('.').unwrap_or(body);
+        let mut segments = Vec::new();
+        for part in body.split('.').filter(|_| !body.is_empty()) {
+            let (key, mut indices) = part.split_once('[').unwrap_or((part, ""));
+            match key {
+                "" if indices.is_empty() => return Err(format!("empty segment in '{source}'")),
+                "" => {}
+                "*" => segments.push(Segment::Wildcard),
+                key => segments.push(Segment::Key(key.to_owned())),
+            }
+            while !indices.is_empty() {
+                let (index, rest) = indices
+                    .split_once(']')
+                    .ok_or_else(|| format!("unclosed '[' in '{source}'"))?;
+                segments.push(match index {
+                    "*" => Segment::Wildcard,
+                    n => Segment::Index(
+                        n.parse()
+                            .map_err(|_| format!("bad index '{n}' in '{source}'"))?,
+                    ),
+                });
+                indices = rest.strip_prefix('[').unwrap_or(rest);
+                if !rest.is_empty() && !rest.starts_with('[') {
+                    return Err(format!("unexpected '{rest}' in '{source}'"));
+                }
+            }
+        }
+        Ok(Self {
+            source: source.to_owned(),
+            segments,
+        })
+    }
+
+    /// Every value the path selects from `root`.
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+        for segment in &self.segments {
+            current = current
+                .into_iter()
+                .flat_map(|v| segment.select(v))
+                .collect();
+        }
+        current
+    }
+}
+
+impl Segment {
+    fn select<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        match (self, value) {
+            (Self::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+            (Self::Index(i), Value::Array(items)) => items.get(*i).into_iter().collect(),
+            (Self::Wildcard, Value::Array(items)) => items.iter().collect(),
+            (Self::Wildcard, Value::Object(map)) => map.values().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for ArgumentPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl fmt::Debug for ArgumentPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.source)
+    }
+}
+
+impl Serialize for ArgumentPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for ArgumentPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Self::parse(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn select(path: &str, args: &Value) -> Vec<Value> {
+        let path = ArgumentPath::parse(path).unwrap();
+        path.select(args).into_iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_path_selection() {
+        let args = json!({"targets": [{"host": "10.0.0.1"}, {"host": "8.8.8.8"}], "n": 3});
+        assert_eq!(select("$.n", &args), [json!(3)]);
+        assert_eq!(select("targets[1].host", &args), [json!("8.8.8.8")]);
+        assert_eq!(
+            select("$.targets[*].host", &args),
+            [json!("10.0.0.1"), json!("8.8.8.8")]
+        );
+        assert_eq!(select("$", &args), std::slice::from_ref(&args));
+        assert!(select("$.missing.host", &args).is_empty());
+        for bad in ["a..b", "a[x]", "a[1", "a[1]b"] {
+            assert!(ArgumentPath::parse(bad).is_err(), "{bad}");
+        }
+    }
+}
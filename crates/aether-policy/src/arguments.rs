@@ -0,0 +1,322 @@
+//! Conditions over tool-call arguments (PRD §11).
+//!
+//! An `argument` condition selects values from the tool call's arguments
+//! JSON with a path expression and tests them with a matcher:
+//!
+//! ```yaml
+//! type: argument
+//! path: $.targets[*].host
+//! matches: { op: cidr, network: 10.0.0.0/8 }
+//! quantifier: all
+//! ```
+//!
+//! The path is an `ArgumentPath`. With `quantifier: any` (the default) one
+//! selected value must match, with `all` every one must. A path that
+//! selects nothing never matches, so "every URL is internal" does not hold
+//! for a call that has no URL.
+//!
+//! `path_under` resolves a relative path against the absolute working
+//! directory in the `request.cwd` attribute. Without one it cannot place the
+//! path, so it neither matches nor fails to match: the engine denies any call
+//! where a rule would have to test such a path.
+
+use std::net::IpAddr;
+use std::path::{Component, Path, PathBuf};
+
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::attributes::Pattern;
+
+/// How many selected values must match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quantifier {
+    #[default]
+    Any,
+    All,
+}
+
+impl Quantifier {
+    /// Apply `matcher` to `values`, resolving relative paths against
+    /// `cwd`; false when `values` is empty.
+    pub fn test(self, values: &[&Value], matcher: &ArgumentMatch, cwd: Option<&str>) -> bool {
+        if values.is_empty() {
+            return false;
+        }
+        match self {
+            Self::Any => values.iter().any(|v| matcher.matches(v, cwd)),
+            Self::All => values.iter().all(|v| matcher.matches(v, cwd)),
+        }
+    }
+
+    /// Whether `test` hinges on a value `matcher` cannot resolve: one is
+    /// unresolved and the resolved ones do not already settle the result.
+    pub fn is_unresolved(
+        self,
+        values: &[&Value],
+        matcher: &ArgumentMatch,
+        cwd: Option<&str>,
+    ) -> bool {
+        let mut resolved = values.iter().filter(|v| !matcher.is_unresolved(v, cwd));
+        values.iter().any(|v| matcher.is_unresolved(v, cwd))
+            && match self {
+                Self::Any => !resolved.any(|v| matcher.matches(v, cwd)),
+                Self::All => resolved.all(|v| matcher.matches(v, cwd)),
+            }
+    }
+}
+
+/// A test applied to one argument value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ArgumentMatch {
+    /// A string starting with `prefix`.
+    Prefix { prefix: String },
+    /// A filesystem path equal to or below `path`, compared by component
+    /// after resolving `.` and `..`, so `/tmp/../etc/passwd` is under
+    /// `/etc`. A relative path is first joined to the call's working
+    /// directory; without one it never matches (see the module docs).
+    PathUnder { path: String },
+    /// A filesystem path that is not absolute.
+    RelativePath,
+    /// A string matching a regular expression.
+    Regex { pattern: Pattern },
+    /// An IP address string inside `network`. IPv4-mapped IPv6 addresses
+    /// (`::ffff:127.0.0.1`) are compared as the IPv4 address they carry.
+    Cidr { network: IpNet },
+    /// A number within the inclusive bounds.
+    Between {
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+}
+
+impl ArgumentMatch {
+    /// Test `value`, resolving a relative path against `cwd`.
+    pub fn matches(&self, value: &Value, cwd: Option<&str>) -> bool {
+        if let Self::Between { min, max } = self {
+            return value
+                .as_f64()
+                .is_some_and(|n| min.is_none_or(|min| n >= min) && max.is_none_or(|max| n <= max));
+        }
+        let Some(text) = value.as_str() else {
+            return false;
+        };
+        match self {
+            Self::Prefix { prefix } => text.starts_with(prefix.as_str()),
+            Self::PathUnder { path } => {
+                resolve(text, cwd).is_some_and(|p| p.starts_with(normalize(Path::new(path))))
+            }
+            Self::RelativePath => !Path::new(text).is_absolute(),
+            Self::Regex { pattern } => pattern.is_match(text),
+            Self::Cidr { network } => text
+                .parse::<IpAddr>()
+                .is_ok_and(|ip| network.contains(&ip.to_canonical())),
+            Self::Between { .. } => false,
+        }
+    }
+
+    /// Whether `value` is a path this `path_under` cannot place, being
+    /// relative with no absolute `cwd` to resolve it against.
+    pub fn is_unresolved(&self, value: &Value, cwd: Option<&str>) -> bool {
+        matches!(self, Self::PathUnder { .. })
+            && value
+                .as_str()
+                .is_some_and(|text| resolve(text, cwd).is_none())
+    }
+}
+
+/// `text` as an absolute, normalized path, joined to `cwd` if relative;
+/// `None` if neither is absolute.
+fn resolve(text: &str, cwd: Option<&str>) -> Option<PathBuf> {
+    let path = Path::new(text);
+    if path.is_absolute() {
+        return Some(normalize(path));
+    }
+    let cwd = Path::new(cwd?);
+    cwd.is_absolute().then(|| normalize(&cwd.join(path)))
+}
+
+/// Lexically resolve `.` and `..`; `..` at the root stays at the root.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aether_core::tool::ToolAccessLevel;
+    use serde_json::json;
+
+    use crate::document::{PolicyDocument, PolicyFormat};
+    use crate::engine::PolicyEngine;
+    use crate::evaluation::EvaluationContext;
+    use crate::rules::AgentTier;
+    use crate::testing::tool_call;
+
+    #[test]
+    fn test_matchers() {
+        let m = |json: Value| serde_json::from_value::<ArgumentMatch>(json).unwrap();
+        let etc = m(json!({"op": "path_under", "path": "/etc"}));
+        assert!(etc.matches(&json!("/etc/passwd"), None));
+        assert!(etc.matches(&json!("/tmp/../etc/shadow"), None));
+        assert!(!etc.matches(&json!("/etcetera"), None));
+        assert!(etc.matches(&json!("../../etc/passwd"), Some("/srv/app")));
+        assert!(!etc.matches(&json!("etc/passwd"), Some("/srv")));
+        // Without an absolute cwd a relative path is neither in nor out.
+        for cwd in [None, Some("srv")] {
+            assert!(!etc.matches(&json!("../../etc/passwd"), cwd));
+            assert!(etc.is_unresolved(&json!("../../etc/passwd"), cwd));
+        }
+        assert!(!etc.is_unresolved(&json!("/etc/passwd"), None));
+        assert!(!etc.is_unresolved(&json!("etc/passwd"), Some("/srv")));
+        let relative = m(json!({"op": "relative_path"}));
+        assert!(relative.matches(&json!("../x"), None) && !relative.matches(&json!("/x"), None));
+        assert!(!relative.is_unresolved(&json!("../x"), None));
+
+        let private = m(json!({"op": "cidr", "network": "10.0.0.0/8"}));
+        assert!(private.matches(&json!("10.1.2.3"), None));
+        assert!(!private.matches(&json!("11.0.0.1"), None));
+        assert!(!private.matches(&json!("host"), None));
+        let loopback = m(json!({"op": "cidr", "network": "127.0.0.0/8"}));
+        assert!(loopback.matches(&json!("::ffff:127.0.0.1"), None));
+        assert!(!loopback.matches(&json!("::ffff:10.0.0.1"), None));
+
+        let bounded = m(json!({"op": "between", "max": 100}));
+        assert!(bounded.matches(&json!(100), None) && !bounded.matches(&json!(100.5), None));
+        assert!(!bounded.matches(&json!("50"), None));
+        let https = m(json!({"op": "prefix", "prefix": "https://"}));
+        assert!(https.matches(&json!("https://a"), None));
+    }
+
+    #[test]
+    fn test_quantifiers_need_a_value() {
+        let https = ArgumentMatch::Prefix {
+            prefix: "https://".into(),
+        };
+        let (a, b) = (json!("https://a"), json!("http://b"));
+        assert!(Quantifier::Any.test(&[&a, &b], &https, None));
+        assert!(!Quantifier::All.test(&[&a, &b], &https, None));
+        assert!(!Quantifier::All.test(&[], &https, None));
+    }
+
+    const POLICY: &str = r#"
+rules:
+  - id: http-fetch-internal-only
+    condition:
+      type: all
+      conditions:
+        - { type: tool_name, pattern: http_fetch }
+        - type: not
+          condition:
+            type: argument
+            path: $.url
+            matches: { op: regex, pattern: '^https?://[^/:]+\.internal(:[0-9]+)?(/|$)' }
+    effect: { type: deny, reason: external fetch }
+  - id: read-file-not-etc
+    condition:
+      type: all
+      conditions:
+        - { type: tool_name, pattern: read_file }
+        - { type: argument, path: path, matches: { op: path_under, path: /etc } }
+    effect: { type: deny, reason: system config }
+  - id: write-file-only-srv
+    condition:
+      type: all
+      conditions:
+        - { type: tool_name, pattern: write_file }
+        - { type: argument, path: path, matches: { op: path_under, path: /srv } }
+    effect: { type: allow }
+  - id: write-file-elsewhere
+    condition: { type: tool_name, pattern: write_file }
+    effect: { type: deny, reason: outside /srv }
+  - id: allow-rest
+    condition: { type: always_allow }
+    effect: { type: allow }
+"#;
+
+    fn call(tool: &str, args: Value) -> EvaluationContext {
+        tool_call(ToolAccessLevel::Public, AgentTier::WORKER)
+            .with_tool_identity(tool, None)
+            .with_arguments(args)
+    }
+
+    #[test]
+    fn test_engine_evaluates_argument_rules() {
+        let document = PolicyDocument::parse(POLICY, PolicyFormat::Yaml).unwrap();
+        let engine = PolicyEngine::with_rules(document.rules);
+        let decide = |tool, args| engine.decide(&call(tool, args)).matched_rule;
+
+        let internal = json!({"url": "https://billing.internal/v1"});
+        assert_eq!(decide("http_fetch", internal), "allow-rest");
+        let external = json!({"url": "https://billing.internal.evil.com/"});
+        assert_eq!(decide("http_fetch", external), "http-fetch-internal-only");
+        // No URL at all is not an internal URL.
+        assert_eq!(decide("http_fetch", json!({})), "http-fetch-internal-only");
+        assert_eq!(
+            decide("read_file", json!({"path": "/etc/passwd"})),
+            "read-file-not-etc"
+        );
+        assert_eq!(
+            decide("read_file", json!({"path": "/srv/app.log"})),
+            "allow-rest"
+        );
+        assert_eq!(
+            decide("write_file", json!({"path": "/srv/app.log"})),
+            "write-file-only-srv"
+        );
+        assert_eq!(
+            decide("write_file", json!({"path": "/etc/cron.d/x"})),
+            "write-file-elsewhere"
+        );
+        // The allow must not admit a relative path that climbs out of /srv.
+        let escape = call("write_file", json!({"path": "../../etc/passwd"}));
+        assert!(!engine.decide(&escape).is_allowed());
+    }
+
+    #[test]
+    fn test_engine_denies_unresolved_relative_paths() {
+        let document = PolicyDocument::parse(POLICY, PolicyFormat::Yaml).unwrap();
+        let engine = PolicyEngine::with_rules(document.rules);
+        let in_dir = |tool, path, cwd: Option<&str>| {
+            let attributes = cwd.map(|cwd| ("cwd".to_owned(), cwd.to_owned()));
+            let ctx = call(tool, json!({"path": path}));
+            engine.decide(&ctx.with_attributes(attributes.into_iter().collect()))
+        };
+
+        // Neither the deny nor the allow rule can place these paths.
+        for tool in ["read_file", "write_file"] {
+            for path in ["../../etc/passwd", "srv/app.log"] {
+                let decision = in_dir(tool, path, None);
+                assert!(!decision.is_allowed(), "{tool} {path}");
+                assert!(
+                    decision.reason.contains("cannot be resolved"),
+                    "{tool} {path}"
+                );
+            }
+        }
+        // With a working directory they resolve like absolute paths.
+        let read = in_dir("read_file", "../../etc/passwd", Some("/srv/app"));
+        assert_eq!(read.matched_rule, "read-file-not-etc");
+        let write = in_dir("write_file", "../../etc/passwd", Some("/srv/app"));
+        assert_eq!(write.matched_rule, "write-file-elsewhere");
+        let write = in_dir("write_file", "logs/app.log", Some("/srv/app"));
+        assert_eq!(write.matched_rule, "write-file-only-srv");
+        // A relative path a rule never tests with `path_under` is fine.
+        assert_eq!(in_dir("list_dir", "../x", None).matched_rule, "allow-rest");
+    }
+}
@@ -0,0 +1,227 @@
+//! Attribute-based conditions (PRD §11).
+//!
+//! ABAC rules compare a named attribute of the request against a matcher.
+//! Attribute names are dotted paths into the `EvaluationContext`:
+//!
+//! | name | value |
+//! |------|-------|
+//! | `subject.principal` | `agent`, `user` or `api_key` |
+//! | `subject.agent_tier`, `subject.spawn_depth` | number |
+//! | `subject.user_role` | `OWNER`, `ADMIN`, … (absent for agents) |
+//! | `subject.budget_remaining` | fraction in [0, 1] |
+//! | `tenant.tier` | `FREE`, `PRO`, `ENTERPRISE`, `INTERNAL` |
+//! | `action` | `tool_execute`, `agent_spawn`, … |
+//! | `resource.kind` | `tool`, `agent`, … |
+//! | `resource.tool_name`, `resource.tool_version`, `resource.access_level` | tool resources only |
+//! | `request.<key>` | entry of the request's attribute bag |
+//!
+//! Every attribute resolves to a string; numeric matchers parse it. A
+//! missing attribute, or one that does not parse, matches nothing.
+
+use std::fmt;
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::evaluation::{EvaluationContext, PolicyResource};
+
+/// A test applied to one attribute value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum AttributeMatch {
+    /// The attribute is present.
+    Exists,
+    Equals {
+        value: String,
+    },
+    In {
+        values: Vec<String>,
+    },
+    /// Shell-style pattern: `*` matches any run of characters, `?` one.
+    Glob {
+        pattern: String,
+    },
+    /// Regular expression, unanchored unless the pattern anchors itself.
+    Regex {
+        pattern: Pattern,
+    },
+    /// Numeric `value < bound`.
+    LessThan {
+        bound: f64,
+    },
+    /// Numeric `value <= bound`.
+    AtMost {
+        bound: f64,
+    },
+    /// Numeric `value > bound`.
+    GreaterThan {
+        bound: f64,
+    },
+    /// Numeric `value >= bound`.
+    AtLeast {
+        bound: f64,
+    },
+}
+
+impl AttributeMatch {
+    /// Whether `value` (the resolved attribute, if any) matches.
+    pub fn matches(&self, value: Option<&str>) -> bool {
+        let Some(value) = value else {
+            return false;
+        };
+        let number = || value.trim().parse::<f64>().ok();
+        match self {
+            Self::Exists => true,
+            Self::Equals { value: expected } => value == expected,
+            Self::In { values } => values.iter().any(|v| v == value),
+            Self::Glob { pattern } => glob_match(pattern, value),
+            Self::Regex { pattern } => pattern.is_match(value),
+            Self::LessThan { bound } => number().is_some_and(|n| n < *bound),
+            Self::AtMost { bound } => number().is_some_and(|n| n <= *bound),
+            Self::GreaterThan { bound } => number().is_some_and(|n| n > *bound),
+            Self::AtLeast { bound } => number().is_some_and(|n| n >= *bound),
+        }
+    }
+}
+
+/// A compiled regular expression, written in documents as its source.
+///
+/// Compiling on deserialize makes a bad pattern a document parse error
+/// rather than a rule that silently never matches.
+#[derive(Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// # Errors
+    /// Returns the regex compile error.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Self)
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/{}/", self.0.as_str())
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Self::new(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Match `text` against a `*`/`?` wildcard pattern.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it is currently absorbing up to.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((sp, st)) => {
+                    star = Some((sp, st + 1));
+                    p = sp + 1;
+                    t = st + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl EvaluationContext {
+    /// Resolve a dotted attribute name (see the module docs).
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        if let Some(key) = name.strip_prefix("request.") {
+            return self.attributes.get(key).cloned();
+        }
+        let subject = &self.subject;
+        match name {
+            "subject.principal" => serde_name(&subject.principal.kind()),
+            "subject.agent_tier" => Some(subject.agent_tier.0.to_string()),
+            "subject.spawn_depth" => Some(subject.spawn_depth.to_string()),
+            "subject.user_role" => subject.user_role.as_ref().and_then(serde_name),
+            "subject.budget_remaining" => Some(subject.budget_remaining_fraction.to_string()),
+            "tenant.tier" => serde_name(&self.tenant_tier),
+            "action" => serde_name(&self.action),
+            "resource.kind" => serde_name(&self.resource.kind()),
+            "resource.access_level" => self.resource.tool_access().as_ref().and_then(serde_name),
+            _ => self.tool_attribute(name),
+        }
+    }
+
+    fn tool_attribute(&self, name: &str) -> Option<String> {
+        let PolicyResource::Tool {
+            name: tool,
+            version,
+            ..
+        } = &self.resource
+        else {
+            return None;
+        };
+        match name {
+            "resource.tool_name" => Some(tool.clone()).filter(|n| !n.is_empty()),
+            "resource.tool_version" => version.as_ref().map(ToString::to_string),
+            _ => None,
+        }
+    }
+}
+
+/// The serialized name of a unit enum variant.
+fn serde_name<T: Serialize>(value: &T) -> Option<String> {
+    match serde_json::to_value(value).ok()? {
+        serde_json::Value::String(s) => Some(s),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("shell_*", "shell_exec"));
+        assert!(glob_match("shell_*", "shell_"));
+        assert!(glob_match("*_fetch", "http_fetch"));
+        assert!(glob_match("a*b?d", "axxbcd"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("shell_*", "bash_shell_exec"));
+        assert!(!glob_match("a?c", "ac"));
+    }
+
+    #[test]
+    fn test_matchers() {
+        let matcher = |json: &str| serde_json::from_str::<AttributeMatch>(json).unwrap();
+        assert!(matcher(r#"{"op": "regex", "pattern": "^v[0-9]+$"}"#).matches(Some("v12")));
+        assert!(matcher(r#"{"op": "at_least", "bound": 2}"#).matches(Some("2")));
+        assert!(!matcher(r#"{"op": "less_than", "bound": 2}"#).matches(Some("two")));
+        assert!(matcher(r#"{"op": "in", "values": ["a", "b"]}"#).matches(Some("b")));
+        assert!(!matcher(r#"{"op": "exists"}"#).matches(None));
+        let bad = serde_json::from_str::<AttributeMatch>(r#"{"op": "regex", "pattern": "("}"#);
+        assert!(bad.is_err());
+    }
+}
@@ -0,0 +1,387 @@
+//! Rule conditions and their evaluation (PRD §11).
+//!
+//! A `RuleCondition` is a predicate over an `EvaluationContext`. Leaf
+//! conditions test one property of the subject, action or resource;
+//! `All`, `Any` and `Not` compose them, so "PROTECTED tool AND tier ≤ 2" is
+//! a single rule rather than a pair of rules whose order must be right.
+
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+
+use aether_core::ids::ToolId;
+use aether_core::tenant::{TenantTier, UserRole};
+use aether_core::tool::ToolAccessLevel;
+
+use crate::argument_path::ArgumentPath;
+use crate::arguments::{ArgumentMatch, Quantifier};
+use crate::attributes::{glob_match, AttributeMatch};
+use crate::evaluation::{EvaluationContext, PolicyResource, ResourceKind};
+use crate::rules::{PolicyAction, PrincipalKind};
+use crate::temporal::{DaySet, MaintenanceWindow, TimeWindow, ValidityPeriod};
+
+/// The logical condition that must be true for a rule to fire.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleCondition {
+    /// Requires the subject's agent tier to meet a minimum.
+    AgentTierMinimum { minimum: u8 },
+    /// Requires a specific tool access level to match the subject's clearance.
+    ToolAccessLevel { required: ToolAccessLevel },
+    /// The resource is a tool with exactly this access level.
+    ToolAccessIs { level: ToolAccessLevel },
+    /// Requires the budget remaining fraction to be above a threshold.
+    BudgetAbove { threshold: f64 },
+    /// User must hold at least this role.
+    UserRoleMinimum { minimum: UserRole },
+    /// Agent must be explicitly approved for restricted operations.
+    RestrictedApproved,
+    /// The requested action is one of `actions`.
+    ActionIn { actions: Vec<PolicyAction> },
+    /// The resource is of this kind.
+    ResourceKind { kind: ResourceKind },
+    /// The resource is one of these tools.
+    ToolIdIn { tool_ids: Vec<ToolId> },
+    /// The acting agent is at least `depth` spawns below a root agent.
+    SpawnDepthAtLeast { depth: u8 },
+    /// A named attribute satisfies `matches` (see `attributes`).
+    Attribute {
+        attribute: String,
+        matches: AttributeMatch,
+    },
+    /// The resource is a tool whose name matches a `*`/`?` pattern.
+    ToolName { pattern: String },
+    /// The resource is a tool whose version satisfies `requirement`.
+    ToolVersion { requirement: VersionReq },
+    /// The tenant is on one of `tiers`.
+    TenantTierIn { tiers: Vec<TenantTier> },
+    /// Tool-call arguments selected by `path` satisfy `matches` (see
+    /// `arguments`).
+    Argument {
+        path: ArgumentPath,
+        matches: ArgumentMatch,
+        #[serde(default)]
+        quantifier: Quantifier,
+    },
+    /// The acting principal is one of `kinds`.
+    PrincipalIn { kinds: Vec<PrincipalKind> },
+    /// The evaluation time falls inside a daily local-time window.
+    TimeOfDay(TimeWindow),
+    /// The evaluation time falls on one of the given local weekdays.
+    DaysOfWeek(DaySet),
+    /// The evaluation time falls inside a recurring maintenance window.
+    MaintenanceWindow(MaintenanceWindow),
+    /// The evaluation time is inside an absolute validity period.
+    ValidDuring(ValidityPeriod),
+    /// Every sub-condition holds (true when empty).
+    All { conditions: Vec<RuleCondition> },
+    /// At least one sub-condition holds (false when empty).
+    Any { conditions: Vec<RuleCondition> },
+    /// The sub-condition does not hold.
+    Not { condition: Box<RuleCondition> },
+    /// Always-true sentinel for default rules.
+    AlwaysAllow,
+    /// Always-false sentinel for deny-by-default.
+    AlwaysDeny,
+}
+
+impl RuleCondition {
+    /// Conjunction of `conditions`.
+    pub fn all(conditions: impl IntoIterator<Item = RuleCondition>) -> Self {
+        Self::All {
+            conditions: conditions.into_iter().collect(),
+        }
+    }
+
+    /// Disjunction of `conditions`.
+    pub fn any(conditions: impl IntoIterator<Item = RuleCondition>) -> Self {
+        Self::Any {
+            conditions: conditions.into_iter().collect(),
+        }
+    }
+
+    /// Negation of `condition`.
+    pub fn negate(condition: RuleCondition) -> Self {
+        Self::Not {
+            condition: Box::new(condition),
+        }
+    }
+
+    /// Matches only the given action.
+    pub fn action(action: PolicyAction) -> Self {
+        Self::ActionIn {
+            actions: vec![action],
+        }
+    }
+
+    /// Matches any of `actions`.
+    pub fn actions(actions: impl IntoIterator<Item = PolicyAction>) -> Self {
+        Self::ActionIn {
+            actions: actions.into_iter().collect(),
+        }
+    }
+
+    /// Matches a human user or an API key.
+    pub fn human() -> Self {
+        Self::PrincipalIn {
+            kinds: vec![PrincipalKind::User, PrincipalKind::ApiKey],
+        }
+    }
+
+    /// Matches an agent.
+    pub fn agent() -> Self {
+        Self::PrincipalIn {
+            kinds: vec![PrincipalKind::Agent],
+        }
+    }
+
+    /// User role is at least `minimum`.
+    pub fn role(minimum: UserRole) -> Self {
+        Self::UserRoleMinimum { minimum }
+    }
+
+    /// Whether the condition matches every context, so later rules never run.
+    ///
+    /// Conservative: `false` means "may not match", not "never matches".
+    pub fn is_unconditional(&self) -> bool {
+        match self {
+            Self::AlwaysAllow | Self::AlwaysDeny => true,
+            Self::All { conditions } => conditions.iter().all(Self::is_unconditional),
+            Self::Any { conditions } => conditions.iter().any(Self::is_unconditional),
+            _ => false,
+        }
+    }
+
+    /// Evaluate the condition against `ctx`.
+    pub fn matches(&self, ctx: &EvaluationContext) -> bool {
+        match self {
+            Self::All { conditions } => conditions.iter().all(|c| c.matches(ctx)),
+            Self::Any { conditions } => conditions.iter().any(|c| c.matches(ctx)),
+            Self::Not { condition } => !condition.matches(ctx),
+            leaf => leaf.matches_leaf(ctx),
+        }
+    }
+
+    /// Whether the outcome on `ctx` hinges on a relative path argument that
+    /// `path_under` cannot resolve (see `arguments`), so that neither
+    /// matching nor skipping the condition is safe.
+    pub fn is_unresolved(&self, ctx: &EvaluationContext) -> bool {
+        let settled = |c: &Self, outcome: bool| !c.is_unresolved(ctx) && c.matches(ctx) == outcome;
+        match self {
+            Self::All { conditions } => {
+                conditions.iter().any(|c| c.is_unresolved(ctx))
+                    && !conditions.iter().any(|c| settled(c, false))
+            }
+            Self::Any { conditions } => {
+                conditions.iter().any(|c| c.is_unresolved(ctx))
+                    && !conditions.iter().any(|c| settled(c, true))
+            }
+            Self::Not { condition } => condition.is_unresolved(ctx),
+            Self::Argument {
+                path,
+                matches,
+                quantifier,
+            } => ctx.resource.arguments().is_some_and(|args| {
+                quantifier.is_unresolved(&path.select(args), matches, cwd(ctx))
+            }),
+            _ => false,
+        }
+    }
+
+    fn matches_leaf(&self, ctx: &EvaluationContext) -> bool {
+        let subject = &ctx.subject;
+        let tool_access = ctx.resource.tool_access();
+        match self {
+            Self::BudgetAbove { threshold } => subject.budget_remaining_fraction <= *threshold,
+            Self::ToolAccessLevel { required } => tool_access.is_some_and(|a| a >= *required),
+            Self::ToolAccessIs { level } => tool_access == Some(*level),
+            Self::AgentTierMinimum { minimum } => subject.agent_tier.0 <= *minimum,
+            Self::RestrictedApproved => subject.restricted_approved,
+            Self::UserRoleMinimum { minimum } => {
+                subject.user_role.as_ref().is_some_and(|r| r >= minimum)
+            }
+            Self::ActionIn { actions } => actions.contains(&ctx.action),
+            Self::ResourceKind { kind } => ctx.resource.kind() == *kind,
+            Self::ToolIdIn { tool_ids } => ctx
+                .resource
+                .tool_id()
+                .is_some_and(|id| tool_ids.contains(&id)),
+            Self::SpawnDepthAtLeast { depth } => subject.spawn_depth >= *depth,
+            Self::Attribute { attribute, matches } => {
+                matches.matches(ctx.attribute(attribute).as_deref())
+            }
+            Self::ToolName { pattern } => matches!(
+                &ctx.resource,
+                PolicyResource::Tool { name, .. } if glob_match(pattern, name)
+            ),
+            Self::ToolVersion { requirement } => matches!(
+                &ctx.resource,
+                PolicyResource::Tool { version: Some(v), .. } if requirement.matches(v)
+            ),
+            Self::TenantTierIn { tiers } => tiers.contains(&ctx.tenant_tier),
+            Self::Argument {
+                path,
+                matches,
+                quantifier,
+            } => ctx
+                .resource
+                .arguments()
+                .is_some_and(|args| quantifier.test(&path.select(args), matches, cwd(ctx))),
+            Self::PrincipalIn { kinds } => kinds.contains(&subject.principal.kind()),
+            Self::TimeOfDay(window) => window.contains(ctx.evaluated_at),
+            Self::DaysOfWeek(days) => days.contains(ctx.evaluated_at),
+            Self::MaintenanceWindow(window) => window.contains(ctx.evaluated_at),
+            Self::ValidDuring(period) => period.contains(ctx.evaluated_at),
+            Self::AlwaysAllow | Self::AlwaysDeny => true,
+            Self::All { .. } | Self::Any { .. } | Self::Not { .. } => self.matches(ctx),
+        }
+    }
+}
+
+/// The call's working directory, from the `request.cwd` attribute.
+fn cwd(ctx: &EvaluationContext) -> Option<&str> {
+    ctx.attributes.get("cwd").map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use chrono::{TimeZone, Utc};
+
+    use crate::rules::AgentTier;
+    use crate::testing::tool_call;
+
+    fn protected_tier_2() -> RuleCondition {
+        RuleCondition::all([
+            RuleCondition::ToolAccessIs {
+                level: ToolAccessLevel::Protected,
+            },
+            RuleCondition::AgentTierMinimum { minimum: 2 },
+        ])
+    }
+
+    #[test]
+    fn test_all_requires_every_condition() {
+        let cond = protected_tier_2();
+        assert!(cond.matches(&tool_call(
+            ToolAccessLevel::Protected,
+            AgentTier::SPECIALIST
+        )));
+        assert!(!cond.matches(&tool_call(ToolAccessLevel::Protected, AgentTier::WORKER)));
+        assert!(!cond.matches(&tool_call(ToolAccessLevel::Public, AgentTier::BOSS)));
+    }
+
+    #[test]
+    fn test_any_and_not() {
+        let public_or_protected = RuleCondition::any([
+            RuleCondition::ToolAccessIs {
+                level: ToolAccessLevel::Public,
+            },
+            RuleCondition::ToolAccessIs {
+                level: ToolAccessLevel::Protected,
+            },
+        ]);
+        let (sensor, boss) = (AgentTier::SENSOR, AgentTier::BOSS);
+        assert!(public_or_protected.matches(&tool_call(ToolAccessLevel::Public, sensor)));
+        assert!(!public_or_protected.matches(&tool_call(ToolAccessLevel::Critical, boss)));
+        let elevated = RuleCondition::negate(public_or_protected);
+        assert!(elevated.matches(&tool_call(ToolAccessLevel::Critical, boss)));
+        assert!(!RuleCondition::any([]).matches(&tool_call(ToolAccessLevel::Public, boss)));
+    }
+
+    #[test]
+    fn test_action_and_resource_kind() {
+        let c = tool_call(ToolAccessLevel::Public, AgentTier::BOSS);
+        assert!(RuleCondition::action(PolicyAction::ToolExecute).matches(&c));
+        assert!(!RuleCondition::action(PolicyAction::AgentSpawn).matches(&c));
+        let agent = RuleCondition::ResourceKind {
+            kind: ResourceKind::Agent,
+        };
+        assert!(!agent.matches(&c));
+    }
+
+    #[test]
+    fn test_nested_conditions_round_trip_through_serde() {
+        let cond = RuleCondition::negate(protected_tier_2());
+        let json = serde_json::to_string(&cond).unwrap();
+        let back: RuleCondition = serde_json::from_str(&json).unwrap();
+        let c = tool_call(ToolAccessLevel::Protected, AgentTier::WORKER);
+        assert_eq!(back.matches(&c), cond.matches(&c));
+        assert!(json.contains(r#""type":"not""#));
+    }
+
+    #[test]
+    fn test_temporal_condition_uses_context_clock() {
+        let yaml = "
+type: maintenance_window
+days: [Sat]
+start: \"22:00:00\"
+end: \"02:00:00\"
+timezone: Europe/Berlin
+";
+        let window: RuleCondition = serde_yaml::from_str(yaml).unwrap();
+        let c = tool_call(ToolAccessLevel::Public, AgentTier::BOSS);
+        // 2026-03-07 is a Saturday; 21:30 UTC = 22:30 in Berlin.
+        let saturday = Utc.with_ymd_and_hms(2026, 3, 7, 21, 30, 0).unwrap();
+        assert!(window.matches(&c.clone().at(saturday)));
+        assert!(!window.matches(&c.at(saturday - chrono::Duration::days(1))));
+    }
+
+    #[test]
+    fn test_abac_shell_tools_for_free_tier_outside_sandbox() {
+        let yaml = "
+type: all
+conditions:
+  - { type: tool_name, pattern: \"shell_*\" }
+  - { type: tenant_tier_in, tiers: [FREE] }
+  - type: not
+    condition:
+      type: attribute
+      attribute: request.execution_scope
+      matches: { op: equals, value: sandbox }
+";
+        let cond: RuleCondition = serde_yaml::from_str(yaml).unwrap();
+        let tool = |name| {
+            tool_call(ToolAccessLevel::Public, AgentTier::WORKER).with_tool_identity(name, None)
+        };
+        let shell = tool("shell_exec");
+        let sandboxed = HashMap::from([("execution_scope".to_string(), "sandbox".to_string())]);
+
+        assert!(cond.matches(&shell));
+        assert!(!cond.matches(&shell.clone().with_attributes(sandboxed)));
+        assert!(!cond.matches(&shell.clone().with_tenant_tier(TenantTier::Pro)));
+        assert!(!cond.matches(&tool("web_search")));
+    }
+
+    #[test]
+    fn test_tool_version_range_and_numeric_attribute() {
+        let c = tool_call(ToolAccessLevel::Public, AgentTier::WORKER)
+            .with_tool_identity("http_fetch", Some(semver::Version::new(1, 4, 2)));
+        let range = |req: &str| RuleCondition::ToolVersion {
+            requirement: VersionReq::parse(req).unwrap(),
+        };
+        assert!(range(">=1.2, <2").matches(&c));
+        assert!(!range("^2").matches(&c));
+        // Tools without a known version never satisfy a range.
+        assert!(!range("*").matches(&tool_call(ToolAccessLevel::Public, AgentTier::WORKER)));
+
+        let low_tier = RuleCondition::Attribute {
+            attribute: "subject.agent_tier".into(),
+            matches: AttributeMatch::AtLeast { bound: 3.0 },
+        };
+        assert!(low_tier.matches(&c));
+        let missing = RuleCondition::Attribute {
+            attribute: "request.region".into(),
+            matches: AttributeMatch::Exists,
+        };
+        assert!(!missing.matches(&c));
+    }
+
+    #[test]
+    fn test_unconditional_detection() {
+        assert!(RuleCondition::all([RuleCondition::AlwaysAllow]).is_unconditional());
+        assert!(!protected_tier_2().is_unconditional());
+        assert!(!RuleCondition::negate(RuleCondition::AlwaysDeny).is_unconditional());
+    }
+}
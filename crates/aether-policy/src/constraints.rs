@@ -0,0 +1,138 @@
+//! Obligations attached to allow decisions (PRD §11).
+//!
+//! A rule with a `Constrain` effect does not decide; when it matches, its
+//! constraints are merged into whatever allow decision follows. Merging
+//! always keeps the stricter value, so adding a rule can only tighten an
+//! allowed call. The tool gateway enforces the merged result.
+
+use serde::{Deserialize, Serialize};
+
+use aether_core::tool::ExecutionScope;
+
+/// Conditions an allowed call must run under.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolicyConstraints {
+    /// Upper bound on the tool's timeout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_timeout_ms: Option<u64>,
+    /// Scope the call must execute in, overriding the tool's own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_scope: Option<ExecutionScope>,
+    /// The call's ledger block must be signed.
+    pub require_ledger_signing: bool,
+    /// Maximum spend for this call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_spend_usd: Option<f64>,
+    /// Tool output must be redacted before it reaches the agent.
+    pub redact_output: bool,
+}
+
+impl PolicyConstraints {
+    /// Whether no constraint is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Why merging these constraints could leave a call less restricted
+    /// than without them, if it could.
+    ///
+    /// Limits and flags only ever tighten, except a NaN spend cap, which
+    /// compares false against every bound it is merged with. A scope
+    /// overrides the tool's own, so only `Sandbox`, which no other scope
+    /// displaces, is provably at least as strict.
+    pub fn loosening(&self) -> Option<&'static str> {
+        if self.max_spend_usd.is_some_and(f64::is_nan) {
+            return Some("max_spend_usd must be a number");
+        }
+        match self.execution_scope {
+            None | Some(ExecutionScope::Sandbox) => None,
+            Some(_) => Some("execution_scope may only require SANDBOX"),
+        }
+    }
+
+    /// Tighten `self` with `other`: smaller limits, the stricter scope (see
+    /// `stricter_scope`), and any flag set by either.
+    pub fn merge(&mut self, other: &Self) {
+        self.max_timeout_ms = min_some(self.max_timeout_ms, other.max_timeout_ms);
+        self.max_spend_usd = min_some(self.max_spend_usd, other.max_spend_usd);
+        self.execution_scope = match (self.execution_scope.take(), &other.execution_scope) {
+            (Some(a), Some(b)) => Some(stricter_scope(&a, b)),
+            (a, b) => a.or_else(|| b.clone()),
+        };
+        self.require_ledger_signing |= other.require_ledger_signing;
+        self.redact_output |= other.redact_output;
+    }
+}
+
+fn min_some<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// The scope a call runs in when two rules each require one.
+///
+/// Scopes are not ranked by distance from the agent: only `Sandbox`
+/// guarantees isolation, so a sandbox requirement survives every merge.
+/// `External` says where a call is queued, not how it is isolated, and
+/// only displaces `Inline`.
+fn stricter_scope(a: &ExecutionScope, b: &ExecutionScope) -> ExecutionScope {
+    use ExecutionScope::{External, Inline, Sandbox};
+    match (a, b) {
+        (Sandbox, _) | (_, Sandbox) => Sandbox,
+        (External, _) | (_, External) => External,
+        (Inline, Inline) => Inline,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_keeps_stricter_values() {
+        let mut merged = PolicyConstraints {
+            max_timeout_ms: Some(30_000),
+            execution_scope: Some(ExecutionScope::Sandbox),
+            ..Default::default()
+        };
+        merged.merge(&PolicyConstraints {
+            max_timeout_ms: Some(60_000),
+            execution_scope: Some(ExecutionScope::Inline),
+            max_spend_usd: Some(0.5),
+            redact_output: true,
+            ..Default::default()
+        });
+        assert_eq!(merged.max_timeout_ms, Some(30_000));
+        assert_eq!(merged.execution_scope, Some(ExecutionScope::Sandbox));
+        assert_eq!(merged.max_spend_usd, Some(0.5));
+        assert!(merged.redact_output && !merged.require_ledger_signing);
+
+        let scope = |a: ExecutionScope, b: ExecutionScope| {
+            let mut merged = PolicyConstraints {
+                execution_scope: Some(a),
+                ..Default::default()
+            };
+            merged.merge(&PolicyConstraints {
+                execution_scope: Some(b),
+                ..Default::default()
+            });
+            merged.execution_scope
+        };
+        use ExecutionScope::{External, Inline, Sandbox};
+        assert_eq!(scope(Sandbox, External), Some(Sandbox));
+        assert_eq!(scope(External, Sandbox), Some(Sandbox));
+        assert_eq!(scope(Inline, External), Some(External));
+        assert_eq!(scope(External, Inline), Some(External));
+    }
+
+    #[test]
+    fn test_empty_constraints_deserialize_from_empty_object() {
+        let parsed: PolicyConstraints = serde_json::from_str("{}").unwrap();
+        assert!(parsed.is_empty());
+        let json = serde_json::to_string(&parsed).unwrap();
+        assert!(!json.contains("max_timeout_ms"));
+    }
+}
@@ -0,0 +1,291 @@
+//! Built-in rule set (PRD §03, §11).
+//!
+//! Human principals (users and API keys) get tenant RBAC —
+//! Owner → Admin → Developer → Viewer — over every `PolicyAction`. Agents
+//! get tier-gated tool access plus the few non-tool actions agents perform.
+//! Tool rules match on the exact access level and apply to every principal,
+//! so budget exhaustion and human approval hold for users too. Anything not
+//! allowed here falls through to the engine's default deny.
+
+use aether_core::tenant::UserRole;
+use aether_core::tool::ToolAccessLevel;
+
+use crate::condition::RuleCondition;
+use crate::rules::{PolicyAction, PolicyEffect, PolicyRule};
+
+/// Non-tool actions a Developer may take.
+const DEVELOPER_ACTIONS: [PolicyAction; 4] = [
+    PolicyAction::WorkflowCreate,
+    PolicyAction::WorkflowRun,
+    PolicyAction::MemoryWrite,
+    PolicyAction::AgentSpawn,
+];
+
+/// Non-tool actions that additionally need Admin.
+const ADMIN_ACTIONS: [PolicyAction; 4] = [
+    PolicyAction::WorkflowDelete,
+    PolicyAction::CronCreate,
+    PolicyAction::MemoryDelete,
+    PolicyAction::HumanReviewApprove,
+];
+
+/// Built-in ruleset.
+pub fn default_rules() -> Vec<PolicyRule> {
+    let mut rules = vec![PolicyRule::new(
+        "budget-exhausted-deny",
+        "Deny all tool calls when budget is fully exhausted",
+        RuleCondition::BudgetAbove { threshold: 0.0 },
+        PolicyEffect::deny("budget exhausted"),
+    )];
+    rules.extend(rbac_rules());
+    rules.extend(agent_rules());
+    rules.extend(tool_rules());
+    rules
+}
+
+/// Tenant RBAC for users and API keys.
+fn rbac_rules() -> Vec<PolicyRule> {
+    let human = |condition| RuleCondition::all([RuleCondition::human(), condition]);
+    vec![
+        PolicyRule::new(
+            "rbac-viewer-read-only",
+            "Viewers have read-only access",
+            human(RuleCondition::negate(RuleCondition::role(
+                UserRole::Developer,
+            ))),
+            PolicyEffect::deny("Viewer role is read-only"),
+        ),
+        PolicyRule::new(
+            "rbac-owner-system-config",
+            "Only Owners change tenant configuration",
+            human(RuleCondition::all([
+                RuleCondition::action(PolicyAction::SystemConfig),
+                RuleCondition::role(UserRole::Owner),
+            ])),
+            PolicyEffect::Allow,
+        ),
+        PolicyRule::new(
+            "rbac-admin-manage",
+            "Admins delete workflows, schedule crons, delete memory and approve reviews",
+            human(RuleCondition::all([
+                RuleCondition::actions(ADMIN_ACTIONS),
+                RuleCondition::role(UserRole::Admin),
+            ])),
+            PolicyEffect::Allow,
+        ),
+        PolicyRule::new(
+            "rbac-developer-build",
+            "Developers create and run workflows, write memory and spawn agents",
+            human(RuleCondition::actions(DEVELOPER_ACTIONS)),
+            PolicyEffect::Allow,
+        ),
+        PolicyRule::new(
+            "rbac-insufficient-role",
+            "Other non-tool actions need a higher role",
+            human(RuleCondition::negate(RuleCondition::action(
+                PolicyAction::ToolExecute,
+            ))),
+            PolicyEffect::deny("role does not permit this action"),
+        ),
+    ]
+}
+
+/// Non-tool actions agents take on their own.
+fn agent_rules() -> Vec<PolicyRule> {
+    let agent = |condition| RuleCondition::all([RuleCondition::agent(), condition]);
+    vec![
+        PolicyRule::new(
+            "agent-spawn-tier-2",
+            "Tier ≤ 2 agents spawn sub-agents and run workflows",
+            agent(RuleCondition::all([
+                RuleCondition::actions([PolicyAction::AgentSpawn, PolicyAction::WorkflowRun]),
+                RuleCondition::AgentTierMinimum { minimum: 2 },
+            ])),
+            PolicyEffect::Allow,
+        ),
+        PolicyRule::new(
+            "agent-memory-write",
+            "Agents write to memory",
+            agent(RuleCondition::action(PolicyAction::MemoryWrite)),
+            PolicyEffect::Allow,
+        ),
+    ]
+}
+
+/// Tool execution by access level, for every principal.
+fn tool_rules() -> Vec<PolicyRule> {
+    vec![
+        PolicyRule::new(
+            "critical-tool-human-approval",
+            "CRITICAL tools require human approval — agents cannot self-approve",
+            tool_access(ToolAccessLevel::Critical),
+            PolicyEffect::require_approval(
+                "CRITICAL tools require human-in-the-loop approval",
+                UserRole::Owner,
+            ),
+        ),
+        PolicyRule::new(
+            "restricted-tool-approved-allow",
+            "RESTRICTED tools are allowed once the agent is pre-approved",
+            RuleCondition::all([
+                tool_access(ToolAccessLevel::Restricted),
+                RuleCondition::RestrictedApproved,
+            ]),
+            PolicyEffect::Allow,
+        ),
+        PolicyRule::new(
+            "restricted-tool-requires-approval",
+            "RESTRICTED tools need pre-approval or a per-call human approval",
+            tool_access(ToolAccessLevel::Restricted),
+            PolicyEffect::require_approval("RESTRICTED tool requires approval", UserRole::Admin),
+        ),
+        PolicyRule::new(
+            "protected-tool-tier-2-minimum",
+            "PROTECTED tools require Tier ≤ 2 agents or Developer users",
+            RuleCondition::all([
+                tool_access(ToolAccessLevel::Protected),
+                RuleCondition::any([
+                    RuleCondition::AgentTierMinimum { minimum: 2 },
+                    RuleCondition::all([
+                        RuleCondition::human(),
+                        RuleCondition::role(UserRole::Developer),
+                    ]),
+                ]),
+            ]),
+            PolicyEffect::Allow,
+        ),
+        PolicyRule::new(
+            "protected-tool-tier-deny",
+            "PROTECTED tools are denied below Tier 2",
+            tool_access(ToolAccessLevel::Protected),
+            PolicyEffect::deny("PROTECTED tool requires a Tier 1 or Tier 2 agent"),
+        ),
+        PolicyRule::new(
+            "public-tool-allow-all",
+            "PUBLIC tools are available to all agents",
+            tool_access(ToolAccessLevel::Public),
+            PolicyEffect::Allow,
+        ),
+    ]
+}
+
+/// Tool execution of a tool with exactly `level` access.
+fn tool_access(level: ToolAccessLevel) -> RuleCondition {
+    RuleCondition::all([
+        RuleCondition::action(PolicyAction::ToolExecute),
+        RuleCondition::ToolAccessIs { level },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aether_core::ids::{TenantId, UserId, WorkflowId};
+
+    use crate::document::PolicyDocument;
+    use crate::engine::PolicyEngine;
+    use crate::evaluation::{EvaluationContext, PolicyResource};
+    use crate::rules::AgentTier;
+    use crate::testing::tool_call;
+
+    const ALL_ACTIONS: [PolicyAction; 9] = [
+        PolicyAction::AgentSpawn,
+        PolicyAction::MemoryWrite,
+        PolicyAction::MemoryDelete,
+        PolicyAction::WorkflowRun,
+        PolicyAction::WorkflowCreate,
+        PolicyAction::WorkflowDelete,
+        PolicyAction::CronCreate,
+        PolicyAction::HumanReviewApprove,
+        PolicyAction::SystemConfig,
+    ];
+
+    fn allowed(engine: &PolicyEngine, role: UserRole, action: PolicyAction) -> bool {
+        let resource = PolicyResource::Workflow {
+            workflow_id: WorkflowId::new(),
+        };
+        let ctx =
+            EvaluationContext::for_user(TenantId::new(), UserId::new(), role, action, resource);
+        engine.decide(&ctx).is_allowed()
+    }
+
+    #[test]
+    fn test_default_rules_form_valid_document() {
+        let document = PolicyDocument {
+            description: String::new(),
+            rules: default_rules(),
+        };
+        assert!(document.validate().is_ok());
+    }
+
+    #[test]
+    fn test_role_matrix_covers_every_action() {
+        let engine = PolicyEngine::default();
+        for action in ALL_ACTIONS {
+            let expected_minimum = if action == PolicyAction::SystemConfig {
+                UserRole::Owner
+            } else if ADMIN_ACTIONS.contains(&action) {
+                UserRole::Admin
+            } else {
+                UserRole::Developer
+            };
+            for role in [
+                UserRole::Viewer,
+                UserRole::Developer,
+                UserRole::Admin,
+                UserRole::Owner,
+            ] {
+                let expected = role >= expected_minimum;
+                assert_eq!(
+                    allowed(&engine, role.clone(), action),
+                    expected,
+                    "{role:?} {action:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_api_key_uses_its_role() {
+        let engine = PolicyEngine::default();
+        let ctx = |role| {
+            EvaluationContext::for_api_key(
+                TenantId::new(),
+                "ci-deploy",
+                role,
+                PolicyAction::CronCreate,
+                PolicyResource::Schedule {
+                    workflow_id: WorkflowId::new(),
+                },
+            )
+        };
+        assert!(engine.decide(&ctx(UserRole::Admin)).is_allowed());
+        let denied = engine.decide(&ctx(UserRole::Developer));
+        assert_eq!(denied.matched_rule, "rbac-insufficient-role");
+    }
+
+    #[test]
+    fn test_agents_cannot_approve_reviews_or_configure() {
+        let engine = PolicyEngine::default();
+        for action in [PolicyAction::HumanReviewApprove, PolicyAction::SystemConfig] {
+            let mut ctx = EvaluationContext::for_user(
+                TenantId::new(),
+                UserId::new(),
+                UserRole::Owner,
+                action,
+                PolicyResource::Tenant,
+            );
+            ctx.subject.principal = crate::rules::Principal::Agent;
+            ctx.subject.agent_tier = crate::rules::AgentTier::BOSS;
+            assert!(!engine.decide(&ctx).is_allowed(), "{action:?}");
+        }
+    }
+
+    #[test]
+    fn test_agent_role_does_not_unlock_protected_tools() {
+        let engine = PolicyEngine::default();
+        let mut ctx = tool_call(ToolAccessLevel::Protected, AgentTier::WORKER);
+        ctx.subject.user_role = Some(UserRole::Developer);
+        assert_eq!(engine.decide(&ctx).matched_rule, "protected-tool-tier-deny");
+    }
+}
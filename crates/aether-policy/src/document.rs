@@ -0,0 +1,391 @@
+//! Declarative policy documents loaded from YAML, JSON or TOML (PRD §11).
+//!
+//! A `PolicyDocument` is an ordered rule list, the same data `default_rules`
+//! builds in code. Documents are validated on load: rule ids must be unique
+//! and non-empty, and no rule may follow an unconditional allow or deny (it
+//! could never match under first-match-wins).
+//!
+//! A policy directory holds `default.<ext>` (optional, replaces the built-in
+//! rules), `platform.<ext>` (optional, platform-mandatory rules), one
+//! `<tenant_id>.<ext>` per tenant with its own policy set and one
+//! `<tenant_id>.overlay.<ext>` per tenant overlay.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::TenantId;
+
+use crate::rules::PolicyRule;
+
+/// File stem of a directory's engine-wide policy document.
+pub const DEFAULT_POLICY_STEM: &str = "default";
+
+/// File stem of a directory's platform-mandatory policy document.
+pub const PLATFORM_POLICY_STEM: &str = "platform";
+
+/// Stem suffix marking a tenant overlay (`<tenant_id>.overlay.<ext>`).
+pub const OVERLAY_SUFFIX: &str = ".overlay";
+
+/// Serialization format of a policy document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl PolicyFormat {
+    /// Format for a file extension (`yaml`, `yml`, `json`, `toml`).
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// An ordered, validated set of policy rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyDocument {
+    #[serde(default)]
+    pub description: String,
+    pub rules: Vec<PolicyRule>,
+}
+
+impl PolicyDocument {
+    /// Parse and validate a document.
+    ///
+    /// # Errors
+    /// Returns `SerializationError` for malformed input and
+    /// `ValidationFailed` if the rules are inconsistent.
+    pub fn parse(input: &str, format: PolicyFormat) -> Result<Self> {
+        let parsed = match format {
+            PolicyFormat::Yaml => serde_yaml::from_str(input).map_err(|e| e.to_string()),
+            PolicyFormat::Json => serde_json::from_str(input).map_err(|e| e.to_string()),
+            PolicyFormat::Toml => toml::from_str(input).map_err(|e| e.to_string()),
+        };
+        let document: Self = parsed.map_err(|e| {
+            AetherError::SerializationError(format!("invalid {format:?} policy document: {e}"))
+        })?;
+        document.validate()?;
+        Ok(document)
+    }
+
+    /// Load a document, choosing the format from the file extension.
+    ///
+    /// # Errors
+    /// As `parse`, plus `StorageError` if the file cannot be read and
+    /// `ValidationFailed` for an unknown extension.
+    pub fn load(path: &Path) -> Result<Self> {
+        let format = format_of(path).ok_or_else(|| AetherError::ValidationFailed {
+            field: "policy_file".into(),
+            reason: format!(
+                "{} is not a .yaml, .yml, .json or .toml file",
+                path.display()
+            ),
+        })?;
+        let input = fs::read_to_string(path).map_err(|e| {
+            AetherError::StorageError(format!("cannot read policy {}: {e}", path.display()))
+        })?;
+        Self::parse(&input, format)
+    }
+
+    /// Check rule ids and reachability.
+    ///
+    /// # Errors
+    /// Returns `ValidationFailed` naming the offending rule.
+    pub fn validate(&self) -> Result<()> {
+        let mut ids = HashSet::new();
+        let mut unconditional: Option<&str> = None;
+        for (i, rule) in self.rules.iter().enumerate() {
+            let invalid = |field: &str, reason: String| AetherError::ValidationFailed {
+                field: format!("rules[{i}].{field}"),
+                reason,
+            };
+            if rule.id.trim().is_empty() {
+                return Err(invalid("id", "rule id must not be empty".into()));
+            }
+            if !ids.insert(rule.id.as_str()) {
+                return Err(invalid("id", format!("duplicate rule id '{}'", rule.id)));
+            }
+            if let Some(earlier) = unconditional {
+                let reason = format!(
+                    "rule '{}' is unreachable after unconditional rule '{earlier}'",
+                    rule.id
+                );
+                return Err(invalid("condition", reason));
+            }
+            if rule.condition.is_unconditional() && rule.effect.is_decisive() {
+                unconditional = Some(&rule.id);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every policy document in a directory.
+#[derive(Debug, Clone, Default)]
+pub struct PolicyDirectory {
+    /// `default.<ext>`, if present.
+    pub default: Option<PolicyDocument>,
+    /// `platform.<ext>`, if present.
+    pub platform: Option<PolicyDocument>,
+    pub tenants: HashMap<TenantId, PolicyDocument>,
+    pub overlays: HashMap<TenantId, PolicyDocument>,
+}
+
+impl PolicyDirectory {
+    /// Load `default.<ext>` and every `<tenant_id>.<ext>` in `dir`.
+    ///
+    /// Files with other extensions are ignored.
+    ///
+    /// # Errors
+    /// Returns `ValidationFailed` for a policy file whose name is not
+    /// `default`, `platform`, a tenant id or a tenant overlay, or for two
+    /// files naming the same policy set, and propagates
+    /// `PolicyDocument::load` errors.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let entries = fs::read_dir(dir).map_err(|e| {
+            AetherError::StorageError(format!("cannot read policy dir {}: {e}", dir.display()))
+        })?;
+        let mut loaded = Self::default();
+        for entry in entries {
+            let path = entry
+                .map_err(|e| AetherError::StorageError(e.to_string()))?
+                .path();
+            if format_of(&path).is_none() {
+                continue;
+            }
+            loaded.insert(&path, PolicyDocument::load(&path)?)?;
+        }
+        Ok(loaded)
+    }
+
+    fn insert(&mut self, path: &Path, document: PolicyDocument) -> Result<()> {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let invalid = |reason: String| AetherError::ValidationFailed {
+            field: "policy_file".into(),
+            reason,
+        };
+        let tenant = |name: &str| {
+            name.parse::<TenantId>()
+                .map_err(|_| invalid(format!("{} is not named after a tenant id", path.display())))
+        };
+        let duplicate = if stem == DEFAULT_POLICY_STEM {
+            self.default.replace(document).is_some()
+        } else if stem == PLATFORM_POLICY_STEM {
+            self.platform.replace(document).is_some()
+        } else if let Some(name) = stem.strip_suffix(OVERLAY_SUFFIX) {
+            self.overlays.insert(tenant(name)?, document).is_some()
+        } else {
+            self.tenants.insert(tenant(stem)?, document).is_some()
+        };
+        if duplicate {
+            return Err(invalid(format!("more than one policy file for '{stem}'")));
+        }
+        Ok(())
+    }
+}
+
+fn format_of(path: &Path) -> Option<PolicyFormat> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .and_then(PolicyFormat::from_extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use aether_core::error::ErrorCode;
+    use aether_core::tool::ToolAccessLevel;
+
+    use crate::condition::RuleCondition;
+    use crate::engine::PolicyEngine;
+    use crate::rules::{AgentTier, PolicyEffect};
+    use crate::testing::{tool_call, ToolCall};
+
+    const YAML: &str = "
+description: sensors only
+rules:
+  - id: tier-gate
+    condition: { type: agent_tier_minimum, minimum: 3 }
+    effect: { type: deny, reason: too privileged }
+  - id: allow-rest
+    condition: { type: always_allow }
+    effect: { type: allow }
+";
+
+    const JSON: &str = r#"{
+  "description": "sensors only",
+  "rules": [
+    {"id": "tier-gate", "condition": {"type": "agent_tier_minimum", "minimum": 3},
+     "effect": {"type": "deny", "reason": "too privileged"}},
+    {"id": "allow-rest", "condition": {"type": "always_allow"}, "effect": {"type": "allow"}}
+  ]
+}"#;
+
+    const TOML: &str = r#"
+description = "sensors only"
+
+[[rules]]
+id = "tier-gate"
+condition = { type = "agent_tier_minimum", minimum = 3 }
+effect = { type = "deny", reason = "too privileged" }
+
+[[rules]]
+id = "allow-rest"
+condition = { type = "always_allow" }
+effect = { type = "allow" }
+"#;
+
+    /// Temporary policy directory removed on drop.
+    struct TempPolicyDir(PathBuf);
+
+    impl TempPolicyDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("aether-policy-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn write(&self, name: &str, contents: &str) {
+            fs::write(self.0.join(name), contents).unwrap();
+        }
+    }
+
+    impl Drop for TempPolicyDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn rule(id: &str, condition: RuleCondition) -> PolicyRule {
+        PolicyRule::new(id, "", condition, PolicyEffect::Allow)
+    }
+
+    fn validation_field(document: PolicyDocument) -> String {
+        match document.validate() {
+            Err(AetherError::ValidationFailed { field, .. }) => field,
+            other => panic!("expected ValidationFailed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_formats_parse_to_same_rules() {
+        for (input, format) in [
+            (YAML, PolicyFormat::Yaml),
+            (JSON, PolicyFormat::Json),
+            (TOML, PolicyFormat::Toml),
+        ] {
+            let document = PolicyDocument::parse(input, format).unwrap();
+            let ids: Vec<&str> = document.rules.iter().map(|r| r.id.as_str()).collect();
+            assert_eq!(ids, ["tier-gate", "allow-rest"], "{format:?}");
+            assert_eq!(
+                document.rules[0].effect,
+                PolicyEffect::deny("too privileged")
+            );
+        }
+    }
+
+    #[test]
+    fn test_malformed_document_is_serialization_error() {
+        let err = PolicyDocument::parse("rules: [{ id: x }]", PolicyFormat::Yaml).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::SerializationError);
+    }
+
+    #[test]
+    fn test_duplicate_rule_id_rejected() {
+        let document = PolicyDocument {
+            description: String::new(),
+            rules: vec![
+                rule("a", RuleCondition::RestrictedApproved),
+                rule("a", RuleCondition::AlwaysAllow),
+            ],
+        };
+        assert_eq!(validation_field(document), "rules[1].id");
+    }
+
+    #[test]
+    fn test_rule_after_unconditional_rejected() {
+        let document = PolicyDocument {
+            description: String::new(),
+            rules: vec![
+                rule("all", RuleCondition::AlwaysDeny),
+                rule("never", RuleCondition::RestrictedApproved),
+            ],
+        };
+        assert_eq!(validation_field(document), "rules[1].condition");
+    }
+
+    #[test]
+    fn test_directory_policy_applies_only_to_its_tenant() {
+        let dir = TempPolicyDir::new();
+        let (tenant, other) = (TenantId::new(), TenantId::new());
+        dir.write(&format!("{tenant}.yaml"), YAML);
+        dir.write("README.md", "ignored");
+
+        let engine = PolicyEngine::from_policy_dir(&dir.0).unwrap();
+        assert_eq!(engine.rules_for(&tenant).next().unwrap().id, "tier-gate");
+        let call = |tenant_id, tier| ToolCall::new(ToolAccessLevel::Public, tier).tenant(tenant_id);
+        assert!(!engine
+            .decide(&call(tenant, AgentTier::BOSS).build())
+            .is_allowed());
+        assert!(engine
+            .decide(&call(tenant, AgentTier::SENSOR).build())
+            .is_allowed());
+        // Tenants without a file keep the built-in rules.
+        assert!(engine
+            .decide(&call(other, AgentTier::BOSS).build())
+            .is_allowed());
+    }
+
+    #[test]
+    fn test_directory_default_replaces_builtin_rules() {
+        let dir = TempPolicyDir::new();
+        dir.write("default.json", JSON);
+        let engine = PolicyEngine::from_policy_dir(&dir.0).unwrap();
+        assert!(!engine
+            .decide(&tool_call(ToolAccessLevel::Public, AgentTier::BOSS))
+            .is_allowed());
+    }
+
+    #[test]
+    fn test_directory_loads_platform_and_overlays() {
+        let dir = TempPolicyDir::new();
+        let tenant = TenantId::new();
+        dir.write("platform.yaml", "rules: []");
+        dir.write(&format!("{tenant}.overlay.json"), JSON);
+        // The overlay's `allow-rest` rule would widen the base rules.
+        let err = PolicyEngine::from_policy_dir(&dir.0).err().unwrap();
+        assert_eq!(err.code(), ErrorCode::ValidationFailed);
+
+        let loaded = PolicyDirectory::load(&dir.0).unwrap();
+        assert!(loaded.platform.is_some() && loaded.overlays.contains_key(&tenant));
+    }
+
+    #[test]
+    fn test_directory_rejects_unknown_name_and_duplicates() {
+        let dir = TempPolicyDir::new();
+        dir.write("staging.toml", TOML);
+        assert!(PolicyDirectory::load(&dir.0).is_err());
+
+        let dir = TempPolicyDir::new();
+        dir.write("default.yaml", YAML);
+        dir.write("default.toml", TOML);
+        let err = PolicyDirectory::load(&dir.0).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::ValidationFailed);
+    }
+}
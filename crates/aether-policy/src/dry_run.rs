@@ -0,0 +1,277 @@
+//! Policy dry runs against recorded traffic (PRD §11).
+//!
+//! Before rolling out a rule change, replay a corpus of recorded
+//! `EvaluationContext`s through the current and the candidate engine and
+//! look at every decision whose effect (allow, deny, pending approval)
+//! changes, or whose effect holds but under different constraints (e.g. a
+//! call that is still allowed but now sandboxed). Contexts keep their
+//! recorded `evaluated_at`, so temporal rules replay as they were decided.
+//! Flips are grouped by the candidate's deciding rule, by tenant and by
+//! action to show who a change would affect.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
+
+use serde::{Deserialize, Serialize};
+
+use aether_core::error::{AetherError, Result};
+use aether_core::ids::TenantId;
+
+use crate::engine::PolicyEngine;
+use crate::evaluation::{EvaluationContext, PolicyDecision};
+use crate::rules::PolicyAction;
+
+/// One context whose decision effect or constraints changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionFlip {
+    /// Position in the corpus (0-based).
+    pub index: usize,
+    pub tenant_id: TenantId,
+    pub action: PolicyAction,
+    pub before: PolicyDecision,
+    pub after: PolicyDecision,
+}
+
+impl DecisionFlip {
+    /// Whether the candidate blocks what the current engine allowed.
+    pub fn newly_denied(&self) -> bool {
+        self.before.is_allowed() && !self.after.is_allowed()
+    }
+
+    /// Whether the candidate allows what the current engine blocked.
+    pub fn newly_allowed(&self) -> bool {
+        !self.before.is_allowed() && self.after.is_allowed()
+    }
+
+    /// Whether only the constraints changed, not the effect.
+    pub fn constraints_changed(&self) -> bool {
+        self.before.effect == self.after.effect
+    }
+}
+
+/// Flip counts by direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlipCounts {
+    pub newly_denied: usize,
+    pub newly_allowed: usize,
+    /// Still blocked, but switched between deny and pending approval.
+    pub approval_changes: usize,
+    /// Same effect, but different constraints (e.g. allowed, now sandboxed).
+    pub constraint_changes: usize,
+}
+
+impl FlipCounts {
+    fn add(&mut self, flip: &DecisionFlip) {
+        if flip.constraints_changed() {
+            self.constraint_changes += 1;
+        } else if flip.newly_denied() {
+            self.newly_denied += 1;
+        } else if flip.newly_allowed() {
+            self.newly_allowed += 1;
+        } else {
+            self.approval_changes += 1;
+        }
+    }
+}
+
+/// Outcome of a dry run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DryRunReport {
+    /// Contexts evaluated.
+    pub evaluated: usize,
+    /// Contexts whose deciding rule changed but whose effect and
+    /// constraints did not.
+    pub rule_changes: usize,
+    pub flips: Vec<DecisionFlip>,
+    /// Keyed by the candidate engine's deciding rule.
+    pub by_rule: BTreeMap<String, FlipCounts>,
+    pub by_tenant: HashMap<TenantId, FlipCounts>,
+    pub by_action: HashMap<PolicyAction, FlipCounts>,
+}
+
+impl DryRunReport {
+    /// Totals across all flips.
+    pub fn totals(&self) -> FlipCounts {
+        let mut totals = FlipCounts::default();
+        self.flips.iter().for_each(|f| totals.add(f));
+        totals
+    }
+
+    fn record(&mut self, ctx: &EvaluationContext, before: PolicyDecision, after: PolicyDecision) {
+        let index = self.evaluated;
+        self.evaluated += 1;
+        if before.effect == after.effect && before.constraints == after.constraints {
+            if before.matched_rule != after.matched_rule {
+                self.rule_changes += 1;
+            }
+            return;
+        }
+        let flip = DecisionFlip {
+            index,
+            tenant_id: ctx.tenant_id,
+            action: ctx.action,
+            before,
+            after,
+        };
+        self.by_rule
+            .entry(flip.after.matched_rule.clone())
+            .or_default()
+            .add(&flip);
+        self.by_tenant.entry(flip.tenant_id).or_default().add(&flip);
+        self.by_action.entry(flip.action).or_default().add(&flip);
+        self.flips.push(flip);
+    }
+}
+
+/// Replay `contexts` through `current` and `candidate`.
+pub fn dry_run<'a>(
+    current: &PolicyEngine,
+    candidate: &PolicyEngine,
+    contexts: impl IntoIterator<Item = &'a EvaluationContext>,
+) -> DryRunReport {
+    let mut report = DryRunReport::default();
+    for ctx in contexts {
+        report.record(ctx, current.decide(ctx), candidate.decide(ctx));
+    }
+    report
+}
+
+/// Replay a JSON Lines corpus (one serialized context per line; blank
+/// lines are skipped).
+///
+/// # Errors
+/// Returns `StorageError` if reading fails and `SerializationError` naming
+/// the line number of a malformed context.
+pub fn dry_run_jsonl(
+    current: &PolicyEngine,
+    candidate: &PolicyEngine,
+    corpus: impl BufRead,
+) -> Result<DryRunReport> {
+    let mut report = DryRunReport::default();
+    for (i, line) in corpus.lines().enumerate() {
+        let line = line.map_err(|e| AetherError::StorageError(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let ctx: EvaluationContext = serde_json::from_str(&line)
+            .map_err(|e| AetherError::SerializationError(format!("corpus line {}: {e}", i + 1)))?;
+        report.record(&ctx, current.decide(&ctx), candidate.decide(&ctx));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aether_core::error::ErrorCode;
+    use aether_core::tool::{ExecutionScope, ToolAccessLevel};
+
+    use crate::condition::RuleCondition;
+    use crate::constraints::PolicyConstraints;
+    use crate::defaults::default_rules;
+    use crate::rules::{AgentTier, PolicyEffect, PolicyRule};
+    use crate::testing::ToolCall;
+
+    /// Candidate that also denies PUBLIC tools to sensors.
+    fn stricter() -> PolicyEngine {
+        let mut rules = vec![PolicyRule::new(
+            "sensor-deny",
+            "",
+            RuleCondition::negate(RuleCondition::AgentTierMinimum { minimum: 3 }),
+            PolicyEffect::deny("sensors are read-only"),
+        )];
+        rules.extend(default_rules());
+        PolicyEngine::with_rules(rules)
+    }
+
+    #[test]
+    fn test_flips_grouped_by_rule_tenant_and_action() {
+        let (t1, t2) = (TenantId::new(), TenantId::new());
+        let corpus = [
+            ToolCall::new(ToolAccessLevel::Public, AgentTier::SENSOR)
+                .tenant(t1)
+                .build(),
+            ToolCall::new(ToolAccessLevel::Public, AgentTier::BOSS)
+                .tenant(t1)
+                .build(),
+            ToolCall::new(ToolAccessLevel::Public, AgentTier::SENSOR)
+                .tenant(t2)
+                .build(),
+            ToolCall::new(ToolAccessLevel::Critical, AgentTier::SENSOR)
+                .tenant(t2)
+                .build(),
+        ];
+        let report = dry_run(&PolicyEngine::default(), &stricter(), &corpus);
+
+        assert_eq!(report.evaluated, 4);
+        let indices: Vec<usize> = report.flips.iter().map(|f| f.index).collect();
+        assert_eq!(indices, [0, 2, 3]);
+        assert_eq!(report.rule_changes, 0);
+        assert_eq!(report.by_rule["sensor-deny"].newly_denied, 2);
+        // The critical call moves from pending approval to a hard deny.
+        assert_eq!(report.by_rule["sensor-deny"].approval_changes, 1);
+        assert_eq!(report.by_tenant[&t1].newly_denied, 1);
+        assert_eq!(report.by_tenant[&t2].newly_denied, 1);
+        assert_eq!(report.by_action[&PolicyAction::ToolExecute].newly_denied, 2);
+        assert_eq!(report.totals().newly_allowed, 0);
+    }
+
+    #[test]
+    fn test_constraint_only_changes_are_flips() {
+        let mut rules = vec![PolicyRule::new(
+            "sandbox-all",
+            "",
+            RuleCondition::AlwaysAllow,
+            PolicyEffect::Constrain {
+                constraints: PolicyConstraints {
+                    execution_scope: Some(ExecutionScope::Sandbox),
+                    ..Default::default()
+                },
+            },
+        )];
+        rules.extend(default_rules());
+        let candidate = PolicyEngine::with_rules(rules);
+        let corpus = [
+            ToolCall::new(ToolAccessLevel::Public, AgentTier::WORKER).build(),
+            // Denials carry no constraints, so they do not flip.
+            ToolCall::new(ToolAccessLevel::Public, AgentTier::WORKER)
+                .budget(0.0)
+                .build(),
+        ];
+        let report = dry_run(&PolicyEngine::default(), &candidate, &corpus);
+
+        assert_eq!(report.flips.len(), 1);
+        assert!(report.flips[0].constraints_changed());
+        assert_eq!(report.rule_changes, 0);
+        let totals = report.totals();
+        assert_eq!((totals.constraint_changes, totals.newly_denied), (1, 0));
+        assert_eq!(
+            report.by_rule["public-tool-allow-all"].constraint_changes,
+            1
+        );
+    }
+
+    #[test]
+    fn test_jsonl_corpus() {
+        let t = TenantId::new();
+        let recorded = [
+            ToolCall::new(ToolAccessLevel::Public, AgentTier::SENSOR)
+                .tenant(t)
+                .build(),
+            ToolCall::new(ToolAccessLevel::Public, AgentTier::BOSS)
+                .tenant(t)
+                .build(),
+        ];
+        let corpus: String = recorded
+            .iter()
+            .map(|c| serde_json::to_string(c).unwrap() + "\n\n")
+            .collect();
+        let report =
+            dry_run_jsonl(&stricter(), &PolicyEngine::default(), corpus.as_bytes()).unwrap();
+        assert_eq!(report.evaluated, 2);
+        assert_eq!(report.totals().newly_allowed, 1);
+
+        let err = dry_run_jsonl(&stricter(), &stricter(), "{}\n".as_bytes()).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::SerializationError);
+    }
+}
@@ -5,33 +5,132 @@
 //!
 //! Rule order matters: most-restrictive rules first (budget, critical, restricted)
 //! then permissive rules (protected, public).
+//!
+//! Each tenant may have its own rule set; tenants without one use the
+//! engine-wide rules. Platform-mandatory rules run before either, and a
+//! tenant's overlay runs between the two (see `overlay`).
+
+use std::collections::HashMap;
+use std::path::Path;
 
 use aether_core::error::{AetherError, Result};
-use aether_core::tool::ToolAccessLevel;
+use aether_core::ids::TenantId;
 
-use crate::evaluation::{DecisionEffect, EvaluationContext, PolicyDecision, PolicyResource};
-use crate::rules::{AgentTier, PolicyEffect, PolicyRule, RuleCondition, default_rules};
+use crate::constraints::PolicyConstraints;
+use crate::defaults::default_rules;
+use crate::document::{PolicyDirectory, PolicyDocument};
+use crate::evaluation::{EvaluationContext, PolicyDecision, PolicyResource};
+use crate::explain::{PolicyExplanation, RuleTrace};
+use crate::overlay::validate_overlay;
+use crate::rules::PolicyRule;
 
 /// Central policy evaluation engine.
 ///
 /// # Single Responsibility
 /// Only evaluates rules. Does not store state. Does not call external services.
 pub struct PolicyEngine {
+    platform_rules: Vec<PolicyRule>,
     rules: Vec<PolicyRule>,
+    tenant_rules: HashMap<TenantId, Vec<PolicyRule>>,
+    overlays: HashMap<TenantId, PolicyDocument>,
 }
 
 impl Default for PolicyEngine {
     fn default() -> Self {
-        Self {
-            rules: default_rules(),
-        }
+        Self::with_rules(default_rules())
     }
 }
 
 impl PolicyEngine {
     /// Create engine with custom rules.
     pub fn with_rules(rules: Vec<PolicyRule>) -> Self {
-        Self { rules }
+        Self {
+            platform_rules: Vec::new(),
+            rules,
+            tenant_rules: HashMap::new(),
+            overlays: HashMap::new(),
+        }
+    }
+
+    /// Rules evaluated first for every tenant; no tenant policy can skip them.
+    ///
+    /// # Errors
+    /// Returns `ValidationFailed` if an overlay already added shadows one of
+    /// `rules` (see `validate_overlay`).
+    pub fn with_platform_rules(mut self, rules: Vec<PolicyRule>) -> Result<Self> {
+        for overlay in self.overlays.values() {
+            validate_overlay(overlay, &rules)?;
+        }
+        self.platform_rules = rules;
+        Ok(self)
+    }
+
+    /// Evaluate `overlay` for `tenant_id` after the platform rules and
+    /// before the base rules.
+    ///
+    /// # Errors
+    /// Returns `ValidationFailed` if the overlay could widen access (see
+    /// `validate_overlay`).
+    pub fn with_tenant_overlay(
+        mut self,
+        tenant_id: TenantId,
+        overlay: PolicyDocument,
+    ) -> Result<Self> {
+        validate_overlay(&overlay, &self.platform_rules)?;
+        self.overlays.insert(tenant_id, overlay);
+        Ok(self)
+    }
+
+    /// Evaluate `tenant_id`'s requests against `document` instead of the
+    /// engine-wide rules.
+    ///
+    /// # Errors
+    /// Returns `ValidationFailed` if the document is invalid (see
+    /// `PolicyDocument::validate`).
+    pub fn with_tenant_policy(
+        mut self,
+        tenant_id: TenantId,
+        document: PolicyDocument,
+    ) -> Result<Self> {
+        document.validate()?;
+        self.tenant_rules.insert(tenant_id, document.rules);
+        Ok(self)
+    }
+
+    /// Build an engine from a policy directory (see `PolicyDirectory`).
+    ///
+    /// `default.<ext>` replaces the built-in rules; without it they stay.
+    ///
+    /// # Errors
+    /// Propagates `PolicyDirectory::load`, `with_tenant_policy`,
+    /// `with_platform_rules` and `with_tenant_overlay` errors.
+    pub fn from_policy_dir(dir: &Path) -> Result<Self> {
+        let loaded = PolicyDirectory::load(dir)?;
+        let base = loaded.default.map_or_else(default_rules, |d| d.rules);
+        let platform = loaded.platform.map(|d| d.rules).unwrap_or_default();
+        let engine = loaded
+            .tenants
+            .into_iter()
+            .try_fold(Self::with_rules(base), |engine, (tenant_id, document)| {
+                engine.with_tenant_policy(tenant_id, document)
+            })?
+            .with_platform_rules(platform)?;
+        loaded
+            .overlays
+            .into_iter()
+            .try_fold(engine, |engine, (tenant_id, overlay)| {
+                engine.with_tenant_overlay(tenant_id, overlay)
+            })
+    }
+
+    /// Rules evaluated for `tenant_id`, in order: platform, overlay, base.
+    pub fn rules_for<'a>(&'a self, tenant_id: &TenantId) -> impl Iterator<Item = &'a PolicyRule> {
+        let overlay = self
+            .overlays
+            .get(tenant_id)
+            .map_or(&[][..], |d| d.rules.as_slice());
+        let base = self.tenant_rules.get(tenant_id).unwrap_or(&self.rules);
+        self.platform_rules.iter().chain(overlay).chain(base)
     }
 
     /// Evaluate a policy context against all rules.
@@ -53,57 +152,36 @@ impl PolicyEngine {
 
     /// Return the decision without converting to an error.
     /// Use this when you need the decision for audit/logging purposes.
+    ///
+    /// `Constrain` rules matched before the deciding rule contribute their
+    /// constraints to an allow decision. A rule that hinges on a relative
+    /// path it cannot resolve denies the call (see `arguments`).
     pub fn decide(&self, ctx: &EvaluationContext) -> PolicyDecision {
-        for rule in &self.rules {
-            if let Some(decision) = self.evaluate_rule(rule, ctx) {
-                return decision;
-            }
-        }
-        // Fail-safe: deny if no rule matched
-        PolicyDecision::deny("default-deny", "no matching rule — default deny")
-    }
-
-    fn evaluate_rule(
-        &self,
-        rule: &PolicyRule,
-        ctx: &EvaluationContext,
-    ) -> Option<PolicyDecision> {
-        let matched = match &rule.condition {
-            RuleCondition::BudgetAbove { threshold } => {
-                ctx.subject.budget_remaining_fraction <= *threshold
-            }
-            RuleCondition::ToolAccessLevel { required } => {
-                let tool_access = self.extract_tool_access(ctx);
-                tool_access.map(|a| a >= *required).unwrap_or(false)
-            }
-            RuleCondition::AgentTierMinimum { minimum } => {
-                ctx.subject.agent_tier.0 <= *minimum
-            }
-            RuleCondition::RestrictedApproved => ctx.subject.restricted_approved,
-            RuleCondition::UserRoleMinimum { minimum } => ctx
-                .subject
-                .user_role
-                .as_ref()
-                .map(|r| r >= minimum)
-                .unwrap_or(false),
-            RuleCondition::AlwaysAllow => true,
-            RuleCondition::AlwaysDeny => true,
-        };
-
-        if !matched {
-            return None;
-        }
-
-        Some(match &rule.effect {
-            PolicyEffect::Allow => PolicyDecision::allow(rule.id),
-            PolicyEffect::Deny { reason } => PolicyDecision::deny(rule.id, *reason),
-        })
+        let mut constraints = PolicyConstraints::default();
+        self.rules_for(&ctx.tenant_id)
+            .find_map(|rule| rule.apply(ctx, &mut constraints))
+            .unwrap_or_else(default_deny)
     }
 
-    fn extract_tool_access(&self, ctx: &EvaluationContext) -> Option<ToolAccessLevel> {
-        match &ctx.resource {
-            PolicyResource::Tool { access_level, .. } => Some(*access_level),
-            _ => None,
+    /// Decide, recording each evaluated rule and its condition tree.
+    ///
+    /// Slower than `decide`; meant for debugging unexpected decisions.
+    pub fn explain(&self, ctx: &EvaluationContext) -> PolicyExplanation {
+        let mut constraints = PolicyConstraints::default();
+        let mut rules = Vec::new();
+        for rule in self.rules_for(&ctx.tenant_id) {
+            rules.push(RuleTrace {
+                rule_id: rule.id.clone(),
+                effect: rule.effect.clone(),
+                condition: rule.condition.trace(ctx),
+            });
+            if let Some(decision) = rule.apply(ctx, &mut constraints) {
+                return PolicyExplanation { decision, rules };
+            }
+        }
+        PolicyExplanation {
+            decision: default_deny(),
+            rules,
         }
     }
 
@@ -122,13 +200,20 @@ impl PolicyEngine {
     }
 }
 
+/// Fail-safe: deny if no rule matched.
+fn default_deny() -> PolicyDecision {
+    PolicyDecision::deny("default-deny", "no matching rule — default deny")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::condition::RuleCondition;
+    use crate::evaluation::EvaluationContext;
+    use crate::rules::{AgentTier, PolicyAction, PolicyEffect};
     use aether_core::ids::{AgentId, TaskId, TenantId, ToolId};
+    use aether_core::tool::ExecutionScope;
     use aether_core::tool::ToolAccessLevel;
-    use crate::evaluation::EvaluationContext;
-    use crate::rules::AgentTier;
 
     fn make_ctx(tool_access: ToolAccessLevel, tier: AgentTier, budget: f64) -> EvaluationContext {
         EvaluationContext::tool_execute(
@@ -148,7 +233,10 @@ mod tests {
         let engine = PolicyEngine::default();
         let ctx = make_ctx(ToolAccessLevel::Public, AgentTier::SENSOR, 1.0);
         let d = engine.decide(&ctx);
-        assert!(d.is_allowed(), "public tools should be allowed for all tiers");
+        assert!(
+            d.is_allowed(),
+            "public tools should be allowed for all tiers"
+        );
     }
 
     #[test]
@@ -167,6 +255,119 @@ mod tests {
         assert!(!d.is_allowed(), "RESTRICTED tools denied without approval");
     }
 
+    #[test]
+    fn test_restricted_tool_allowed_with_approval() {
+        let engine = PolicyEngine::default();
+        let mut ctx = make_ctx(ToolAccessLevel::Restricted, AgentTier::WORKER, 1.0);
+        ctx.subject.restricted_approved = true;
+        let d = engine.decide(&ctx);
+        assert!(d.is_allowed(), "approved agents may use RESTRICTED tools");
+        assert_eq!(d.matched_rule, "restricted-tool-approved-allow");
+    }
+
+    #[test]
+    fn test_approval_does_not_unlock_critical_tools() {
+        let engine = PolicyEngine::default();
+        let mut ctx = make_ctx(ToolAccessLevel::Critical, AgentTier::BOSS, 1.0);
+        ctx.subject.restricted_approved = true;
+        assert_eq!(
+            engine.decide(&ctx).matched_rule,
+            "critical-tool-human-approval"
+        );
+    }
+
+    #[test]
+    fn test_protected_tool_requires_tier_2() {
+        let engine = PolicyEngine::default();
+        let ok = make_ctx(ToolAccessLevel::Protected, AgentTier::SPECIALIST, 1.0);
+        assert!(engine.decide(&ok).is_allowed());
+        let low = make_ctx(ToolAccessLevel::Protected, AgentTier::WORKER, 1.0);
+        assert_eq!(engine.decide(&low).matched_rule, "protected-tool-tier-deny");
+    }
+
+    #[test]
+    fn test_non_tool_action_falls_through_to_default_deny() {
+        let engine = PolicyEngine::default();
+        let mut ctx = make_ctx(ToolAccessLevel::Public, AgentTier::BOSS, 1.0);
+        ctx.action = PolicyAction::WorkflowDelete;
+        assert_eq!(engine.decide(&ctx).matched_rule, "default-deny");
+    }
+
+    fn constrain(id: &str, constraints: PolicyConstraints) -> PolicyRule {
+        PolicyRule::new(
+            id,
+            "",
+            RuleCondition::AlwaysAllow,
+            PolicyEffect::Constrain { constraints },
+        )
+    }
+
+    #[test]
+    fn test_constrain_rules_merge_into_allow() {
+        let mut rules = vec![
+            constrain(
+                "sandbox-everything",
+                PolicyConstraints {
+                    execution_scope: Some(ExecutionScope::Sandbox),
+                    max_timeout_ms: Some(60_000),
+                    ..Default::default()
+                },
+            ),
+            constrain(
+                "short-timeout",
+                PolicyConstraints {
+                    max_timeout_ms: Some(30_000),
+                    ..Default::default()
+                },
+            ),
+        ];
+        rules.extend(default_rules());
+        let engine = PolicyEngine::with_rules(rules);
+
+        let allowed = engine.decide(&make_ctx(ToolAccessLevel::Public, AgentTier::WORKER, 1.0));
+        assert_eq!(allowed.matched_rule, "public-tool-allow-all");
+        assert_eq!(
+            allowed.constraints.execution_scope,
+            Some(ExecutionScope::Sandbox)
+        );
+        assert_eq!(allowed.constraints.max_timeout_ms, Some(30_000));
+
+        let denied = engine.decide(&make_ctx(ToolAccessLevel::Public, AgentTier::BOSS, 0.0));
+        assert!(!denied.is_allowed() && denied.constraints.is_empty());
+    }
+
+    #[test]
+    fn test_constrain_after_deciding_rule_is_ignored() {
+        let mut rules = default_rules();
+        rules.push(constrain(
+            "too-late",
+            PolicyConstraints {
+                redact_output: true,
+                ..Default::default()
+            },
+        ));
+        let engine = PolicyEngine::with_rules(rules);
+        let d = engine.decide(&make_ctx(ToolAccessLevel::Public, AgentTier::WORKER, 1.0));
+        assert!(d.is_allowed() && d.constraints.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_tenant_policy_rejected() {
+        let allow =
+            |id: &str| PolicyRule::new(id, "", RuleCondition::AlwaysAllow, PolicyEffect::Allow);
+        let duplicate = PolicyDocument {
+            description: String::new(),
+            rules: vec![allow("same"), allow("same")],
+        };
+        let err = PolicyEngine::default()
+            .with_tenant_policy(TenantId::new(), duplicate)
+            .err();
+        assert_eq!(
+            err.map(|e| e.code()),
+            Some(aether_core::error::ErrorCode::ValidationFailed)
+        );
+    }
+
     #[test]
     fn test_budget_exhausted_denies_all() {
         let engine = PolicyEngine::default();
@@ -3,12 +3,18 @@
 //! `EvaluationContext` is the full input to the policy engine.
 //! `PolicyDecision` is the output.
 
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use semver::Version;
 use serde::{Deserialize, Serialize};
 
-use aether_core::ids::{AgentId, TaskId, TenantId, ToolId};
+use aether_core::ids::{AgentId, ApprovalId, TaskId, TenantId, ToolId, UserId, WorkflowId};
+use aether_core::tenant::{TenantTier, UserRole};
 use aether_core::tool::ToolAccessLevel;
 
-use crate::rules::{AgentTier, PolicyAction, PolicySubject};
+use crate::constraints::PolicyConstraints;
+use crate::rules::{AgentTier, PolicyAction, PolicySubject, Principal};
 
 /// Full context passed to the policy engine for a single evaluation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,10 +23,22 @@ pub struct EvaluationContext {
     pub subject: PolicySubject,
     pub action: PolicyAction,
     pub resource: PolicyResource,
+    /// Instant the decision is made for; temporal conditions read this
+    /// rather than the wall clock. Defaults to now when absent.
+    #[serde(default = "Utc::now")]
+    pub evaluated_at: DateTime<Utc>,
+    /// Service tier of `tenant_id`; the most restricted tier when unknown.
+    #[serde(default)]
+    pub tenant_tier: TenantTier,
+    /// Request-context attributes (e.g. `ToolExecutionContext::metadata`),
+    /// read by conditions as `request.<key>`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, String>,
 }
 
 impl EvaluationContext {
     /// Shorthand constructor for tool execution checks.
+    #[allow(clippy::too_many_arguments)]
     pub fn tool_execute(
         tenant_id: TenantId,
         agent_id: AgentId,
@@ -34,10 +52,12 @@ impl EvaluationContext {
         Self {
             tenant_id,
             subject: PolicySubject {
+                principal: Principal::Agent,
                 agent_tier,
                 user_role: None,
                 budget_remaining_fraction,
                 restricted_approved,
+                spawn_depth: 0,
             },
             action: PolicyAction::ToolExecute,
             resource: PolicyResource::Tool {
@@ -45,8 +65,106 @@ impl EvaluationContext {
                 access_level: tool_access,
                 agent_id,
                 task_id,
+                name: String::new(),
+                version: None,
+                arguments: serde_json::Value::Null,
+            },
+            evaluated_at: Utc::now(),
+            tenant_tier: TenantTier::default(),
+            attributes: HashMap::new(),
+        }
+    }
+
+    /// Context for a human user acting with `role`.
+    pub fn for_user(
+        tenant_id: TenantId,
+        user_id: UserId,
+        role: UserRole,
+        action: PolicyAction,
+        resource: PolicyResource,
+    ) -> Self {
+        Self::for_principal(
+            tenant_id,
+            Principal::User { user_id },
+            role,
+            action,
+            resource,
+        )
+    }
+
+    /// Context for an API key issued with `role`.
+    pub fn for_api_key(
+        tenant_id: TenantId,
+        key_id: impl Into<String>,
+        role: UserRole,
+        action: PolicyAction,
+        resource: PolicyResource,
+    ) -> Self {
+        let principal = Principal::ApiKey {
+            key_id: key_id.into(),
+        };
+        Self::for_principal(tenant_id, principal, role, action, resource)
+    }
+
+    fn for_principal(
+        tenant_id: TenantId,
+        principal: Principal,
+        role: UserRole,
+        action: PolicyAction,
+        resource: PolicyResource,
+    ) -> Self {
+        Self {
+            tenant_id,
+            subject: PolicySubject {
+                principal,
+                agent_tier: AgentTier::SENSOR,
+                user_role: Some(role),
+                budget_remaining_fraction: 1.0,
+                restricted_approved: false,
+                spawn_depth: 0,
             },
+            action,
+            resource,
+            evaluated_at: Utc::now(),
+            tenant_tier: TenantTier::default(),
+            attributes: HashMap::new(),
+        }
+    }
+
+    /// Evaluate as of `evaluated_at` instead of now.
+    pub fn at(mut self, evaluated_at: DateTime<Utc>) -> Self {
+        self.evaluated_at = evaluated_at;
+        self
+    }
+
+    /// Evaluate for a tenant on `tier`.
+    pub fn with_tenant_tier(mut self, tier: TenantTier) -> Self {
+        self.tenant_tier = tier;
+        self
+    }
+
+    /// Attach request-context attributes.
+    pub fn with_attributes(mut self, attributes: HashMap<String, String>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Arguments of a tool call, for `argument` conditions; no effect on
+    /// other resources.
+    pub fn with_arguments(mut self, tool_arguments: serde_json::Value) -> Self {
+        if let PolicyResource::Tool { arguments, .. } = &mut self.resource {
+            *arguments = tool_arguments;
         }
+        self
+    }
+
+    /// Name and version of a tool resource; no effect on other resources.
+    pub fn with_tool_identity(mut self, tool_name: &str, tool_version: Option<Version>) -> Self {
+        if let PolicyResource::Tool { name, version, .. } = &mut self.resource {
+            *name = tool_name.to_owned();
+            *version = tool_version;
+        }
+        self
     }
 }
 
@@ -59,31 +177,120 @@ pub enum PolicyResource {
         access_level: ToolAccessLevel,
         agent_id: AgentId,
         task_id: TaskId,
+        /// Registered tool name; empty when not known.
+        #[serde(default)]
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version: Option<Version>,
+        /// The call's arguments (`ToolCall::arguments`); null when not known.
+        #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+        arguments: serde_json::Value,
     },
     Agent {
         agent_id: AgentId,
         tier: AgentTier,
     },
     Workflow {
-        workflow_id: aether_core::ids::WorkflowId,
+        workflow_id: WorkflowId,
     },
     Memory {
         scope: String,
     },
+    /// A cron schedule that runs a workflow.
+    Schedule {
+        workflow_id: WorkflowId,
+    },
+    /// A pending human approval request.
+    Approval {
+        approval_id: ApprovalId,
+    },
+    /// The tenant's own configuration.
+    Tenant,
+}
+
+/// Discriminant of `PolicyResource`, for conditions scoped to a resource kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceKind {
+    Tool,
+    Agent,
+    Workflow,
+    Memory,
+    Schedule,
+    Approval,
+    Tenant,
+}
+
+impl PolicyResource {
+    /// Which kind of resource this is.
+    pub fn kind(&self) -> ResourceKind {
+        match self {
+            Self::Tool { .. } => ResourceKind::Tool,
+            Self::Agent { .. } => ResourceKind::Agent,
+            Self::Workflow { .. } => ResourceKind::Workflow,
+            Self::Memory { .. } => ResourceKind::Memory,
+            Self::Schedule { .. } => ResourceKind::Schedule,
+            Self::Approval { .. } => ResourceKind::Approval,
+            Self::Tenant => ResourceKind::Tenant,
+        }
+    }
+
+    /// Id of a tool resource; `None` for other resources.
+    pub fn tool_id(&self) -> Option<ToolId> {
+        match self {
+            Self::Tool { tool_id, .. } => Some(*tool_id),
+            _ => None,
+        }
+    }
+
+    /// Tool, calling agent and task of a tool call; `None` for other resources.
+    pub fn tool_call(&self) -> Option<(ToolId, AgentId, TaskId)> {
+        match self {
+            Self::Tool {
+                tool_id,
+                agent_id,
+                task_id,
+                ..
+            } => Some((*tool_id, *agent_id, *task_id)),
+            _ => None,
+        }
+    }
+
+    /// Arguments of a tool call; `None` for other resources or when unknown.
+    pub fn arguments(&self) -> Option<&serde_json::Value> {
+        match self {
+            Self::Tool { arguments, .. } if !arguments.is_null() => Some(arguments),
+            _ => None,
+        }
+    }
+
+    /// Access level of a tool resource; `None` for other resources.
+    pub fn tool_access(&self) -> Option<ToolAccessLevel> {
+        match self {
+            Self::Tool { access_level, .. } => Some(*access_level),
+            _ => None,
+        }
+    }
 }
 
 /// Policy decision returned by the engine.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PolicyDecision {
     pub effect: DecisionEffect,
     pub matched_rule: String,
     pub reason: String,
+    /// Obligations on an allowed call (or on a held call once approved);
+    /// empty for denials.
+    #[serde(default)]
+    pub constraints: PolicyConstraints,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DecisionEffect {
     Allow,
     Deny,
+    /// Not allowed until a human approves (see `approval`).
+    PendingApproval,
 }
 
 impl PolicyDecision {
@@ -92,17 +299,37 @@ impl PolicyDecision {
             effect: DecisionEffect::Allow,
             matched_rule: rule_id.into(),
             reason: "allowed by policy".into(),
+            constraints: PolicyConstraints::default(),
         }
     }
 
+    /// Attach constraints to the decision.
+    pub fn with_constraints(mut self, constraints: PolicyConstraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
     pub fn deny(rule_id: impl Into<String>, reason: impl Into<String>) -> Self {
         Self {
             effect: DecisionEffect::Deny,
             matched_rule: rule_id.into(),
             reason: reason.into(),
+            constraints: PolicyConstraints::default(),
         }
     }
 
+    /// Held for human approval; `matched_rule` names the rule that asked.
+    pub fn pending(rule_id: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            effect: DecisionEffect::PendingApproval,
+            ..Self::deny(rule_id, reason)
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.effect == DecisionEffect::PendingApproval
+    }
+
     pub fn is_allowed(&self) -> bool {
         self.effect == DecisionEffect::Allow
     }
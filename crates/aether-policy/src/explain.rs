@@ -0,0 +1,285 @@
+//! Decision traces for debugging policy (PRD §11).
+//!
+//! `PolicyEngine::explain` records every rule it evaluated, in order, with a
+//! condition tree showing what each leaf compared (actual value vs. the
+//! rule's threshold) and whether it matched. Evaluation stops at the
+//! deciding rule, exactly as `decide` does, so the trace ends with either
+//! that rule or the fail-safe default deny.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::condition::RuleCondition;
+use crate::evaluation::{EvaluationContext, PolicyDecision};
+use crate::rules::PolicyEffect;
+
+/// Evaluation of one condition node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionTrace {
+    /// Condition type, as written in policy documents (`budget_above`, `all`, …).
+    pub condition: String,
+    pub matched: bool,
+    /// What was compared; empty for combinators.
+    pub detail: String,
+    /// Sub-condition traces for `all`, `any` and `not`, all evaluated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ConditionTrace>,
+}
+
+/// Evaluation of one rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleTrace {
+    pub rule_id: String,
+    pub effect: PolicyEffect,
+    pub condition: ConditionTrace,
+}
+
+impl RuleTrace {
+    pub fn matched(&self) -> bool {
+        self.condition.matched
+    }
+}
+
+/// The decision plus every rule evaluated on the way to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyExplanation {
+    pub decision: PolicyDecision,
+    pub rules: Vec<RuleTrace>,
+}
+
+impl PolicyExplanation {
+    /// Trace of the rule that decided; `None` for the default deny.
+    pub fn deciding_rule(&self) -> Option<&RuleTrace> {
+        self.rules
+            .last()
+            .filter(|r| r.matched() && r.rule_id == self.decision.matched_rule)
+    }
+}
+
+impl fmt::Display for PolicyExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = &self.decision;
+        writeln!(f, "{:?} by {}: {}", d.effect, d.matched_rule, d.reason)?;
+        for rule in &self.rules {
+            let mark = if rule.matched() { "matched" } else { "skipped" };
+            writeln!(f, "  {} [{mark}]", rule.rule_id)?;
+            write_condition(f, &rule.condition, 2)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_condition(
+    f: &mut fmt::Formatter<'_>,
+    trace: &ConditionTrace,
+    depth: usize,
+) -> fmt::Result {
+    let mark = if trace.matched { '+' } else { '-' };
+    let indent = "  ".repeat(depth);
+    if trace.detail.is_empty() {
+        writeln!(f, "{indent}{mark} {}", trace.condition)?;
+    } else {
+        writeln!(f, "{indent}{mark} {}: {}", trace.condition, trace.detail)?;
+    }
+    for child in &trace.children {
+        write_condition(f, child, depth + 1)?;
+    }
+    Ok(())
+}
+
+impl RuleCondition {
+    /// Evaluate like `matches`, recording every node.
+    pub fn trace(&self, ctx: &EvaluationContext) -> ConditionTrace {
+        let children: Vec<ConditionTrace> = match self {
+            Self::All { conditions } | Self::Any { conditions } => {
+                conditions.iter().map(|c| c.trace(ctx)).collect()
+            }
+            Self::Not { condition } => vec![condition.trace(ctx)],
+            _ => Vec::new(),
+        };
+        let matched = match self {
+            Self::All { .. } => children.iter().all(|c| c.matched),
+            Self::Any { .. } => children.iter().any(|c| c.matched),
+            Self::Not { .. } => !children.iter().all(|c| c.matched),
+            leaf => leaf.matches(ctx),
+        };
+        ConditionTrace {
+            condition: self.name().into(),
+            matched,
+            detail: self.detail(ctx),
+            children,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::AgentTierMinimum { .. } => "agent_tier_minimum",
+            Self::ToolAccessLevel { .. } => "tool_access_level",
+            Self::ToolAccessIs { .. } => "tool_access_is",
+            Self::BudgetAbove { .. } => "budget_above",
+            Self::UserRoleMinimum { .. } => "user_role_minimum",
+            Self::RestrictedApproved => "restricted_approved",
+            Self::ActionIn { .. } => "action_in",
+            Self::ResourceKind { .. } => "resource_kind",
+            Self::ToolIdIn { .. } => "tool_id_in",
+            Self::SpawnDepthAtLeast { .. } => "spawn_depth_at_least",
+            Self::Attribute { .. } => "attribute",
+            Self::ToolName { .. } => "tool_name",
+            Self::ToolVersion { .. } => "tool_version",
+            Self::TenantTierIn { .. } => "tenant_tier_in",
+            Self::Argument { .. } => "argument",
+            Self::PrincipalIn { .. } => "principal_in",
+            Self::TimeOfDay(_) => "time_of_day",
+            Self::DaysOfWeek(_) => "days_of_week",
+            Self::MaintenanceWindow(_) => "maintenance_window",
+            Self::ValidDuring(_) => "valid_during",
+            Self::All { .. } => "all",
+            Self::Any { .. } => "any",
+            Self::Not { .. } => "not",
+            Self::AlwaysAllow => "always_allow",
+            Self::AlwaysDeny => "always_deny",
+        }
+    }
+
+    fn detail(&self, ctx: &EvaluationContext) -> String {
+        let subject = &ctx.subject;
+        let access = ctx.resource.tool_access();
+        let at = ctx.evaluated_at;
+        match self {
+            Self::AgentTierMinimum { minimum } => {
+                format!("agent tier {} vs minimum {minimum}", subject.agent_tier.0)
+            }
+            Self::ToolAccessLevel { required } => {
+                format!("tool access {access:?} vs ≥ {required:?}")
+            }
+            Self::ToolAccessIs { level } => format!("tool access {access:?} vs {level:?}"),
+            Self::BudgetAbove { threshold } => format!(
+                "budget remaining {} vs ≤ {threshold}",
+                subject.budget_remaining_fraction
+            ),
+            Self::UserRoleMinimum { minimum } => {
+                format!("user role {:?} vs minimum {minimum:?}", subject.user_role)
+            }
+            Self::RestrictedApproved => {
+                format!("restricted_approved = {}", subject.restricted_approved)
+            }
+            Self::ActionIn { actions } => format!("action {:?} vs {actions:?}", ctx.action),
+            Self::ResourceKind { kind } => {
+                format!("resource {:?} vs {kind:?}", ctx.resource.kind())
+            }
+            Self::ToolIdIn { tool_ids } => {
+                format!("tool {:?} vs {tool_ids:?}", ctx.resource.tool_id())
+            }
+            Self::SpawnDepthAtLeast { depth } => {
+                format!("spawn depth {} vs ≥ {depth}", subject.spawn_depth)
+            }
+            Self::Attribute { attribute, matches } => {
+                format!(
+                    "{attribute} = {:?} vs {matches:?}",
+                    ctx.attribute(attribute)
+                )
+            }
+            Self::ToolName { pattern } => {
+                format!(
+                    "tool name {:?} vs {pattern}",
+                    ctx.attribute("resource.tool_name")
+                )
+            }
+            Self::ToolVersion { requirement } => {
+                let version = ctx.attribute("resource.tool_version");
+                format!("tool version {version:?} vs {requirement}")
+            }
+            Self::TenantTierIn { tiers } => {
+                format!("tenant tier {:?} vs {tiers:?}", ctx.tenant_tier)
+            }
+            Self::Argument {
+                path,
+                matches,
+                quantifier,
+            } => {
+                let values = ctx.resource.arguments().map(|args| path.select(args));
+                format!("{path} = {values:?} vs {quantifier:?} {matches:?}")
+            }
+            Self::PrincipalIn { kinds } => {
+                format!("principal {:?} vs {kinds:?}", subject.principal.kind())
+            }
+            Self::TimeOfDay(w) => format!("{at} vs {}–{} {}", w.start, w.end, w.timezone),
+            Self::DaysOfWeek(d) => format!("{at} vs {:?} {}", d.days, d.timezone),
+            Self::MaintenanceWindow(w) => {
+                format!("{at} vs {:?} {}–{} {}", w.days, w.start, w.end, w.timezone)
+            }
+            Self::ValidDuring(p) => format!("{at} vs [{:?}, {:?})", p.not_before, p.not_after),
+            Self::All { .. } | Self::Any { .. } | Self::Not { .. } => String::new(),
+            Self::AlwaysAllow | Self::AlwaysDeny => "always".into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aether_core::tool::ToolAccessLevel;
+
+    use crate::engine::PolicyEngine;
+    use crate::rules::AgentTier;
+    use crate::testing::{tool_call, ToolCall};
+
+    #[test]
+    fn test_explain_stops_at_deciding_rule() {
+        let engine = PolicyEngine::default();
+        let ctx = tool_call(ToolAccessLevel::Restricted, AgentTier::BOSS);
+        let explained = engine.explain(&ctx);
+        let ids: Vec<&str> = explained.rules.iter().map(|r| r.rule_id.as_str()).collect();
+        assert_eq!(ids[0], "budget-exhausted-deny");
+        assert_eq!(
+            ids[ids.len() - 3..],
+            [
+                "critical-tool-human-approval",
+                "restricted-tool-approved-allow",
+                "restricted-tool-requires-approval",
+            ]
+        );
+        let deciding = explained.deciding_rule().unwrap();
+        assert_eq!(deciding.rule_id, explained.decision.matched_rule);
+        let approved_rule = &explained.rules[ids.len() - 2];
+        assert!(!approved_rule.matched());
+        // The failed leaf of the `all` shows the approval flag.
+        let approved = &approved_rule.condition.children[1];
+        assert_eq!(approved.detail, "restricted_approved = false");
+    }
+
+    #[test]
+    fn test_explain_reports_actual_vs_threshold() {
+        let engine = PolicyEngine::default();
+        let ctx = ToolCall::new(ToolAccessLevel::Public, AgentTier::BOSS)
+            .budget(0.0)
+            .build();
+        let explained = engine.explain(&ctx);
+        let budget = &explained.rules[0].condition;
+        assert!(budget.matched);
+        assert_eq!(budget.detail, "budget remaining 0 vs ≤ 0");
+        assert!(explained
+            .to_string()
+            .starts_with("Deny by budget-exhausted-deny"));
+    }
+
+    #[test]
+    fn test_default_deny_has_no_deciding_rule() {
+        let engine = PolicyEngine::with_rules(Vec::new());
+        let ctx = tool_call(ToolAccessLevel::Public, AgentTier::BOSS);
+        let explained = engine.explain(&ctx);
+        assert_eq!(explained.decision.matched_rule, "default-deny");
+        assert!(explained.rules.is_empty() && explained.deciding_rule().is_none());
+    }
+
+    #[test]
+    fn test_explain_agrees_with_decide() {
+        let engine = PolicyEngine::default();
+        for access in [ToolAccessLevel::Public, ToolAccessLevel::Protected] {
+            for tier in [AgentTier::BOSS, AgentTier::SENSOR] {
+                let c = ToolCall::new(access, tier).budget(0.5).build();
+                assert_eq!(engine.explain(&c).decision, engine.decide(&c));
+            }
+        }
+    }
+}
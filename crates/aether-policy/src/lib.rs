@@ -9,11 +9,54 @@
 //! // let ctx = EvaluationContext::tool_execute(tenant, agent, task, tier, tool, access, budget, approved);
 //! // let decision = engine.decide(&ctx);
 //! ```
+//!
+//! Rules can also be loaded from YAML/JSON/TOML policy documents, one set
+//! per tenant: `PolicyEngine::from_policy_dir(dir)`. Platform-mandatory
+//! rules and tighten-only tenant overlays layer on top (see `overlay`).
 
+pub mod approval;
+pub mod approval_gate;
+pub mod approval_review;
+pub mod approval_store;
+pub mod argument_path;
+pub mod arguments;
+pub mod attributes;
+pub mod condition;
+pub mod constraints;
+pub mod defaults;
+pub mod document;
+pub mod dry_run;
 pub mod engine;
 pub mod evaluation;
+pub mod explain;
+pub mod overlay;
 pub mod rules;
+pub mod temporal;
+#[cfg(test)]
+pub(crate) mod testing;
 
+pub use approval::{args_hash, ApprovalRequest, ApprovalStatus, Clock, Reviewer, SystemClock};
+pub use approval_gate::{ApprovalGate, GateDecision};
+pub use approval_store::{ApprovalStore, InMemoryApprovalStore};
+pub use argument_path::ArgumentPath;
+pub use arguments::{ArgumentMatch, Quantifier};
+pub use attributes::{glob_match, AttributeMatch, Pattern};
+pub use condition::RuleCondition;
+pub use constraints::PolicyConstraints;
+pub use defaults::default_rules;
+pub use document::{
+    PolicyDirectory, PolicyDocument, PolicyFormat, DEFAULT_POLICY_STEM, OVERLAY_SUFFIX,
+    PLATFORM_POLICY_STEM,
+};
+pub use dry_run::{dry_run, dry_run_jsonl, DecisionFlip, DryRunReport, FlipCounts};
 pub use engine::PolicyEngine;
-pub use evaluation::{DecisionEffect, EvaluationContext, PolicyDecision, PolicyResource};
-pub use rules::{AgentTier, PolicyAction, PolicyEffect, PolicyRule, PolicySubject, default_rules};
+pub use evaluation::{
+    DecisionEffect, EvaluationContext, PolicyDecision, PolicyResource, ResourceKind,
+};
+pub use explain::{ConditionTrace, PolicyExplanation, RuleTrace};
+pub use overlay::validate_overlay;
+pub use rules::{
+    AgentTier, PolicyAction, PolicyEffect, PolicyRule, PolicySubject, Principal, PrincipalKind,
+    DEFAULT_APPROVAL_TTL_SECS,
+};
+pub use temporal::{DaySet, MaintenanceWindow, TimeWindow, ValidityPeriod};
@@ -0,0 +1,274 @@
+//! Layered tenant policy: platform rules, overlays, base rules (PRD §11).
+//!
+//! For each request the engine evaluates, in order:
+//!
+//! 1. platform-mandatory rules, which no tenant policy can bypass;
+//! 2. the tenant's overlay, if any;
+//! 3. the base rules — the tenant's own document or the engine-wide rules.
+//!
+//! An overlay may only tighten: its rules deny or constrain, never allow
+//! or hold for approval, and its constraints must be provably at least as
+//! strict as none (see `PolicyConstraints::loosening`), so any request it
+//! matches ends up no more permitted than the layers beneath would have
+//! left it. Enterprise
+//! tenants use overlays to forbid specific tools (`tool_id_in`) or cap
+//! sub-agent depth (`spawn_depth_at_least`).
+
+use std::collections::HashSet;
+
+use aether_core::error::{AetherError, Result};
+
+use crate::document::PolicyDocument;
+use crate::rules::{PolicyEffect, PolicyRule};
+
+/// Check that `overlay` can only tighten the policy beneath it.
+///
+/// # Errors
+/// Returns `ValidationFailed` if the document is itself invalid, if a rule
+/// allows or requires approval, if its constraints could loosen a call, or
+/// if a rule reuses a platform rule's id.
+pub fn validate_overlay(overlay: &PolicyDocument, platform: &[PolicyRule]) -> Result<()> {
+    overlay.validate()?;
+    let platform_ids: HashSet<&str> = platform.iter().map(|r| r.id.as_str()).collect();
+    for (i, rule) in overlay.rules.iter().enumerate() {
+        let invalid = |field: &str, reason: String| AetherError::ValidationFailed {
+            field: format!("rules[{i}].{field}"),
+            reason,
+        };
+        if !matches!(
+            rule.effect,
+            PolicyEffect::Deny { .. } | PolicyEffect::Constrain { .. }
+        ) {
+            let reason = format!("overlay rule '{}' may only deny or constrain", rule.id);
+            return Err(invalid("effect", reason));
+        }
+        if let PolicyEffect::Constrain { constraints } = &rule.effect {
+            if let Some(loosening) = constraints.loosening() {
+                let reason = format!("overlay rule '{}': {loosening}", rule.id);
+                return Err(invalid("effect.constraints", reason));
+            }
+        }
+        if platform_ids.contains(rule.id.as_str()) {
+            let reason = format!("overlay rule '{}' shadows a platform rule", rule.id);
+            return Err(invalid("id", reason));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aether_core::error::ErrorCode;
+    use aether_core::ids::{AgentId, TenantId, ToolId};
+    use aether_core::tenant::UserRole;
+    use aether_core::tool::{ExecutionScope, ToolAccessLevel};
+
+    use crate::condition::RuleCondition;
+    use crate::constraints::PolicyConstraints;
+    use crate::document::PolicyFormat;
+    use crate::engine::PolicyEngine;
+    use crate::evaluation::{EvaluationContext, PolicyResource};
+    use crate::rules::{AgentTier, PolicyAction};
+    use crate::testing::ToolCall;
+
+    fn call(tenant_id: TenantId, tool_id: ToolId) -> EvaluationContext {
+        ToolCall::new(ToolAccessLevel::Public, AgentTier::WORKER)
+            .tenant(tenant_id)
+            .tool(tool_id)
+            .build()
+    }
+
+    fn spawn(tenant_id: TenantId, spawn_depth: u8) -> EvaluationContext {
+        let mut ctx = call(tenant_id, ToolId::new());
+        ctx.action = PolicyAction::AgentSpawn;
+        ctx.resource = PolicyResource::Agent {
+            agent_id: AgentId::new(),
+            tier: AgentTier::WORKER,
+        };
+        ctx.subject.agent_tier = AgentTier::SPECIALIST;
+        ctx.subject.spawn_depth = spawn_depth;
+        ctx
+    }
+
+    fn overlay(rules: Vec<PolicyRule>) -> PolicyDocument {
+        PolicyDocument {
+            description: String::new(),
+            rules,
+        }
+    }
+
+    fn deny(id: &str, condition: RuleCondition) -> PolicyRule {
+        PolicyRule::new(id, "", condition, PolicyEffect::deny(id))
+    }
+
+    #[test]
+    fn test_overlay_denies_only_for_its_tenant() {
+        let (tenant, other, shell) = (TenantId::new(), TenantId::new(), ToolId::new());
+        let forbid = deny(
+            "no-shell",
+            RuleCondition::ToolIdIn {
+                tool_ids: vec![shell],
+            },
+        );
+        let depth = deny(
+            "max-depth-2",
+            RuleCondition::all([
+                RuleCondition::action(PolicyAction::AgentSpawn),
+                RuleCondition::SpawnDepthAtLeast { depth: 2 },
+            ]),
+        );
+        let engine = PolicyEngine::default()
+            .with_tenant_overlay(tenant, overlay(vec![forbid, depth]))
+            .unwrap();
+
+        assert_eq!(engine.decide(&call(tenant, shell)).matched_rule, "no-shell");
+        assert!(engine.decide(&call(tenant, ToolId::new())).is_allowed());
+        assert!(engine.decide(&call(other, shell)).is_allowed());
+        assert!(engine.decide(&spawn(tenant, 1)).is_allowed());
+        assert_eq!(engine.decide(&spawn(tenant, 2)).matched_rule, "max-depth-2");
+        assert!(engine.decide(&spawn(other, 2)).is_allowed());
+    }
+
+    fn constrain(id: &str, constraints: PolicyConstraints) -> PolicyRule {
+        let effect = PolicyEffect::Constrain { constraints };
+        PolicyRule::new(id, "", RuleCondition::AlwaysAllow, effect)
+    }
+
+    fn sandbox(id: &str) -> PolicyRule {
+        let constraints = PolicyConstraints {
+            execution_scope: Some(ExecutionScope::Sandbox),
+            ..Default::default()
+        };
+        constrain(id, constraints)
+    }
+
+    #[test]
+    fn test_overlay_constraints_reach_base_allow() {
+        let tenant = TenantId::new();
+        let engine = PolicyEngine::default()
+            .with_tenant_overlay(tenant, overlay(vec![sandbox("sandbox-all")]))
+            .unwrap();
+        let d = engine.decide(&call(tenant, ToolId::new()));
+        assert_eq!(d.matched_rule, "public-tool-allow-all");
+        assert_eq!(d.constraints.execution_scope, Some(ExecutionScope::Sandbox));
+    }
+
+    #[test]
+    fn test_widening_overlays_rejected() {
+        let platform = vec![deny("platform-freeze", RuleCondition::AlwaysDeny)];
+        let widening = [
+            PolicyRule::new(
+                "allow-all",
+                "",
+                RuleCondition::AlwaysAllow,
+                PolicyEffect::Allow,
+            ),
+            PolicyRule::new(
+                "approve-instead",
+                "",
+                RuleCondition::RestrictedApproved,
+                PolicyEffect::require_approval("held", UserRole::Viewer),
+            ),
+        ];
+        for rule in widening {
+            let err = validate_overlay(&overlay(vec![rule]), &platform).unwrap_err();
+            assert!(
+                matches!(err, AetherError::ValidationFailed { ref field, .. }
+                if field == "rules[0].effect")
+            );
+        }
+        // A shadowing overlay is caught whichever is added first.
+        let shadow = overlay(vec![deny(
+            "platform-freeze",
+            RuleCondition::RestrictedApproved,
+        )]);
+        let err = PolicyEngine::default()
+            .with_platform_rules(platform.clone())
+            .unwrap()
+            .with_tenant_overlay(TenantId::new(), shadow.clone())
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), ErrorCode::ValidationFailed);
+        let err = PolicyEngine::default()
+            .with_tenant_overlay(TenantId::new(), shadow)
+            .unwrap()
+            .with_platform_rules(platform)
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), ErrorCode::ValidationFailed);
+    }
+
+    const HOSTILE: &str = r#"
+rules:
+  - id: run-external
+    condition: { type: always_allow }
+    effect: { type: constrain, constraints: { execution_scope: EXTERNAL } }
+  - id: allow-all
+    condition: { type: always_allow }
+    effect: { type: allow }
+"#;
+
+    #[test]
+    fn test_platform_sandbox_survives_hostile_policy() {
+        let tenant = TenantId::new();
+        let hostile = PolicyDocument::parse(HOSTILE, PolicyFormat::Yaml).unwrap();
+        let nan_cap = PolicyConstraints {
+            max_spend_usd: Some(f64::NAN),
+            ..Default::default()
+        };
+        for document in [
+            hostile.clone(),
+            overlay(vec![constrain("nan-cap", nan_cap)]),
+        ] {
+            let err = validate_overlay(&document, &[]).unwrap_err();
+            assert!(
+                matches!(err, AetherError::ValidationFailed { ref field, .. }
+                if field == "rules[0].effect.constraints")
+            );
+        }
+        // A tenant's own policy is not an overlay, but still cannot lift
+        // the platform's sandbox.
+        let engine = PolicyEngine::default()
+            .with_platform_rules(vec![sandbox("platform-sandbox")])
+            .unwrap()
+            .with_tenant_policy(tenant, hostile)
+            .unwrap();
+        let d = engine.decide(&call(tenant, ToolId::new()));
+        assert_eq!(d.matched_rule, "allow-all");
+        assert_eq!(d.constraints.execution_scope, Some(ExecutionScope::Sandbox));
+    }
+
+    #[test]
+    fn test_platform_rules_precede_tenant_policy() {
+        let (tenant, shell) = (TenantId::new(), ToolId::new());
+        let permissive = overlay(vec![PolicyRule::new(
+            "allow-all",
+            "",
+            RuleCondition::AlwaysAllow,
+            PolicyEffect::Allow,
+        )]);
+        let engine = PolicyEngine::default()
+            .with_tenant_policy(tenant, permissive)
+            .unwrap()
+            .with_platform_rules(vec![deny(
+                "platform-no-shell",
+                RuleCondition::ToolIdIn {
+                    tool_ids: vec![shell],
+                },
+            )])
+            .unwrap();
+        assert_eq!(
+            engine.decide(&call(tenant, shell)).matched_rule,
+            "platform-no-shell"
+        );
+        assert_eq!(
+            engine.decide(&call(tenant, ToolId::new())).matched_rule,
+            "allow-all"
+        );
+        let first = engine.explain(&call(tenant, shell)).rules[0]
+            .rule_id
+            .clone();
+        assert_eq!(first, "platform-no-shell");
+    }
+}
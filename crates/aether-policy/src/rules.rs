@@ -7,8 +7,12 @@
 
 use serde::{Deserialize, Serialize};
 
+use aether_core::ids::UserId;
 use aether_core::tenant::UserRole;
-use aether_core::tool::ToolAccessLevel;
+
+use crate::condition::RuleCondition;
+use crate::constraints::PolicyConstraints;
+use crate::evaluation::{EvaluationContext, PolicyDecision};
 
 /// An agent's operational tier (maps to PRD §22 T1/T2/T3/T4).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -29,7 +33,7 @@ impl AgentTier {
 }
 
 /// Which action is being evaluated.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PolicyAction {
     ToolExecute,
@@ -44,15 +48,62 @@ pub enum PolicyAction {
     SystemConfig,
 }
 
+/// Who is acting: an agent, a human user, or an API key.
+///
+/// API keys act on behalf of the tenant with the role they were issued.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Principal {
+    #[default]
+    Agent,
+    User {
+        user_id: UserId,
+    },
+    ApiKey {
+        key_id: String,
+    },
+}
+
+/// Discriminant of `Principal`, for conditions on who is acting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrincipalKind {
+    Agent,
+    User,
+    ApiKey,
+}
+
+impl Principal {
+    pub fn kind(&self) -> PrincipalKind {
+        match self {
+            Self::Agent => PrincipalKind::Agent,
+            Self::User { .. } => PrincipalKind::User,
+            Self::ApiKey { .. } => PrincipalKind::ApiKey,
+        }
+    }
+}
+
 /// The subject making the request.
+///
+/// For users and API keys `agent_tier` is `AgentTier::SENSOR`, the lowest
+/// tier, so a tier check grants them only what it grants every agent
+/// (`AgentTierMinimum { minimum: 4 }` matches them). Their `user_role`
+/// applies instead; rules granting by role should also require a human
+/// principal, since `principal` defaults to `Agent` and an agent subject
+/// may still carry a role.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicySubject {
+    #[serde(default)]
+    pub principal: Principal,
     pub agent_tier: AgentTier,
     pub user_role: Option<UserRole>,
     /// Budget remaining as a fraction [0.0, 1.0].
     pub budget_remaining_fraction: f64,
     /// Whether this agent has been explicitly approved for RESTRICTED tools.
     pub restricted_approved: bool,
+    /// Sub-agent depth of the acting agent (0 for root agents and humans).
+    #[serde(default)]
+    pub spawn_depth: u8,
 }
 
 /// A single policy rule.
@@ -60,83 +111,121 @@ pub struct PolicySubject {
 /// Rules are evaluated in order; first match wins.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyRule {
-    pub id: &'static str,
-    pub description: &'static str,
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
     pub condition: RuleCondition,
     pub effect: PolicyEffect,
 }
 
-/// The logical condition that must be true for a rule to fire.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-pub enum RuleCondition {
-    /// Requires the subject's agent tier to meet a minimum.
-    AgentTierMinimum { minimum: u8 },
-    /// Requires a specific tool access level to match the subject's clearance.
-    ToolAccessLevel { required: ToolAccessLevel },
-    /// Requires the budget remaining fraction to be above a threshold.
-    BudgetAbove { threshold: f64 },
-    /// User must hold at least this role.
-    UserRoleMinimum { minimum: UserRole },
-    /// Agent must be explicitly approved for restricted operations.
-    RestrictedApproved,
-    /// Always-true sentinel for default rules.
-    AlwaysAllow,
-    /// Always-false sentinel for deny-by-default.
-    AlwaysDeny,
+impl PolicyRule {
+    pub fn new(
+        id: impl Into<String>,
+        description: impl Into<String>,
+        condition: RuleCondition,
+        effect: PolicyEffect,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            description: description.into(),
+            condition,
+            effect,
+        }
+    }
+
+    /// Apply the rule to `ctx`: its decision, or `None` if it does not
+    /// match or only merges into `constraints`. A rule hinging on a
+    /// relative path it cannot resolve denies (see `arguments`).
+    pub(crate) fn apply(
+        &self,
+        ctx: &EvaluationContext,
+        constraints: &mut PolicyConstraints,
+    ) -> Option<PolicyDecision> {
+        if self.condition.is_unresolved(ctx) {
+            let reason = "relative path argument cannot be resolved without an absolute cwd";
+            return Some(PolicyDecision::deny(&self.id, reason));
+        }
+        if !self.condition.matches(ctx) {
+            return None;
+        }
+        let id = &self.id;
+        match &self.effect {
+            PolicyEffect::Allow => {
+                Some(PolicyDecision::allow(id).with_constraints(std::mem::take(constraints)))
+            }
+            PolicyEffect::Deny { reason } => Some(PolicyDecision::deny(id, reason)),
+            PolicyEffect::RequireApproval { reason, .. } => Some(
+                PolicyDecision::pending(id, reason).with_constraints(std::mem::take(constraints)),
+            ),
+            PolicyEffect::Constrain { constraints: c } => {
+                constraints.merge(c);
+                None
+            }
+        }
+    }
 }
 
-/// Whether the rule permits or denies the action.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Whether the rule permits or denies the action, or only constrains it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum PolicyEffect {
     Allow,
-    Deny { reason: &'static str },
+    Deny {
+        reason: String,
+    },
+    /// Add constraints to the eventual allow decision and keep evaluating.
+    Constrain {
+        constraints: PolicyConstraints,
+    },
+    /// Hold the call until a user with at least `approver_role` approves it
+    /// (see `approval`). The approval expires after `ttl_secs`.
+    RequireApproval {
+        reason: String,
+        approver_role: UserRole,
+        #[serde(default = "default_approval_ttl")]
+        ttl_secs: u64,
+    },
+}
+
+/// Lifetime of an approval request unless a rule sets `ttl_secs`.
+pub const DEFAULT_APPROVAL_TTL_SECS: u64 = 3_600;
+
+fn default_approval_ttl() -> u64 {
+    DEFAULT_APPROVAL_TTL_SECS
 }
 
-/// Built-in ruleset.
-pub fn default_rules() -> Vec<PolicyRule> {
-    vec![
-        PolicyRule {
-            id: "budget-exhausted-deny",
-            description: "Deny all tool calls when budget is fully exhausted",
-            condition: RuleCondition::BudgetAbove { threshold: 0.0 },
-            effect: PolicyEffect::Deny {
-                reason: "budget exhausted",
-            },
-        },
-        PolicyRule {
-            id: "critical-tool-deny-agent",
-            description: "CRITICAL tools require human approval — agents cannot self-approve",
-            condition: RuleCondition::ToolAccessLevel {
-                required: ToolAccessLevel::Critical,
-            },
-            effect: PolicyEffect::Deny {
-                reason: "CRITICAL tools require human-in-the-loop approval",
-            },
-        },
-        PolicyRule {
-            id: "restricted-tool-requires-approval",
-            description: "RESTRICTED tools require explicit pre-approval flag",
-            condition: RuleCondition::ToolAccessLevel {
-                required: ToolAccessLevel::Restricted,
-            },
-            effect: PolicyEffect::Deny {
-                reason: "RESTRICTED tool requires policy approval",
-            },
-        },
-        PolicyRule {
-            id: "protected-tool-tier-2-minimum",
-            description: "PROTECTED tools require Tier ≤ 2 agents",
-            condition: RuleCondition::AgentTierMinimum { minimum: 2 },
-            effect: PolicyEffect::Allow,
-        },
-        PolicyRule {
-            id: "public-tool-allow-all",
-            description: "PUBLIC tools are available to all agents",
-            condition: RuleCondition::AlwaysAllow,
-            effect: PolicyEffect::Allow,
-        },
-    ]
+impl PolicyEffect {
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self::Deny {
+            reason: reason.into(),
+        }
+    }
+
+    /// Require approval by `approver_role` within the default TTL.
+    pub fn require_approval(reason: impl Into<String>, approver_role: UserRole) -> Self {
+        Self::RequireApproval {
+            reason: reason.into(),
+            approver_role,
+            ttl_secs: DEFAULT_APPROVAL_TTL_SECS,
+        }
+    }
+
+    /// Approver role and TTL of a `RequireApproval` effect.
+    pub fn approval_requirement(&self) -> Option<(UserRole, u64)> {
+        match self {
+            Self::RequireApproval {
+                approver_role,
+                ttl_secs,
+                ..
+            } => Some((approver_role.clone(), *ttl_secs)),
+            _ => None,
+        }
+    }
+
+    /// Whether a match ends evaluation (allow, deny or require approval).
+    pub fn is_decisive(&self) -> bool {
+        !matches!(self, Self::Constrain { .. })
+    }
 }
 
 #[cfg(test)]
@@ -152,7 +241,7 @@ mod tests {
 
     #[test]
     fn test_default_rules_non_empty() {
-        let rules = default_rules();
+        let rules = crate::defaults::default_rules();
         assert!(!rules.is_empty());
     }
 }
@@ -0,0 +1,188 @@
+//! Time-based rule conditions (PRD §11).
+//!
+//! Every temporal condition is evaluated against
+//! `EvaluationContext::evaluated_at`, never the wall clock, so decisions are
+//! reproducible and tests are deterministic. Local times and weekdays are
+//! computed in each condition's IANA timezone (UTC by default), so windows
+//! follow daylight-saving changes.
+
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+fn utc() -> Tz {
+    Tz::UTC
+}
+
+/// Daily local-time window `[start, end)`.
+///
+/// A window with `start > end` wraps past midnight (e.g. 22:00–06:00);
+/// `start == end` is empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    #[serde(default = "utc")]
+    pub timezone: Tz,
+}
+
+impl TimeWindow {
+    /// Whether `at` falls inside the window.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        in_window(
+            at.with_timezone(&self.timezone).time(),
+            self.start,
+            self.end,
+        )
+    }
+}
+
+/// Days of the week, in a timezone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaySet {
+    pub days: Vec<Weekday>,
+    #[serde(default = "utc")]
+    pub timezone: Tz,
+}
+
+impl DaySet {
+    /// Whether `at` falls on one of the days.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.days
+            .contains(&at.with_timezone(&self.timezone).weekday())
+    }
+}
+
+/// Recurring window that opens at `start` on each of `days` and closes at
+/// `end`, which may be on the following day (Sat 22:00 → Sun 02:00).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// Days on which the window opens.
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    #[serde(default = "utc")]
+    pub timezone: Tz,
+}
+
+impl MaintenanceWindow {
+    /// Whether `at` falls inside an occurrence of the window.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&self.timezone);
+        let (time, day) = (local.time(), local.weekday());
+        if !in_window(time, self.start, self.end) {
+            return false;
+        }
+        // In a wrapping window, the early-morning part belongs to the
+        // occurrence that opened the day before.
+        let opened_on = if self.start > self.end && time < self.end {
+            day.pred()
+        } else {
+            day
+        };
+        self.days.contains(&opened_on)
+    }
+}
+
+/// Absolute validity period `[not_before, not_after)`; open-ended when a
+/// bound is absent.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ValidityPeriod {
+    #[serde(default)]
+    pub not_before: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub not_after: Option<DateTime<Utc>>,
+}
+
+impl ValidityPeriod {
+    /// Whether `at` is inside the period.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.not_before.is_none_or(|from| at >= from) && self.not_after.is_none_or(|to| at < to)
+    }
+}
+
+fn in_window(time: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn t(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    /// 2026-03-07 is a Saturday.
+    fn utc_at(day: u32, h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_time_window_wraps_midnight() {
+        let night = TimeWindow {
+            start: t(22, 0),
+            end: t(6, 0),
+            timezone: Tz::UTC,
+        };
+        assert!(night.contains(utc_at(7, 23, 0)));
+        assert!(night.contains(utc_at(8, 5, 59)));
+        assert!(!night.contains(utc_at(8, 6, 0)));
+        assert!(!night.contains(utc_at(8, 12, 0)));
+    }
+
+    #[test]
+    fn test_window_uses_local_time() {
+        let business = TimeWindow {
+            start: t(9, 0),
+            end: t(17, 0),
+            timezone: Tz::America__New_York,
+        };
+        // 14:00 UTC = 09:00 EST.
+        assert!(business.contains(utc_at(6, 14, 0)));
+        assert!(!business.contains(utc_at(6, 13, 59)));
+        let weekend = DaySet {
+            days: vec![Weekday::Sat, Weekday::Sun],
+            timezone: Tz::Asia__Tokyo,
+        };
+        // Friday 20:00 UTC is already Saturday in Tokyo.
+        assert!(weekend.contains(utc_at(6, 20, 0)));
+    }
+
+    #[test]
+    fn test_maintenance_window_spans_into_next_day() {
+        let window = MaintenanceWindow {
+            days: vec![Weekday::Sat],
+            start: t(22, 0),
+            end: t(2, 0),
+            timezone: Tz::UTC,
+        };
+        assert!(window.contains(utc_at(7, 23, 0)));
+        assert!(
+            window.contains(utc_at(8, 1, 0)),
+            "Sunday 01:00 belongs to Saturday's window"
+        );
+        assert!(
+            !window.contains(utc_at(7, 1, 0)),
+            "Saturday 01:00 belongs to Friday"
+        );
+        assert!(!window.contains(utc_at(8, 23, 0)));
+    }
+
+    #[test]
+    fn test_validity_period_bounds() {
+        let period = ValidityPeriod {
+            not_before: Some(utc_at(1, 0, 0)),
+            not_after: Some(utc_at(8, 0, 0)),
+        };
+        assert!(!period.contains(utc_at(1, 0, 0) - chrono::Duration::seconds(1)));
+        assert!(period.contains(utc_at(7, 23, 59)));
+        assert!(!period.contains(utc_at(8, 0, 0)));
+        assert!(ValidityPeriod::default().contains(utc_at(8, 0, 0)));
+    }
+}
@@ -0,0 +1,163 @@
+//! Fixtures shared by the crate's unit tests.
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+
+use aether_core::error::Result;
+use aether_core::ids::{AgentId, ApprovalId, TaskId, TenantId, ToolId, UserId};
+use aether_core::tenant::UserRole;
+use aether_core::tool::ToolAccessLevel;
+use aether_ledger::{InMemoryLedgerStorage, LedgerWriter};
+
+use crate::approval::{ApprovalRequest, Clock, Reviewer};
+use crate::approval_gate::ApprovalGate;
+use crate::approval_store::{ApprovalStore, InMemoryApprovalStore};
+use crate::engine::PolicyEngine;
+use crate::evaluation::EvaluationContext;
+use crate::rules::AgentTier;
+
+/// A tool-execution context with the defaults of `ToolCall`.
+pub(crate) fn tool_call(access: ToolAccessLevel, tier: AgentTier) -> EvaluationContext {
+    ToolCall::new(access, tier).build()
+}
+
+/// Builds a tool-execution `EvaluationContext` with fresh ids, a full
+/// budget and no restricted-tool approval unless overridden.
+pub(crate) struct ToolCall {
+    tenant_id: TenantId,
+    tool_id: ToolId,
+    access: ToolAccessLevel,
+    tier: AgentTier,
+    budget: f64,
+}
+
+impl ToolCall {
+    pub fn new(access: ToolAccessLevel, tier: AgentTier) -> Self {
+        Self {
+            tenant_id: TenantId::new(),
+            tool_id: ToolId::new(),
+            access,
+            tier,
+            budget: 1.0,
+        }
+    }
+
+    pub fn tenant(mut self, tenant_id: TenantId) -> Self {
+        self.tenant_id = tenant_id;
+        self
+    }
+
+    pub fn tool(mut self, tool_id: ToolId) -> Self {
+        self.tool_id = tool_id;
+        self
+    }
+
+    pub fn budget(mut self, fraction: f64) -> Self {
+        self.budget = fraction;
+        self
+    }
+
+    pub fn build(self) -> EvaluationContext {
+        EvaluationContext::tool_execute(
+            self.tenant_id,
+            AgentId::new(),
+            TaskId::new(),
+            self.tier,
+            self.tool_id,
+            self.access,
+            self.budget,
+            false,
+        )
+    }
+}
+
+/// A clock that only moves when a test advances it.
+pub(crate) struct ManualClock(Mutex<DateTime<Utc>>);
+
+impl ManualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self(Mutex::new(start))
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// An approval store that runs every change twice, first on a scratch
+/// copy, as a backend retrying on contention may.
+#[derive(Default)]
+pub(crate) struct RetryingStore(InMemoryApprovalStore);
+
+impl ApprovalStore for RetryingStore {
+    fn insert(&self, request: ApprovalRequest) -> Result<()> {
+        self.0.insert(request)
+    }
+
+    fn get(&self, tenant_id: &TenantId, id: &ApprovalId) -> Result<ApprovalRequest> {
+        self.0.get(tenant_id, id)
+    }
+
+    fn update(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApprovalId,
+        change: &mut dyn FnMut(&mut ApprovalRequest) -> Result<()>,
+    ) -> Result<ApprovalRequest> {
+        change(&mut self.0.get(tenant_id, id)?)?;
+        self.0.update(tenant_id, id, change)
+    }
+
+    fn pending(&self, tenant_id: &TenantId) -> Result<Vec<ApprovalRequest>> {
+        self.0.pending(tenant_id)
+    }
+}
+
+/// An approval gate over in-memory ledger storage and a `RetryingStore`.
+pub(crate) type TestGate = ApprovalGate<InMemoryLedgerStorage, RetryingStore>;
+
+/// A gate over the default rules and an unsigned in-memory ledger.
+pub(crate) fn gate() -> TestGate {
+    test_gate(
+        PolicyEngine::default(),
+        LedgerWriter::new(InMemoryLedgerStorage::new()),
+    )
+}
+
+pub(crate) fn test_gate(
+    engine: PolicyEngine,
+    ledger: LedgerWriter<InMemoryLedgerStorage>,
+) -> TestGate {
+    ApprovalGate::new(Arc::new(engine), RetryingStore::default(), Arc::new(ledger))
+}
+
+/// A Critical tool call by a Boss agent with empty arguments, which the
+/// default rules hold for Owner approval.
+pub(crate) fn critical_call(tenant_id: TenantId) -> EvaluationContext {
+    ToolCall::new(ToolAccessLevel::Critical, AgentTier::BOSS)
+        .tenant(tenant_id)
+        .build()
+        .with_arguments(Value::Object(Default::default()))
+}
+
+pub(crate) fn reviewer(role: UserRole) -> Reviewer {
+    Reviewer {
+        user_id: UserId::new(),
+        role,
+    }
+}
+
+/// File `ctx` with `gate`, which must hold it, and return the approval id.
+pub(crate) fn held(gate: &TestGate, ctx: &EvaluationContext) -> ApprovalId {
+    let outcome = gate.evaluate(ctx).unwrap();
+    assert!(outcome.decision.is_pending());
+    outcome.approval.unwrap().id
+}